@@ -0,0 +1,68 @@
+// Rough throughput numbers for the hot paths, std only: `cargo bench`.
+// Each benchmark runs for about BENCH_TIME and reports the mean time per
+// iteration. Pass a name to run only the benchmarks containing it.
+
+use std::env;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use cryptan::analysis::{scorer_by_name, SCORER_NAMES};
+use cryptan::classical::{
+    caesar, Alphabet, BruteForceOptions, CaesarCipher, ClassicalCipher, VigenereCipher, VigenereSolver,
+};
+use cryptan::encoding::{Codec, Morse};
+
+const BENCH_TIME: Duration = Duration::from_millis(500);
+
+const TEXT: &str = "The morning was cold and clear when the old man walked down to the harbour. He had lived in \
+                    the village for most of his life, and he knew every boat that came and went with the tide.";
+
+fn bench<T>(filter: Option<&str>, name: &str, mut f: impl FnMut() -> T) {
+    if filter.is_some_and(|filter| !name.contains(filter)) {
+        return;
+    }
+
+    // One untimed run to warm caches and lazily built tables.
+    black_box(f());
+    let started = Instant::now();
+    let mut iterations = 0u32;
+    while started.elapsed() < BENCH_TIME {
+        black_box(f());
+        iterations += 1;
+    }
+    let per_iteration = started.elapsed() / iterations.max(1);
+    println!("{:<32} {:>12.3?}/iter  ({} iterations)", name, per_iteration, iterations);
+}
+
+fn main() {
+    // `cargo bench` passes `--bench`; anything else is a filter.
+    let filter = env::args().skip(1).find(|arg| !arg.starts_with('-'));
+    let filter = filter.as_deref();
+
+    let caesar = CaesarCipher::new(7);
+    let encrypted = caesar.encrypt(TEXT);
+    bench(filter, "caesar/encrypt", || caesar.encrypt(black_box(TEXT)));
+    bench(filter, "caesar/decrypt", || caesar.decrypt(black_box(&encrypted)));
+    let megabyte = TEXT.repeat((1 << 20) / TEXT.len());
+    bench(filter, "caesar/encrypt-1mb", || caesar.encrypt(black_box(&megabyte)));
+    bench(filter, "caesar/shift-bytes-1mb", || caesar::caesar_shift_bytes(black_box(megabyte.as_bytes()), 7));
+
+    for name in SCORER_NAMES {
+        let scorer = scorer_by_name(name).expect("listed scorer");
+        let options = BruteForceOptions::default();
+        bench(filter, &format!("caesar/brute/{}", name), || {
+            caesar::brute_force_alphabet(black_box(&encrypted), &Alphabet::latin(), scorer.as_ref(), &options)
+        });
+    }
+
+    let morse = Morse::new();
+    let code = morse.encode(TEXT);
+    bench(filter, "morse/encode", || morse.encode(black_box(TEXT)));
+    bench(filter, "morse/decode", || morse.decode(black_box(&code)));
+    bench(filter, "morse/round-trip", || morse.decode(&morse.encode(black_box(TEXT))));
+
+    let vigenere = VigenereCipher::try_new("HARBOUR").expect("valid keyword");
+    let encrypted = vigenere.encrypt(TEXT);
+    let solver = VigenereSolver::new();
+    bench(filter, "vigenere/solve", || solver.solve(black_box(&encrypted)));
+}
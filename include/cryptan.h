@@ -0,0 +1,35 @@
+/* C interface to cryptan, built with `cargo build --release --features ffi`.
+ *
+ * Strings are NUL-terminated UTF-8. Functions returning `char *` hand over a
+ * new string to be released with cryptan_string_free, or return NULL on
+ * failure; cryptan_last_error then says why. */
+
+#ifndef CRYPTAN_H
+#define CRYPTAN_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* `cipher` and `key` form one recipe step, e.g. ("caesar", "3") or
+ * ("vigenere", "LEMON"); pass NULL as `key` for keyless ciphers such as
+ * "atbash". Codec names are accepted too. */
+char *cryptan_encrypt(const char *cipher, const char *key, const char *text);
+char *cryptan_decrypt(const char *cipher, const char *key, const char *text);
+
+/* `codec` is a codec name such as "base64", "hex" or "morse". Decoding
+ * fails on input the codec can't read. */
+char *cryptan_encode(const char *codec, const char *text);
+char *cryptan_decode(const char *codec, const char *text);
+
+void cryptan_string_free(char *s);
+
+/* Owned by the library; valid until the next call on the same thread.
+ * NULL if the last call succeeded. */
+const char *cryptan_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,121 @@
+use crate::analysis::english_score;
+use crate::classical::{CaesarCipher, ClassicalCipher, VigenereCipher};
+use crate::result::{rank, DecodedResult};
+
+// A repeated key fragment needs at least this many letters confirming the
+// period before we call it a repeating word.
+const MIN_CONFIRMATIONS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CribMatch {
+    // Offset of the crib, counted in letters of the ciphertext.
+    pub position: usize,
+    pub key_fragment: String,
+    pub period: Option<usize>,
+    // The repeating key rotated so it lines up with the start of the text.
+    pub key: Option<String>,
+}
+
+fn letters(text: &str) -> Vec<u8> {
+    text.chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase() as u8 - b'A')
+        .collect()
+}
+
+fn smallest_period(fragment: &[u8]) -> Option<usize> {
+    (1..fragment.len())
+        .filter(|p| fragment.len() - p >= MIN_CONFIRMATIONS)
+        .find(|p| (0..fragment.len() - p).all(|i| fragment[i] == fragment[i + p]))
+}
+
+// Slides the crib over every offset of the ciphertext and derives the
+// Vigenère key letters it implies there. Offsets whose key fragment repeats
+// come first, shortest period first.
+pub fn vigenere_crib(ciphertext: &str, crib: &str) -> Vec<CribMatch> {
+    let cipher = letters(ciphertext);
+    let plain = letters(crib);
+
+    if plain.is_empty() || plain.len() > cipher.len() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<CribMatch> = (0..=cipher.len() - plain.len())
+        .map(|position| {
+            let fragment: Vec<u8> = plain
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (cipher[position + i] + 26 - p) % 26)
+                .collect();
+            let period = smallest_period(&fragment);
+            let key = period.map(|p| {
+                (0..p)
+                    .map(|r| (fragment[(r + p - position % p) % p] + b'A') as char)
+                    .collect()
+            });
+
+            CribMatch {
+                position,
+                key_fragment: fragment.iter().map(|k| (k + b'A') as char).collect(),
+                period,
+                key,
+            }
+        })
+        .collect();
+
+    matches.sort_by_key(|m| (m.period.is_none(), m.period, m.position));
+    matches
+}
+
+// Caesar shifts that turn the crib into ciphertext at some offset, i.e.
+// where every letter of the crib implies the same key. Needs at least two
+// letters of crib; each shift is listed once.
+pub fn caesar_crib(ciphertext: &str, crib: &str) -> Vec<i64> {
+    let cipher = letters(ciphertext);
+    let plain = letters(crib);
+
+    if plain.len() < 2 || plain.len() > cipher.len() {
+        return Vec::new();
+    }
+
+    let mut shifts: Vec<i64> = Vec::new();
+    for position in 0..=cipher.len() - plain.len() {
+        let shift = (cipher[position] + 26 - plain[0]) % 26;
+        let fits = plain.iter().enumerate().all(|(i, p)| (cipher[position + i] + 26 - p) % 26 == shift);
+        if fits && !shifts.contains(&(shift as i64)) {
+            shifts.push(shift as i64);
+        }
+    }
+    shifts
+}
+
+// Decrypts the whole text with every Caesar and Vigenère key the crib
+// points to, most English-looking first. Keys are given as recipe steps
+// (`caesar:3`, `vigenere:LEMON`).
+pub fn crib_attack(ciphertext: &str, crib: &str) -> Vec<DecodedResult> {
+    let mut results: Vec<DecodedResult> = caesar_crib(ciphertext, crib)
+        .into_iter()
+        .map(|shift| {
+            let text = CaesarCipher::new(shift).decrypt(ciphertext);
+            let score = english_score(&text);
+            DecodedResult::new(format!("caesar:{}", shift), text, score).with_scorer("english")
+        })
+        .collect();
+
+    let mut keys: Vec<String> = Vec::new();
+    for key in vigenere_crib(ciphertext, crib).into_iter().filter_map(|m| m.key) {
+        // Period 1 is a Caesar shift, already covered above.
+        if key.len() < 2 || keys.contains(&key) {
+            continue;
+        }
+        if let Some(cipher) = VigenereCipher::new(&key) {
+            let text = cipher.decrypt(ciphertext);
+            let score = english_score(&text);
+            results.push(DecodedResult::new(format!("vigenere:{}", key), text, score).with_scorer("english"));
+        }
+        keys.push(key);
+    }
+
+    rank(&mut results);
+    results
+}
@@ -0,0 +1,55 @@
+// Relative frequency of A-Z in typical English text.
+pub const ENGLISH_LETTER_FREQUENCIES: [f64; 26] = [
+    0.0817, 0.0149, 0.0278, 0.0425, 0.1270, 0.0223, 0.0202, 0.0609, 0.0697, 0.0015, 0.0077, 0.0403, 0.0241,
+    0.0675, 0.0751, 0.0193, 0.0010, 0.0599, 0.0633, 0.0906, 0.0276, 0.0098, 0.0236, 0.0015, 0.0197, 0.0007,
+];
+
+// Counts of A-Z in the text, case-insensitive; everything else is ignored.
+pub fn letter_counts(text: &str) -> [usize; 26] {
+    let mut counts = [0; 26];
+
+    for c in text.chars() {
+        if c.is_ascii_alphabetic() {
+            counts[(c.to_ascii_uppercase() as u8 - b'A') as usize] += 1;
+        }
+    }
+
+    counts
+}
+
+pub fn letter_frequencies(text: &str) -> Vec<(char, f64)> {
+    let counts = letter_counts(text);
+    let total: usize = counts.iter().sum();
+
+    counts
+        .iter()
+        .enumerate()
+        .map(|(i, count)| {
+            let frequency = if total == 0 { 0.0 } else { *count as f64 / total as f64 };
+            ((b'A' + i as u8) as char, frequency)
+        })
+        .collect()
+}
+
+// Higher for bytes that look like English text: each letter scores its
+// English frequency, spaces score like a common letter, and control or
+// non-ASCII bytes count against the text. Normalized by length.
+pub fn english_byte_score(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+
+    let total: f64 = data
+        .iter()
+        .map(|b| match b {
+            b'a'..=b'z' => ENGLISH_LETTER_FREQUENCIES[(b - b'a') as usize],
+            b'A'..=b'Z' => ENGLISH_LETTER_FREQUENCIES[(b - b'A') as usize] * 0.5,
+            b' ' => 0.13,
+            b'\n' | b'\r' | b'\t' => 0.0,
+            _ if b.is_ascii_punctuation() || b.is_ascii_digit() => 0.0,
+            _ => -0.5,
+        })
+        .sum();
+
+    total / data.len() as f64
+}
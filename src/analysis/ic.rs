@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use crate::analysis::letter_counts;
+use crate::error::{Error, Result};
+use crate::json::Json;
+
+// Chance that two letters drawn from English text match, and the same for
+// uniformly random letters (1/26).
+pub const ENGLISH_IC: f64 = 0.0667;
+pub const RANDOM_IC: f64 = 1.0 / 26.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyLength {
+    pub length: usize,
+    // Mean index of coincidence of the columns the text splits into at
+    // this period; near `ENGLISH_IC` when the length is right.
+    pub ic: f64,
+}
+
+impl From<&KeyLength> for Json {
+    fn from(key_length: &KeyLength) -> Self {
+        Json::object(vec![("length", key_length.length.into()), ("ic", key_length.ic.into())])
+    }
+}
+
+impl TryFrom<&Json> for KeyLength {
+    type Error = Error;
+
+    fn try_from(json: &Json) -> Result<Self> {
+        Ok(KeyLength { length: json.usize_field("length")?, ic: json.f64_field("ic")? })
+    }
+}
+
+// Probability that two letters picked at random from the text are equal.
+// Only A-Z count, case-insensitively; 0.0 for fewer than two letters.
+pub fn index_of_coincidence(text: &str) -> f64 {
+    ic_of_counts(&letter_counts(text))
+}
+
+fn ic_of_counts(counts: &[usize; 26]) -> f64 {
+    let total: usize = counts.iter().sum();
+    if total < 2 {
+        return 0.0;
+    }
+
+    let pairs: usize = counts.iter().map(|n| n * n.saturating_sub(1)).sum();
+    pairs as f64 / (total * (total - 1)) as f64
+}
+
+// Friedman's estimate of a polyalphabetic key's length from the text's
+// overall IC. Rough, but needs no guessing; None when the text is too short
+// or looks like plain English (IC at or above `ENGLISH_IC`).
+pub fn friedman_estimate(text: &str) -> Option<f64> {
+    let counts = letter_counts(text);
+    let n = counts.iter().sum::<usize>() as f64;
+    let ic = ic_of_counts(&counts);
+    if n < 2.0 || ic <= RANDOM_IC || ic >= ENGLISH_IC {
+        return None;
+    }
+
+    Some((ENGLISH_IC - RANDOM_IC) * n / ((n - 1.0) * ic - RANDOM_IC * n + ENGLISH_IC))
+}
+
+// Scores every period from 1 to `max_length` by splitting the letters into
+// that many columns and averaging their IC. Best first; multiples of the
+// real length score about as well, so ties go to the shorter one.
+pub fn key_lengths(text: &str, max_length: usize) -> Vec<KeyLength> {
+    let letters: Vec<char> = text.chars().filter(|c| c.is_ascii_alphabetic()).collect();
+    let max_length = max_length.min(letters.len() / 2).max(1);
+
+    let mut candidates: Vec<KeyLength> = (1..=max_length)
+        .map(|length| {
+            let total: f64 = (0..length)
+                .map(|column| {
+                    let column: String = letters.iter().skip(column).step_by(length).collect();
+                    index_of_coincidence(&column)
+                })
+                .sum();
+            KeyLength { length, ic: total / length as f64 }
+        })
+        .collect();
+
+    // Within 10% of the best counts as a tie.
+    let best = candidates.iter().map(|c| c.ic).fold(0.0, f64::max);
+    candidates.sort_by(|a, b| {
+        let a_close = a.ic >= best * 0.9;
+        let b_close = b.ic >= best * 0.9;
+        b_close.cmp(&a_close).then_with(|| {
+            if a_close {
+                a.length.cmp(&b.length)
+            } else {
+                b.ic.total_cmp(&a.ic)
+            }
+        })
+    });
+    candidates
+}
+
+// Kasiski examination: finds trigrams that repeat in the letters of the
+// text and counts how many of the distances between repeats each period
+// from 2 to `max_length` divides. Most votes first, shorter on ties.
+pub fn kasiski(text: &str, max_length: usize) -> Vec<(usize, usize)> {
+    let letters: Vec<u8> = text.bytes().filter(u8::is_ascii_alphabetic).map(|b| b.to_ascii_uppercase()).collect();
+    let mut last_seen: HashMap<&[u8], usize> = HashMap::new();
+    let mut votes = vec![0; max_length + 1];
+
+    for (i, trigram) in letters.windows(3).enumerate() {
+        if let Some(previous) = last_seen.insert(trigram, i) {
+            let distance = i - previous;
+            for (length, count) in votes.iter_mut().enumerate().skip(2) {
+                if distance.is_multiple_of(length) {
+                    *count += 1;
+                }
+            }
+        }
+    }
+
+    let mut ranked: Vec<(usize, usize)> =
+        votes.into_iter().enumerate().skip(2).filter(|(_, count)| *count > 0).collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classical::{ClassicalCipher, VigenereCipher};
+
+    const ENGLISH: &str = include_str!("data/english.txt");
+
+    fn lemon_cipher() -> String {
+        let plain: String = ENGLISH.chars().take(2000).collect();
+        VigenereCipher::new("LEMON").unwrap().encrypt(&plain)
+    }
+
+    #[test]
+    fn english_has_the_english_ic() {
+        assert_eq!(index_of_coincidence("AABB"), 1.0 / 3.0);
+        assert_eq!(index_of_coincidence("A"), 0.0);
+        assert!((index_of_coincidence(ENGLISH) - ENGLISH_IC).abs() < 0.004);
+        assert_eq!(friedman_estimate(ENGLISH), None);
+    }
+
+    #[test]
+    fn finds_the_length_of_lemon() {
+        let cipher = lemon_cipher();
+        assert_eq!(key_lengths(&cipher, 12)[0].length, 5);
+        assert_eq!(kasiski(&cipher, 12)[0].0, 5);
+        let estimate = friedman_estimate(&cipher).unwrap();
+        assert!((3.0..8.0).contains(&estimate), "{}", estimate);
+    }
+}
@@ -0,0 +1,268 @@
+use std::fmt;
+
+use crate::analysis::{chi_squared_english, english_score, index_of_coincidence, letter_counts, meaningful_ratio};
+use crate::classical::caesar;
+use crate::error::{Error, Result};
+use crate::json::Json;
+use crate::magic;
+
+// Below this many letters the letter statistics are too noisy to say
+// anything about the cipher.
+const MIN_LETTERS: usize = 20;
+
+// Mean of English IC and random IC; monoalphabetic ciphers keep the IC
+// of their plaintext, polyalphabetic ones pull it towards random.
+const MONOALPHABETIC_IC: f64 = 0.052;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CipherKind {
+    Plaintext,
+    Morse,
+    Binary,
+    Decimal,
+    Hex,
+    Base32,
+    Base64,
+    Base64Url,
+    Ogham,
+    Rot47,
+    Caesar,
+    // Caesar, affine, Atbash or a keyed substitution alphabet.
+    Monoalphabetic,
+    // Letters in English proportions, but out of order.
+    Transposition,
+    // Vigenère, Beaufort, autokey and friends.
+    Polyalphabetic,
+    Playfair,
+}
+
+impl CipherKind {
+    pub const ALL: &'static [CipherKind] = &[
+        CipherKind::Plaintext,
+        CipherKind::Morse,
+        CipherKind::Binary,
+        CipherKind::Decimal,
+        CipherKind::Hex,
+        CipherKind::Base32,
+        CipherKind::Base64,
+        CipherKind::Base64Url,
+        CipherKind::Ogham,
+        CipherKind::Rot47,
+        CipherKind::Caesar,
+        CipherKind::Monoalphabetic,
+        CipherKind::Transposition,
+        CipherKind::Polyalphabetic,
+        CipherKind::Playfair,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CipherKind::Plaintext => "plaintext",
+            CipherKind::Morse => "morse",
+            CipherKind::Binary => "binary",
+            CipherKind::Decimal => "decimal",
+            CipherKind::Hex => "hex",
+            CipherKind::Base32 => "base32",
+            CipherKind::Base64 => "base64",
+            CipherKind::Base64Url => "base64url",
+            CipherKind::Ogham => "ogham",
+            CipherKind::Rot47 => "rot47",
+            CipherKind::Caesar => "caesar",
+            CipherKind::Monoalphabetic => "monoalphabetic substitution",
+            CipherKind::Transposition => "transposition",
+            CipherKind::Polyalphabetic => "polyalphabetic",
+            CipherKind::Playfair => "playfair",
+        }
+    }
+}
+
+impl fmt::Display for CipherKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Identification {
+    pub kind: CipherKind,
+    // Rough likelihood from 0.0 to 1.0; only meaningful for ranking.
+    pub confidence: f64,
+}
+
+impl From<&Identification> for Json {
+    fn from(guess: &Identification) -> Self {
+        Json::object(vec![("cipher", guess.kind.name().into()), ("confidence", guess.confidence.into())])
+    }
+}
+
+impl TryFrom<&Json> for Identification {
+    type Error = Error;
+
+    fn try_from(json: &Json) -> Result<Self> {
+        let name = json.str_field("cipher")?;
+        let kind = CipherKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.name() == name)
+            .ok_or_else(|| Error::InvalidJson(format!("unknown cipher `{}`", name)))?;
+        Ok(Identification { kind, confidence: json.f64_field("confidence")? })
+    }
+}
+
+// Guesses what produced a ciphertext from its character set and letter
+// statistics, without trying to decrypt it beyond a Caesar brute force.
+pub struct CipherIdentifier {
+    min_confidence: f64,
+}
+
+impl CipherIdentifier {
+    pub fn new() -> Self {
+        Self { min_confidence: 0.1 }
+    }
+
+    // Guesses below this are left out.
+    pub fn with_min_confidence(mut self, min_confidence: f64) -> Self {
+        self.min_confidence = min_confidence;
+        self
+    }
+
+    // Most likely first.
+    pub fn identify(&self, text: &str) -> Vec<Identification> {
+        let mut guesses = Vec::new();
+
+        let text = text.trim();
+        let compact = text.chars().filter(|c| !c.is_whitespace()).count();
+
+        if magic::looks_like_ogham(text) {
+            guesses.push(guess(CipherKind::Ogham, 0.95));
+        }
+        if magic::looks_like_binary(text) {
+            guesses.push(guess(CipherKind::Binary, if compact.is_multiple_of(8) { 0.95 } else { 0.6 }));
+        }
+        if magic::looks_like_morse(text) {
+            guesses.push(guess(CipherKind::Morse, 0.9));
+        }
+        if magic::looks_like_decimal(text) && text.contains([' ', ',']) {
+            guesses.push(guess(CipherKind::Decimal, 0.8));
+        }
+        if magic::looks_like_hex(text) && !magic::looks_like_binary(text) {
+            // Hex-only letters are also plausible Base64; mixed case isn't hex.
+            let single_case = !(text.contains(char::is_lowercase) && text.contains(char::is_uppercase));
+            guesses.push(guess(CipherKind::Hex, if single_case { 0.85 } else { 0.2 }));
+        }
+        if magic::looks_like_base32(text) && !text.contains(char::is_lowercase) {
+            guesses.push(guess(CipherKind::Base32, if compact.is_multiple_of(8) { 0.75 } else { 0.4 }));
+        }
+        if magic::looks_like_base64url(text) {
+            guesses.push(guess(CipherKind::Base64Url, if compact.is_multiple_of(4) { 0.7 } else { 0.5 }));
+        } else if magic::looks_like_base64(text) {
+            let padded = text.ends_with('=');
+            guesses.push(guess(CipherKind::Base64, if padded || compact.is_multiple_of(4) { 0.7 } else { 0.4 }));
+        }
+
+        // Letters of a confidently spotted encoding say nothing about a cipher.
+        let encoded = guesses.iter().any(|g| g.confidence >= 0.7);
+        let letters = letter_counts(text).iter().sum::<usize>();
+        if !encoded && letters >= MIN_LETTERS {
+            self.letter_statistics(text, letters, &mut guesses);
+        } else if !encoded && meaningful_ratio(text) >= 0.5 {
+            guesses.push(guess(CipherKind::Plaintext, meaningful_ratio(text)));
+        }
+
+        guesses.retain(|g| g.confidence >= self.min_confidence);
+        guesses.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        guesses
+    }
+
+    fn letter_statistics(&self, text: &str, letters: usize, guesses: &mut Vec<Identification>) {
+        let words = meaningful_ratio(text);
+        if words >= 0.5 {
+            guesses.push(guess(CipherKind::Plaintext, words));
+            return;
+        }
+
+        if text.is_ascii() && text.chars().filter(|c| c.is_ascii_punctuation()).count() * 5 > text.len() {
+            guesses.push(guess(CipherKind::Rot47, 0.5));
+        }
+
+        let ic = index_of_coincidence(text);
+        // Per-letter chi-squared: well under 1 when the letters are in
+        // English proportions.
+        let frequency_distance = chi_squared_english(text) as f64 / letters as f64;
+
+        if ic >= MONOALPHABETIC_IC {
+            if frequency_distance < 0.5 {
+                guesses.push(guess(CipherKind::Transposition, 0.75));
+                return;
+            }
+
+            if looks_like_playfair(text) {
+                guesses.push(guess(CipherKind::Playfair, 0.45));
+            }
+            let best_shift = caesar::brute_force(text).first().map_or(0.0, |result| english_score(&result.text));
+            if best_shift >= 0.5 {
+                guesses.push(guess(CipherKind::Caesar, best_shift.min(0.9)));
+            }
+            guesses.push(guess(CipherKind::Monoalphabetic, 0.6));
+            return;
+        }
+
+        guesses.push(guess(CipherKind::Polyalphabetic, (0.5 + (MONOALPHABETIC_IC - ic) * 20.0).min(0.8)));
+        if looks_like_playfair(text) {
+            guesses.push(guess(CipherKind::Playfair, 0.55));
+        }
+    }
+}
+
+fn guess(kind: CipherKind, confidence: f64) -> Identification {
+    Identification { kind, confidence }
+}
+
+// An even number of letters, no J, and no pair made of a doubled letter.
+fn looks_like_playfair(text: &str) -> bool {
+    let upper: Vec<char> = text.chars().filter(|c| c.is_ascii_alphabetic()).map(|c| c.to_ascii_uppercase()).collect();
+    let has_double = upper.chunks(2).any(|pair| pair.len() == 2 && pair[0] == pair[1]);
+
+    upper.len().is_multiple_of(2) && !upper.contains(&'J') && !has_double
+}
+
+impl Default for CipherIdentifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classical::{CaesarCipher, ClassicalCipher};
+    use crate::encoding::{Base64, BinaryAscii, Codec, Hex, Morse};
+
+    const PLAIN: &str = "meet me by the old bridge at midnight and bring the letters";
+
+    fn top(text: &str) -> CipherKind {
+        CipherIdentifier::new().identify(text)[0].kind
+    }
+
+    #[test]
+    fn encodings_rank_their_own_codec_first() {
+        assert_eq!(top(&Morse::new().encode(PLAIN)), CipherKind::Morse);
+        assert_eq!(top(&Hex::new().encode(PLAIN)), CipherKind::Hex);
+        assert_eq!(top(&Base64::new().encode(PLAIN)), CipherKind::Base64);
+        assert_eq!(top(&BinaryAscii.encode(PLAIN)), CipherKind::Binary);
+    }
+
+    #[test]
+    fn letters_rank_by_their_statistics() {
+        assert_eq!(top(PLAIN), CipherKind::Plaintext);
+        assert_eq!(top(&CaesarCipher::new(11).encrypt(PLAIN)), CipherKind::Caesar);
+    }
+
+    #[test]
+    fn guesses_come_sorted_and_above_the_minimum() {
+        let guesses = CipherIdentifier::new().with_min_confidence(0.5).identify(&Hex::new().encode(PLAIN));
+        assert!(guesses.windows(2).all(|pair| pair[0].confidence >= pair[1].confidence));
+        assert!(guesses.iter().all(|g| g.confidence >= 0.5));
+        assert!(CipherIdentifier::new().with_min_confidence(1.0).identify(PLAIN).is_empty());
+    }
+}
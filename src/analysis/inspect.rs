@@ -0,0 +1,171 @@
+use std::fmt;
+
+use crate::analysis::{entropy, printable_ratio};
+use crate::json::Json;
+use crate::magic;
+
+const DUMP_WIDTH: usize = 16;
+
+// How many of the commonest bytes an inspection lists.
+const MOST_COMMON: usize = 5;
+
+// What a blob of bytes could be read as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataEncoding {
+    Hex,
+    Base64,
+    Ascii,
+    Utf8,
+    // Not text at all.
+    Binary,
+}
+
+impl DataEncoding {
+    // Every encoding that fits, most specific first: hex digits are also
+    // Base64 symbols, and both are ASCII, which is also UTF-8.
+    pub fn detect(data: &[u8]) -> Vec<DataEncoding> {
+        let Ok(text) = std::str::from_utf8(data) else {
+            return vec![DataEncoding::Binary];
+        };
+
+        let mut encodings = Vec::new();
+        if magic::looks_like_hex(text) {
+            encodings.push(DataEncoding::Hex);
+        }
+        if magic::looks_like_base64(text) || magic::looks_like_base64url(text) {
+            encodings.push(DataEncoding::Base64);
+        }
+        if text.is_ascii() {
+            encodings.push(DataEncoding::Ascii);
+        }
+        encodings.push(DataEncoding::Utf8);
+        encodings
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DataEncoding::Hex => "hex",
+            DataEncoding::Base64 => "base64",
+            DataEncoding::Ascii => "ascii",
+            DataEncoding::Utf8 => "utf-8",
+            DataEncoding::Binary => "binary",
+        }
+    }
+}
+
+impl fmt::Display for DataEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// Byte-level facts about some input, for working out what it is before
+// picking a decoder.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inspection {
+    pub length: usize,
+    pub distinct: usize,
+    // Bits per byte.
+    pub entropy: f64,
+    pub printable_ratio: f64,
+    // Control bytes other than tab, CR and LF.
+    pub control: usize,
+    pub non_ascii: usize,
+    // (byte, count), commonest first.
+    pub most_common: Vec<(u8, usize)>,
+    pub encodings: Vec<DataEncoding>,
+}
+
+impl Inspection {
+    pub fn new(data: &[u8]) -> Self {
+        let mut counts = [0usize; 256];
+        for b in data {
+            counts[*b as usize] += 1;
+        }
+        let mut most_common: Vec<(u8, usize)> =
+            counts.iter().enumerate().filter(|(_, n)| **n > 0).map(|(b, n)| (b as u8, *n)).collect();
+        let distinct = most_common.len();
+        most_common.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        most_common.truncate(MOST_COMMON);
+
+        Self {
+            length: data.len(),
+            distinct,
+            entropy: entropy(data),
+            printable_ratio: printable_ratio(data),
+            control: data.iter().filter(|b| b.is_ascii_control() && !matches!(b, b'\t' | b'\n' | b'\r')).count(),
+            non_ascii: data.iter().filter(|b| !b.is_ascii()).count(),
+            most_common,
+            encodings: DataEncoding::detect(data),
+        }
+    }
+}
+
+impl fmt::Display for Inspection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let encodings: Vec<&str> = self.encodings.iter().map(DataEncoding::name).collect();
+        writeln!(f, "ENCODING: {}", encodings.join(", "))?;
+        writeln!(f, "LENGTH: {} bytes, {} distinct", self.length, self.distinct)?;
+        writeln!(f, "ENTROPY: {:.2} bits per byte", self.entropy)?;
+        writeln!(f, "PRINTABLE: {:.1}%", self.printable_ratio * 100.0)?;
+        writeln!(f, "CONTROL: {}, NON-ASCII: {}", self.control, self.non_ascii)?;
+
+        let common: Vec<String> =
+            self.most_common.iter().map(|(b, n)| format!("{:02x} {} x{}", b, printable(*b), n)).collect();
+        write!(f, "COMMONEST: {}", common.join(", "))
+    }
+}
+
+impl From<&Inspection> for Json {
+    fn from(inspection: &Inspection) -> Self {
+        let common = inspection
+            .most_common
+            .iter()
+            .map(|(b, n)| Json::object(vec![("byte", (*b as usize).into()), ("count", (*n).into())]))
+            .collect();
+
+        Json::object(vec![
+            ("length", inspection.length.into()),
+            ("distinct", inspection.distinct.into()),
+            ("entropy", inspection.entropy.into()),
+            ("printable_ratio", inspection.printable_ratio.into()),
+            ("control", inspection.control.into()),
+            ("non_ascii", inspection.non_ascii.into()),
+            ("most_common", Json::Array(common)),
+            ("encodings", Json::Array(inspection.encodings.iter().map(|e| e.name().into()).collect())),
+        ])
+    }
+}
+
+// The byte as a character, or `.` for anything that wouldn't print.
+fn printable(b: u8) -> char {
+    if b.is_ascii_graphic() || b == b' ' {
+        b as char
+    } else {
+        '.'
+    }
+}
+
+// The classic `hexdump -C` layout: offset, sixteen bytes in hex, then the
+// same bytes as ASCII.
+pub fn hex_dump(data: &[u8]) -> String {
+    let mut dump = String::new();
+
+    for (line, chunk) in data.chunks(DUMP_WIDTH).enumerate() {
+        let mut hex = String::new();
+        for i in 0..DUMP_WIDTH {
+            match chunk.get(i) {
+                Some(b) => hex.push_str(&format!("{:02x} ", b)),
+                None => hex.push_str("   "),
+            }
+            if i == DUMP_WIDTH / 2 - 1 {
+                hex.push(' ');
+            }
+        }
+        let ascii: String = chunk.iter().map(|b| printable(*b)).collect();
+        dump.push_str(&format!("{:08x}  {} |{}|\n", line * DUMP_WIDTH, hex, ascii));
+    }
+
+    dump.push_str(&format!("{:08x}", data.len()));
+    dump
+}
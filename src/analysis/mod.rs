@@ -0,0 +1,28 @@
+pub mod crib;
+pub mod entropy;
+pub mod frequency;
+pub mod ic;
+pub mod identify;
+pub mod inspect;
+pub mod ngram;
+pub mod report;
+pub mod scoring;
+pub mod words;
+pub mod workbench;
+pub mod xor;
+
+pub use crib::{caesar_crib, crib_attack, vigenere_crib, CribMatch};
+pub use entropy::{entropy, printable_ratio};
+pub use frequency::{english_byte_score, letter_counts, letter_frequencies, ENGLISH_LETTER_FREQUENCIES};
+pub use ic::{friedman_estimate, index_of_coincidence, kasiski, key_lengths, KeyLength};
+pub use identify::{CipherIdentifier, CipherKind, Identification};
+pub use inspect::{hex_dump, DataEncoding, Inspection};
+pub use ngram::{LanguageModel, QuadgramModel};
+pub use report::{AnalysisReport, CharacterSet};
+pub use scoring::{
+    chi_squared_english, english_score, scorer_by_name, EnglishScorer, MultiLanguageScorer, NgramScorer,
+    PythonWordfreqScorer, Scorer, WordlistScorer, SCORER_NAMES,
+};
+pub use workbench::Workbench;
+pub use words::{detect_language, meaningful_ratio, user_data_dir, zipf_frequency, Language, UserWords, Wordlist};
+pub use xor::{break_repeating_xor, hamming_distance, xor_key_sizes, KeySize, RepeatingXor};
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const ENGLISH_QUADGRAMS: &str = include_str!("data/english_quadgrams.txt");
+
+// Scores candidate plaintexts on letter statistics, for solvers that rank
+// or hill-climb keys. Higher is more like the modelled language; scores are
+// only comparable between texts of equal length.
+pub trait LanguageModel {
+    fn score(&self, text: &str) -> f64;
+}
+
+// Log10 probabilities of letter quadgrams, read from a frequency table or
+// learned from a sample text. Quadgrams missing from either get `floor`.
+pub struct QuadgramModel {
+    log_probs: HashMap<[u8; 4], f64>,
+    floor: f64,
+}
+
+fn letters(text: &str) -> Vec<u8> {
+    text.bytes()
+        .filter(|b| b.is_ascii_alphabetic())
+        .map(|b| b.to_ascii_uppercase())
+        .collect()
+}
+
+impl QuadgramModel {
+    pub fn from_corpus(corpus: &str) -> Self {
+        let mut counts: HashMap<[u8; 4], usize> = HashMap::new();
+
+        for window in letters(corpus).windows(4) {
+            *counts.entry([window[0], window[1], window[2], window[3]]).or_insert(0) += 1;
+        }
+
+        let total = counts.values().sum::<usize>().max(1) as f64;
+        let log_probs = counts
+            .into_iter()
+            .map(|(quadgram, count)| (quadgram, (count as f64 / total).log10()))
+            .collect();
+
+        Self { log_probs, floor: (0.01 / total).log10() }
+    }
+
+    // A table of `QUADGRAM count` lines; `#` starts a comment line. Unlisted
+    // quadgrams get a tenth of the rarest listed one.
+    pub fn from_table(table: &str) -> Self {
+        let counts: Vec<([u8; 4], f64)> = table
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (quadgram, count) = line.split_once(' ')?;
+                let quadgram: [u8; 4] = letters(quadgram).try_into().ok()?;
+                Some((quadgram, count.trim().parse().ok()?))
+            })
+            .collect();
+
+        let total = counts.iter().map(|(_, count)| count).sum::<f64>().max(1.0);
+        let rarest = counts.iter().map(|(_, count)| *count).reduce(f64::min).unwrap_or(1.0);
+        let log_probs = counts
+            .into_iter()
+            .map(|(quadgram, count)| (quadgram, (count / total).log10()))
+            .collect();
+
+        Self { log_probs, floor: (rarest / 10.0 / total).log10() }
+    }
+
+    pub fn english() -> &'static QuadgramModel {
+        static ENGLISH: OnceLock<QuadgramModel> = OnceLock::new();
+        ENGLISH.get_or_init(|| QuadgramModel::from_table(ENGLISH_QUADGRAMS))
+    }
+}
+
+impl LanguageModel for QuadgramModel {
+    // Sum of quadgram log probabilities over the letters of the text.
+    fn score(&self, text: &str) -> f64 {
+        letters(text)
+            .windows(4)
+            .map(|w| *self.log_probs.get(&[w[0], w[1], w[2], w[3]]).unwrap_or(&self.floor))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_table_is_embedded() {
+        let model = QuadgramModel::english();
+        assert_eq!(model.log_probs.len(), 20000);
+        assert!(model.log_probs[b"TION"] > model.log_probs[b"QUIT"]);
+        assert!(model.floor < model.log_probs.values().cloned().fold(0.0, f64::min));
+    }
+
+    #[test]
+    fn english_outscores_its_own_shuffle() {
+        let model = QuadgramModel::english();
+        let plain = "attack the castle walls at dawn";
+        let shuffled = "tawna lsawl eltsac eht kcatta";
+        assert!(model.score(plain) > model.score(shuffled) + 10.0);
+    }
+}
@@ -0,0 +1,185 @@
+use std::fmt;
+
+use crate::analysis::{entropy, index_of_coincidence, letter_frequencies, CipherIdentifier, Identification};
+use crate::json::Json;
+
+const BOLD: &str = "\x1b[1m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+// Width of the longest bar in the letter chart.
+const CHART_WIDTH: f64 = 40.0;
+
+// The narrowest set of symbols the text is written in, ignoring
+// whitespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharacterSet {
+    Empty,
+    Binary,
+    Digits,
+    Hex,
+    // Dots, dashes and slashes.
+    Morse,
+    Letters,
+    Base64,
+    Ascii,
+    Unicode,
+}
+
+impl CharacterSet {
+    pub fn detect(text: &str) -> Self {
+        let symbols: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+        let all = |f: fn(&char) -> bool| symbols.iter().all(f);
+
+        if symbols.is_empty() {
+            CharacterSet::Empty
+        } else if all(|c| matches!(c, '0' | '1')) {
+            CharacterSet::Binary
+        } else if all(char::is_ascii_digit) {
+            CharacterSet::Digits
+        } else if all(char::is_ascii_hexdigit) {
+            CharacterSet::Hex
+        } else if all(|c| matches!(c, '.' | '-' | '/')) {
+            CharacterSet::Morse
+        } else if all(char::is_ascii_alphabetic) {
+            CharacterSet::Letters
+        } else if all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=' | '-' | '_')) {
+            CharacterSet::Base64
+        } else if all(char::is_ascii) {
+            CharacterSet::Ascii
+        } else {
+            CharacterSet::Unicode
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CharacterSet::Empty => "empty",
+            CharacterSet::Binary => "binary digits",
+            CharacterSet::Digits => "decimal digits",
+            CharacterSet::Hex => "hex digits",
+            CharacterSet::Morse => "dots and dashes",
+            CharacterSet::Letters => "letters",
+            CharacterSet::Base64 => "base64 alphabet",
+            CharacterSet::Ascii => "ASCII",
+            CharacterSet::Unicode => "Unicode",
+        }
+    }
+}
+
+impl fmt::Display for CharacterSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// Everything the analysis tools say about a text at once. `{}` prints it
+// plain and `{:#}` with terminal colours.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalysisReport {
+    pub characters: usize,
+    pub bytes: usize,
+    pub letters: usize,
+    pub digits: usize,
+    pub whitespace: usize,
+    // Punctuation, symbols and anything else.
+    pub other: usize,
+    pub words: usize,
+    pub character_set: CharacterSet,
+    // A-Z, case-insensitive; share of all the letters.
+    pub letter_frequencies: Vec<(char, f64)>,
+    pub index_of_coincidence: f64,
+    // Bits per byte.
+    pub entropy: f64,
+    pub guesses: Vec<Identification>,
+}
+
+impl AnalysisReport {
+    pub fn new(text: &str) -> Self {
+        let count = |f: fn(&char) -> bool| text.chars().filter(f).count();
+        let characters = text.chars().count();
+        let letters = count(|c| c.is_alphabetic());
+        let digits = count(|c| c.is_numeric());
+        let whitespace = count(|c| c.is_whitespace());
+
+        Self {
+            characters,
+            bytes: text.len(),
+            letters,
+            digits,
+            whitespace,
+            other: characters - letters - digits - whitespace,
+            words: text.split_whitespace().count(),
+            character_set: CharacterSet::detect(text),
+            letter_frequencies: letter_frequencies(text),
+            index_of_coincidence: index_of_coincidence(text),
+            entropy: entropy(text.as_bytes()),
+            guesses: CipherIdentifier::new().identify(text),
+        }
+    }
+}
+
+impl fmt::Display for AnalysisReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (bold, cyan, reset) = if f.alternate() { (BOLD, CYAN, RESET) } else { ("", "", "") };
+        let heading = |f: &mut fmt::Formatter, title: &str| writeln!(f, "{}{}{}", bold, title, reset);
+
+        heading(f, "LENGTH")?;
+        writeln!(f, "  {} characters, {} bytes, {} words", self.characters, self.bytes, self.words)?;
+        writeln!(
+            f,
+            "  {} letters, {} digits, {} whitespace, {} other",
+            self.letters, self.digits, self.whitespace, self.other
+        )?;
+        heading(f, "CHARACTER SET")?;
+        writeln!(f, "  {}", self.character_set)?;
+        heading(f, "STATISTICS")?;
+        writeln!(f, "  index of coincidence {:.4} (English 0.0667, random 0.0385)", self.index_of_coincidence)?;
+        writeln!(f, "  entropy {:.2} bits per byte", self.entropy)?;
+
+        let mut frequencies: Vec<&(char, f64)> = self.letter_frequencies.iter().filter(|(_, f)| *f > 0.0).collect();
+        if !frequencies.is_empty() {
+            heading(f, "LETTER FREQUENCIES")?;
+            frequencies.sort_by(|a, b| b.1.total_cmp(&a.1));
+            let top = frequencies[0].1;
+            for (letter, frequency) in frequencies {
+                let bar = "#".repeat((frequency / top * CHART_WIDTH).round() as usize);
+                writeln!(f, "  {} {:5.1}% {}{}{}", letter, frequency * 100.0, cyan, bar, reset)?;
+            }
+        }
+
+        heading(f, "GUESSES")?;
+        if self.guesses.is_empty() {
+            writeln!(f, "  no idea")?;
+        }
+        for guess in &self.guesses {
+            writeln!(f, "  {:.2}  {}", guess.confidence, guess.kind)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&AnalysisReport> for Json {
+    fn from(report: &AnalysisReport) -> Self {
+        let frequencies = report
+            .letter_frequencies
+            .iter()
+            .map(|(letter, frequency)| (letter.to_string(), Json::from(*frequency)))
+            .collect();
+
+        Json::object(vec![
+            ("characters", report.characters.into()),
+            ("bytes", report.bytes.into()),
+            ("letters", report.letters.into()),
+            ("digits", report.digits.into()),
+            ("whitespace", report.whitespace.into()),
+            ("other", report.other.into()),
+            ("words", report.words.into()),
+            ("character_set", report.character_set.name().into()),
+            ("letter_frequencies", Json::Object(frequencies)),
+            ("index_of_coincidence", report.index_of_coincidence.into()),
+            ("entropy", report.entropy.into()),
+            ("guesses", Json::Array(report.guesses.iter().map(Json::from).collect())),
+        ])
+    }
+}
@@ -0,0 +1,343 @@
+use std::ffi::OsStr;
+use std::io::{BufRead, BufReader, Write};
+use std::ops::Deref;
+use std::path::Path;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use crate::analysis::words::{detect_language, words, Language, Wordlist};
+use crate::analysis::{letter_counts, meaningful_ratio, LanguageModel, QuadgramModel, ENGLISH_LETTER_FREQUENCIES};
+use crate::error::{Error, Result};
+use crate::json::Json;
+
+pub const SCORER_NAMES: &[&str] = &["english", "wordlist", "ngram", "multilingual", "python"];
+
+// Rates how much a candidate plaintext reads like English, 0.0 to 1.0, so
+// brute forcers can rank keys. Unlike a `LanguageModel`, scores compare
+// across texts of any length.
+pub trait Scorer {
+    fn score(&self, text: &str) -> f32;
+
+    // Shown with results, so people know what the score measures.
+    fn name(&self) -> &str {
+        "custom"
+    }
+
+    // The language `text` read best in, for scorers that try several.
+    fn language(&self, _text: &str) -> Option<Language> {
+        None
+    }
+}
+
+// `english_score`: dictionary words when there are any, letter frequencies
+// otherwise. The default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnglishScorer;
+
+impl Scorer for EnglishScorer {
+    fn score(&self, text: &str) -> f32 {
+        english_score(text) as f32
+    }
+
+    fn name(&self) -> &str {
+        "english"
+    }
+}
+
+// Share of dictionary words, `Wordlist::meaningful_ratio`. Needs spaces.
+#[derive(Debug, Clone)]
+pub struct WordlistScorer {
+    wordlist: Words,
+}
+
+// The embedded lists live for the whole run; lists from files or callers
+// are shared.
+#[derive(Debug, Clone)]
+enum Words {
+    Embedded(&'static Wordlist),
+    Shared(Arc<Wordlist>),
+}
+
+impl Deref for Words {
+    type Target = Wordlist;
+
+    fn deref(&self) -> &Wordlist {
+        match self {
+            Words::Embedded(wordlist) => wordlist,
+            Words::Shared(wordlist) => wordlist,
+        }
+    }
+}
+
+impl WordlistScorer {
+    // English.
+    pub fn new() -> Self {
+        Self::for_language(Language::English)
+    }
+
+    pub fn for_language(language: Language) -> Self {
+        Self { wordlist: Words::Embedded(Wordlist::for_language(language)) }
+    }
+
+    // Uses `Wordlist::load_cached`, so scorers for the same file share one
+    // copy of it.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self { wordlist: Words::Shared(Wordlist::load_cached(path)?) })
+    }
+
+    pub fn with_wordlist(wordlist: Wordlist) -> Self {
+        Self { wordlist: Words::Shared(Arc::new(wordlist)) }
+    }
+}
+
+impl Default for WordlistScorer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scorer for WordlistScorer {
+    fn score(&self, text: &str) -> f32 {
+        self.wordlist.meaningful_ratio(text) as f32
+    }
+
+    fn name(&self) -> &str {
+        "wordlist"
+    }
+}
+
+// Quadgram fitness per letter. Works without spaces; texts under four
+// letters score 0.0.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NgramScorer;
+
+impl Scorer for NgramScorer {
+    fn score(&self, text: &str) -> f32 {
+        let letters = text.chars().filter(|c| c.is_ascii_alphabetic()).count();
+        if letters < 4 {
+            return 0.0;
+        }
+
+        // English sits around -3.5 per quadgram, random letters near -6.
+        let per_quadgram = QuadgramModel::english().score(text) / (letters - 3) as f64;
+        ((per_quadgram + 6.0) / 2.5).clamp(0.0, 1.0) as f32
+    }
+
+    fn name(&self) -> &str {
+        "ngram"
+    }
+}
+
+// The best `meaningful_ratio` among several languages' word lists, so
+// brute force works without knowing the plaintext's language. Needs spaces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiLanguageScorer {
+    languages: Vec<Language>,
+}
+
+impl MultiLanguageScorer {
+    pub fn new(languages: &[Language]) -> Self {
+        Self { languages: languages.to_vec() }
+    }
+
+    // Every language with an embedded word list.
+    pub fn all() -> Self {
+        Self::new(Language::ALL)
+    }
+
+    pub fn languages(&self) -> &[Language] {
+        &self.languages
+    }
+}
+
+impl Default for MultiLanguageScorer {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl Scorer for MultiLanguageScorer {
+    fn score(&self, text: &str) -> f32 {
+        detect_language(text, &self.languages).map_or(0.0, |(_, ratio)| ratio as f32)
+    }
+
+    fn name(&self) -> &str {
+        "multilingual"
+    }
+
+    fn language(&self, text: &str) -> Option<Language> {
+        detect_language(text, &self.languages).map(|(language, _)| language)
+    }
+}
+
+// Word frequencies from the Python `wordfreq` package, which knows far more
+// words than the embedded lists, with rare words scoring less than common
+// ones: each word's Zipf frequency over 6 (one use per thousand words),
+// capped at 1, averaged by letters. Needs spaces. Every text goes to one
+// `python3` process, started with the scorer and stopped when it's dropped.
+pub struct PythonWordfreqScorer {
+    process: Mutex<WordfreqProcess>,
+}
+
+struct WordfreqProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+// Reads one JSON string per line and answers each with its score.
+const WORDFREQ_SCRIPT: &str = r#"
+import json, sys
+try:
+    from wordfreq import tokenize, zipf_frequency
+except ImportError as e:
+    print("error:", e, flush=True)
+    sys.exit(1)
+print("ready", flush=True)
+for line in sys.stdin:
+    words = [w for w in tokenize(json.loads(line), "en") if any(c.isalpha() for c in w)]
+    letters = sum(len(w) for w in words)
+    total = sum(len(w) * min(zipf_frequency(w, "en") / 6, 1) for w in words)
+    print(total / letters if letters else 0.0, flush=True)
+"#;
+
+impl PythonWordfreqScorer {
+    // Runs `python3` from the PATH.
+    pub fn new() -> Result<Self> {
+        Self::with_interpreter("python3")
+    }
+
+    // Fails with `Error::Python` when the interpreter can't be started or
+    // `wordfreq` isn't installed for it.
+    pub fn with_interpreter<S: AsRef<OsStr>>(python: S) -> Result<Self> {
+        let python = python.as_ref();
+        let mut child = Command::new(python)
+            .args(["-c", WORDFREQ_SCRIPT])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| Error::Python(format!("cannot run {}: {}", python.to_string_lossy(), e)))?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        let mut process = WordfreqProcess { child, stdin, stdout };
+
+        match process.read_line()?.as_str() {
+            "ready" => Ok(Self { process: Mutex::new(process) }),
+            "" => Err(Error::Python(format!("{} exited before loading wordfreq", python.to_string_lossy()))),
+            message => Err(Error::Python(message.trim_start_matches("error: ").to_string())),
+        }
+    }
+}
+
+impl WordfreqProcess {
+    fn read_line(&mut self) -> Result<String> {
+        let mut line = String::new();
+        self.stdout.read_line(&mut line)?;
+        Ok(line.trim_end().to_string())
+    }
+
+    fn score(&mut self, text: &str) -> Result<f32> {
+        writeln!(self.stdin, "{}", Json::from(text))?;
+        let line = self.read_line()?;
+        line.parse().map_err(|_| Error::Python(format!("wordfreq answered `{}`", line)))
+    }
+}
+
+impl Drop for WordfreqProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Scorer for PythonWordfreqScorer {
+    // 0.0 if the Python process has gone away.
+    fn score(&self, text: &str) -> f32 {
+        let mut process = self.process.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        process.score(text).unwrap_or(0.0)
+    }
+
+    fn name(&self) -> &str {
+        "python"
+    }
+}
+
+// `python` fails with `Error::Python` when wordfreq isn't available.
+pub fn scorer_by_name(name: &str) -> Result<Box<dyn Scorer>> {
+    match name.to_ascii_lowercase().as_str() {
+        "english" => Ok(Box::new(EnglishScorer)),
+        "wordlist" | "words" => Ok(Box::new(WordlistScorer::new())),
+        "ngram" | "quadgram" => Ok(Box::new(NgramScorer)),
+        "multilingual" | "auto" => Ok(Box::new(MultiLanguageScorer::all())),
+        "python" | "wordfreq" => Ok(Box::new(PythonWordfreqScorer::new()?)),
+        _ => Err(Error::Unsupported(format!("no scorer `{}`, expected one of: {}", name, SCORER_NAMES.join(", ")))),
+    }
+}
+
+// Pearson's chi-squared distance between the text's A-Z counts and English
+// letter frequencies. Lower is more English-like; texts with no letters get
+// `f32::INFINITY`. Works on text without spaces, where word lookups can't.
+pub fn chi_squared_english(text: &str) -> f32 {
+    let counts = letter_counts(text);
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return f32::INFINITY;
+    }
+
+    counts
+        .iter()
+        .zip(ENGLISH_LETTER_FREQUENCIES)
+        .map(|(observed, frequency)| {
+            let expected = frequency * total as f64;
+            (*observed as f64 - expected).powi(2) / expected
+        })
+        .sum::<f64>() as f32
+}
+
+// How English the text looks, 0.0 to 1.0. Uses the share of dictionary
+// words when the text has several words, and letter frequencies otherwise.
+pub fn english_score(text: &str) -> f64 {
+    if words(text).nth(1).is_some() {
+        return meaningful_ratio(text);
+    }
+
+    let letters = letter_counts(text).iter().sum::<usize>();
+    if letters == 0 {
+        return 0.0;
+    }
+    // Per-letter chi-squared is well under 1 for English and several times
+    // that for shuffled or shifted text.
+    1.0 / (1.0 + chi_squared_english(text) as f64 / letters as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scorers_are_found_by_name() {
+        for name in SCORER_NAMES.iter().filter(|name| **name != "python") {
+            assert_eq!(scorer_by_name(name).unwrap().name(), *name);
+        }
+        assert!(matches!(scorer_by_name("klingon"), Err(Error::Unsupported(_))));
+    }
+
+    #[test]
+    fn python_scorer_needs_an_interpreter() {
+        let missing = PythonWordfreqScorer::with_interpreter("/nonexistent/python3");
+        assert!(matches!(missing, Err(Error::Python(message)) if message.contains("cannot run")));
+    }
+
+    // Only where wordfreq is installed.
+    #[test]
+    fn python_scorer_prefers_english() {
+        let Ok(scorer) = PythonWordfreqScorer::new() else {
+            return;
+        };
+        let plain = scorer.score("the quick brown fox jumps over the lazy dog");
+        let shifted = scorer.score("wkh txlfn eurzq ira mxpsv ryhu wkh odcb grj");
+        assert!(plain > 0.5 && shifted < 0.2, "{} vs {}", plain, shifted);
+        assert_eq!(scorer.score("12345"), 0.0);
+    }
+}
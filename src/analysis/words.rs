@@ -0,0 +1,380 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::error::{Error, Result};
+
+const ENGLISH_CORPUS: &str = include_str!("data/english.txt");
+const ENGLISH_FREQUENCY: &str = include_str!("data/english_frequency.txt");
+const SPANISH_FREQUENCY: &str = include_str!("data/spanish.txt");
+const FRENCH_FREQUENCY: &str = include_str!("data/french.txt");
+const GERMAN_FREQUENCY: &str = include_str!("data/german.txt");
+const VIETNAMESE_FREQUENCY: &str = include_str!("data/vietnamese.txt");
+
+// Zipf frequency (log10 of occurrences per billion words) of a language's
+// most common word; lower ranks follow Zipf's law from it.
+const TOP_ZIPF: f64 = 7.8;
+
+// Words that appear in the sample but not in the ranked list are taken to
+// be about this rare.
+const UNRANKED_ZIPF: f64 = 3.5;
+
+// Words from a user's list, which says nothing about how common they are,
+// all get full credit.
+const LISTED_ZIPF: f64 = 4.0;
+
+// The user data directory, once `Wordlist::with_user_words` has been
+// called; `None` inside when there's no such directory.
+static USER_WORDS: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+// Languages with an embedded word list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+    French,
+    German,
+    Vietnamese,
+}
+
+impl Language {
+    pub const ALL: &'static [Language] =
+        &[Language::English, Language::Spanish, Language::French, Language::German, Language::Vietnamese];
+
+    // ISO 639-1.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+            Language::French => "fr",
+            Language::German => "de",
+            Language::Vietnamese => "vi",
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "en" | "english" => Ok(Language::English),
+            "es" | "spanish" | "español" => Ok(Language::Spanish),
+            "fr" | "french" | "français" => Ok(Language::French),
+            "de" | "german" | "deutsch" => Ok(Language::German),
+            "vi" | "vietnamese" => Ok(Language::Vietnamese),
+            _ => Err(format!("unknown language `{}`, expected en, es, fr, de or vi", s)),
+        }
+    }
+}
+
+pub fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphabetic() && c != '\'')
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| !word.is_empty())
+}
+
+// Every word of the embedded English sample.
+pub fn english_words() -> &'static HashSet<String> {
+    static WORDS: OnceLock<HashSet<String>> = OnceLock::new();
+    WORDS.get_or_init(|| words(ENGLISH_CORPUS).collect())
+}
+
+// A language's vocabulary with how common each word is, for telling real
+// text from what a wrong key produces.
+#[derive(Debug, Clone, Default)]
+pub struct Wordlist {
+    frequencies: HashMap<String, f64>,
+}
+
+impl Wordlist {
+    // One word per line, most common first; `#` starts a comment line.
+    pub fn from_ranked(list: &str) -> Self {
+        let mut wordlist = Self::default();
+        wordlist.add_ranked(list);
+        wordlist
+    }
+
+    // Reads a word list from a file: the first word on each line, in any
+    // order.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let frequencies = text
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_whitespace().next())
+            .map(|word| (word.to_lowercase(), LISTED_ZIPF))
+            .collect();
+        Ok(Self { frequencies })
+    }
+
+    // Like `load`, but each file is read once per run and shared by every
+    // later call with the same path.
+    pub fn load_cached<P: AsRef<Path>>(path: P) -> Result<Arc<Wordlist>> {
+        static LOADED: OnceLock<Mutex<HashMap<PathBuf, Arc<Wordlist>>>> = OnceLock::new();
+        let path = fs::canonicalize(path)?;
+        let mut loaded = LOADED.get_or_init(Default::default).lock().unwrap();
+
+        if let Some(wordlist) = loaded.get(&path) {
+            return Ok(Arc::clone(wordlist));
+        }
+        let wordlist = Arc::new(Self::load(&path)?);
+        loaded.insert(path, Arc::clone(&wordlist));
+        Ok(wordlist)
+    }
+
+    // The ranked list plus every word of the embedded sample text.
+    pub fn english() -> &'static Wordlist {
+        Self::for_language(Language::English)
+    }
+
+    // Built on first use and kept for the rest of the run. Only the
+    // embedded lists, so scores are the same on every machine, unless the
+    // program has called `with_user_words`.
+    pub fn for_language(language: Language) -> &'static Wordlist {
+        // With and without the user's words, for each language.
+        const LISTS: usize = 2 * Language::ALL.len();
+        static BUILT: [OnceLock<Wordlist>; LISTS] = [const { OnceLock::new() }; LISTS];
+
+        let user_dir = USER_WORDS.get().and_then(Option::as_deref);
+        let index = Language::ALL.iter().position(|&l| l == language).expect("every language is in ALL");
+        BUILT[2 * index + user_dir.is_some() as usize].get_or_init(|| {
+            let mut wordlist = match language {
+                Language::English => {
+                    let mut wordlist = Wordlist {
+                        frequencies: english_words().iter().map(|word| (word.clone(), UNRANKED_ZIPF)).collect(),
+                    };
+                    wordlist.add_ranked(ENGLISH_FREQUENCY);
+                    wordlist
+                }
+                Language::Spanish => Wordlist::from_ranked(SPANISH_FREQUENCY),
+                Language::French => Wordlist::from_ranked(FRENCH_FREQUENCY),
+                Language::German => Wordlist::from_ranked(GERMAN_FREQUENCY),
+                Language::Vietnamese => Wordlist::from_ranked(VIETNAMESE_FREQUENCY),
+            };
+            if let Some(dir) = user_dir {
+                wordlist.add_user_words(language, dir);
+            }
+            wordlist
+        })
+    }
+
+    // From now on `english` and `for_language` also hold the words the user
+    // added (`UserWords`), so their names and jargon score as words. Off by
+    // default, so that library results don't depend on whose home
+    // directory they run in; for programs acting for that user, like the
+    // CLI.
+    pub fn with_user_words() {
+        USER_WORDS.get_or_init(user_data_dir);
+    }
+
+    // Like `with_user_words`, with the words kept under `dir` in place of
+    // `user_data_dir`. Only the first call of either counts.
+    pub fn with_user_words_in<P: AsRef<Path>>(dir: P) {
+        USER_WORDS.get_or_init(|| Some(dir.as_ref().to_path_buf()));
+    }
+
+    // Words from the `UserWords` under `dir` the list doesn't have yet. An
+    // unreadable file just adds nothing; `cryptan wordlist` reports it.
+    fn add_user_words(&mut self, language: Language, dir: &Path) {
+        if let Ok(user) = UserWords::open_in(dir.join("wordlists"), language) {
+            for word in user.words() {
+                self.frequencies.entry(word.to_string()).or_insert(LISTED_ZIPF);
+            }
+        }
+    }
+
+    fn add_ranked(&mut self, list: &str) {
+        let ranked = list.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'));
+        for (rank, word) in ranked.enumerate() {
+            self.frequencies.insert(word.to_lowercase(), TOP_ZIPF - ((rank + 1) as f64).log10());
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.frequencies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frequencies.is_empty()
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.frequencies.contains_key(&word.to_lowercase())
+    }
+
+    // How common a word is on the Zipf scale: 7 or more for the commonest
+    // words, around 3 for rare ones, 0.0 for words not in the list. Case
+    // is ignored.
+    pub fn zipf_frequency(&self, word: &str) -> f64 {
+        self.frequencies.get(&word.to_lowercase()).copied().unwrap_or(0.0)
+    }
+
+    // Share of the text, 0.0 to 1.0, made of words in the list. Words
+    // count by their length, so a few short words a cipher happens to
+    // produce ("a", "is") don't count for much, and rarer words count for
+    // less than common ones.
+    pub fn meaningful_ratio(&self, text: &str) -> f64 {
+        let (total, hits) = words(text).fold((0.0, 0.0), |(total, hits), word| {
+            let weight = word.chars().count() as f64;
+            // `words` has already lower-cased it.
+            let zipf = self.frequencies.get(&word).copied().unwrap_or(0.0);
+            let credit = ((zipf - 1.0) / 3.0).clamp(0.0, 1.0);
+            (total + weight, hits + weight * credit)
+        });
+
+        if total == 0.0 {
+            return 0.0;
+        }
+        hits / total
+    }
+}
+
+// Where user data lives: `$CRYPTAN_DATA_DIR`, else `$XDG_DATA_HOME/cryptan`,
+// else `~/.local/share/cryptan` (`%APPDATA%\cryptan` on Windows).
+pub fn user_data_dir() -> Option<PathBuf> {
+    let var = |name| env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+    var("CRYPTAN_DATA_DIR")
+        .or_else(|| var("XDG_DATA_HOME").map(|dir| dir.join("cryptan")))
+        .or_else(|| var("HOME").map(|home| home.join(".local").join("share").join("cryptan")))
+        .or_else(|| var("APPDATA").map(|dir| dir.join("cryptan")))
+}
+
+// Words a user has added to a language's dictionary, such as names and
+// jargon the embedded lists score as gibberish. Kept one per line in
+// `<user_data_dir>/wordlists/<code>.txt` and, after
+// `Wordlist::with_user_words`, merged into `Wordlist::for_language`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserWords {
+    path: PathBuf,
+    words: BTreeSet<String>,
+}
+
+impl UserWords {
+    // The user's words for `language`; none yet if the file doesn't exist.
+    pub fn open(language: Language) -> Result<Self> {
+        let dir = user_data_dir().ok_or_else(|| {
+            Error::Unsupported("no home directory; set CRYPTAN_DATA_DIR for user word lists".to_string())
+        })?;
+        Self::open_in(dir.join("wordlists"), language)
+    }
+
+    pub fn open_in<P: AsRef<Path>>(dir: P, language: Language) -> Result<Self> {
+        let path = dir.as_ref().join(format!("{}.txt", language.code()));
+        let words = match fs::read_to_string(&path) {
+            Ok(text) => text.lines().filter(|line| !line.starts_with('#')).flat_map(words).collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeSet::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, words })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Alphabetical, lower-cased.
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.words.iter().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+
+    // Adds each word of `text` as `words` splits it, so a word is stored the
+    // way scoring will look it up. Returns how many were new.
+    pub fn add(&mut self, text: &str) -> usize {
+        words(text).filter(|word| self.words.insert(word.clone())).count()
+    }
+
+    // Returns how many of the words of `text` were there to remove.
+    pub fn remove(&mut self, text: &str) -> usize {
+        words(text).filter(|word| self.words.remove(word)).count()
+    }
+
+    // Writes the words back, creating the directory if need be. Takes
+    // effect in the next run; word lists already built keep what they had.
+    pub fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut text = String::new();
+        for word in &self.words {
+            text.push_str(word);
+            text.push('\n');
+        }
+        fs::write(&self.path, text)?;
+        Ok(())
+    }
+}
+
+// The language among `languages` whose word list covers the most of the
+// text, with its `meaningful_ratio`; the first listed wins ties. None when
+// no language recognises a word of it.
+pub fn detect_language(text: &str, languages: &[Language]) -> Option<(Language, f64)> {
+    languages
+        .iter()
+        .map(|&language| (language, Wordlist::for_language(language).meaningful_ratio(text)))
+        .filter(|&(_, ratio)| ratio > 0.0)
+        .fold(None, |best: Option<(Language, f64)>, candidate| match best {
+            Some(best) if best.1 >= candidate.1 => Some(best),
+            _ => Some(candidate),
+        })
+}
+
+// `Wordlist::zipf_frequency` for English.
+pub fn zipf_frequency(word: &str) -> f64 {
+    Wordlist::english().zipf_frequency(word)
+}
+
+// `Wordlist::meaningful_ratio` for English.
+pub fn meaningful_ratio(text: &str) -> f64 {
+    Wordlist::english().meaningful_ratio(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The only test that turns user words on: it is for the whole run, and
+    // adds one made-up German word.
+    #[test]
+    fn user_words_stay_out_unless_asked_for() {
+        let dir = env::temp_dir().join(format!("cryptan-words-{}", std::process::id()));
+        let mut user = UserWords::open_in(dir.join("wordlists"), Language::German).unwrap();
+        user.add("qwxzzy");
+        user.save().unwrap();
+
+        assert!(!Wordlist::for_language(Language::German).contains("qwxzzy"));
+        Wordlist::with_user_words_in(&dir);
+        assert!(Wordlist::for_language(Language::German).contains("qwxzzy"));
+        assert!(!Wordlist::for_language(Language::French).contains("qwxzzy"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn cached_loads_share_one_list() {
+        let path = env::temp_dir().join(format!("cryptan-cached-{}.txt", std::process::id()));
+        fs::write(&path, "alpha\nbeta\n").unwrap();
+
+        let first = Wordlist::load_cached(&path).unwrap();
+        let second = Wordlist::load_cached(&path).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(first.contains("beta"));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,109 @@
+use crate::analysis::{letter_counts, ENGLISH_LETTER_FREQUENCIES};
+use crate::error::{Error, Result};
+
+// A substitution key being worked out by hand: which plain letter each
+// cipher letter is thought to stand for, and how the text reads with the
+// guesses so far. Each plain letter stands for at most one cipher letter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workbench {
+    ciphertext: String,
+    // Indexed by cipher letter, A = 0.
+    mapping: [Option<u8>; 26],
+}
+
+impl Workbench {
+    pub fn new(ciphertext: impl Into<String>) -> Self {
+        Self { ciphertext: ciphertext.into(), mapping: [None; 26] }
+    }
+
+    pub fn ciphertext(&self) -> &str {
+        &self.ciphertext
+    }
+
+    // Guesses that `cipher` stands for `plain`, dropping whichever cipher
+    // letter `plain` was given to before.
+    pub fn set(&mut self, cipher: char, plain: char) -> Result<()> {
+        let (cipher, plain) = (letter_index(cipher)?, letter_index(plain)?);
+        for guess in self.mapping.iter_mut() {
+            if *guess == Some(plain) {
+                *guess = None;
+            }
+        }
+        self.mapping[cipher as usize] = Some(plain);
+        Ok(())
+    }
+
+    pub fn clear(&mut self, cipher: char) -> Result<()> {
+        self.mapping[letter_index(cipher)? as usize] = None;
+        Ok(())
+    }
+
+    pub fn reset(&mut self) {
+        self.mapping = [None; 26];
+    }
+
+    // The usual first guess: the text's letters, commonest first, mapped
+    // onto English letters in order of frequency.
+    pub fn guess_by_frequency(&mut self) {
+        let counts = letter_counts(&self.ciphertext);
+        let mut cipher: Vec<usize> = (0..26).filter(|&i| counts[i] > 0).collect();
+        cipher.sort_by(|&a, &b| counts[b].cmp(&counts[a]).then(a.cmp(&b)));
+        let mut english: Vec<usize> = (0..26).collect();
+        english.sort_by(|&a, &b| ENGLISH_LETTER_FREQUENCIES[b].total_cmp(&ENGLISH_LETTER_FREQUENCIES[a]));
+
+        self.reset();
+        for (cipher, plain) in cipher.into_iter().zip(english) {
+            self.mapping[cipher] = Some(plain as u8);
+        }
+    }
+
+    // The plain letter guessed for `cipher`, uppercase.
+    pub fn plain_for(&self, cipher: char) -> Option<char> {
+        let index = letter_index(cipher).ok()?;
+        self.mapping[index as usize].map(|plain| (plain + b'A') as char)
+    }
+
+    // The cipher letter `plain` has been given to, uppercase.
+    pub fn cipher_for(&self, plain: char) -> Option<char> {
+        let plain = letter_index(plain).ok()?;
+        self.mapping.iter().position(|&guess| guess == Some(plain)).map(|cipher| (cipher as u8 + b'A') as char)
+    }
+
+    // Every cipher letter in the text has a guess.
+    pub fn is_complete(&self) -> bool {
+        let counts = letter_counts(&self.ciphertext);
+        (0..26).all(|i| counts[i] == 0 || self.mapping[i].is_some())
+    }
+
+    // The text with each guessed letter replaced, keeping case, and
+    // `unknown` in place of letters without a guess.
+    pub fn preview(&self, unknown: char) -> String {
+        self.ciphertext
+            .chars()
+            .map(|c| {
+                if !c.is_ascii_alphabetic() {
+                    return c;
+                }
+                match self.mapping[(c.to_ascii_uppercase() as u8 - b'A') as usize] {
+                    Some(plain) if c.is_ascii_lowercase() => (plain + b'a') as char,
+                    Some(plain) => (plain + b'A') as char,
+                    None => unknown,
+                }
+            })
+            .collect()
+    }
+
+    // The guesses in the form `SubstitutionCipher::new` takes, the cipher
+    // letter for A, B, ... Z, with `?` for plain letters not placed yet.
+    pub fn key(&self) -> String {
+        ('A'..='Z').map(|plain| self.cipher_for(plain).unwrap_or('?')).collect()
+    }
+}
+
+fn letter_index(c: char) -> Result<u8> {
+    if c.is_ascii_alphabetic() {
+        Ok(c.to_ascii_uppercase() as u8 - b'A')
+    } else {
+        Err(Error::InvalidKey(format!("{:?} is not a letter A-Z", c)))
+    }
+}
@@ -0,0 +1,180 @@
+use crate::analysis::english_byte_score;
+use crate::budget::Budget;
+use crate::error::{Error, Result};
+use crate::json::Json;
+use crate::modern_toy::xor::single_byte_brute_force;
+
+// Key sizes `break_repeating_xor` tries by default, and how many of the
+// likeliest it goes on to solve.
+pub const DEFAULT_MAX_KEY_SIZE: usize = 40;
+pub const DEFAULT_KEY_SIZE_CANDIDATES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeySize {
+    pub size: usize,
+    // Mean Hamming distance between consecutive blocks of this size, per
+    // bit, over the whole text; lowest when the size is a multiple of the
+    // key's length.
+    pub distance: f64,
+}
+
+impl From<&KeySize> for Json {
+    fn from(key_size: &KeySize) -> Self {
+        Json::object(vec![("size", key_size.size.into()), ("distance", key_size.distance.into())])
+    }
+}
+
+impl TryFrom<&Json> for KeySize {
+    type Error = Error;
+
+    fn try_from(json: &Json) -> Result<Self> {
+        Ok(KeySize { size: json.usize_field("size")?, distance: json.f64_field("distance")? })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepeatingXor {
+    pub key: Vec<u8>,
+    pub plaintext: Vec<u8>,
+    // `english_byte_score` of the plaintext.
+    pub score: f64,
+}
+
+// Bits that differ between `a` and `b`, over the length of the shorter.
+pub fn hamming_distance(a: &[u8], b: &[u8]) -> u32 {
+    a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum()
+}
+
+// Key sizes from 1 to `max_size` by normalized Hamming distance, likeliest
+// first. Sizes that don't fit twice into `data` are left out.
+pub fn xor_key_sizes(data: &[u8], max_size: usize) -> Vec<KeySize> {
+    let mut sizes: Vec<KeySize> = (1..=max_size.min(data.len() / 2))
+        .map(|size| {
+            let blocks: Vec<&[u8]> = data.chunks_exact(size).collect();
+            let pairs = blocks.len() - 1;
+            let bits: u32 = blocks.windows(2).map(|pair| hamming_distance(pair[0], pair[1])).sum();
+            KeySize { size, distance: bits as f64 / (pairs * size * 8) as f64 }
+        })
+        .collect();
+    sizes.sort_by(|a, b| a.distance.total_cmp(&b.distance).then(a.size.cmp(&b.size)));
+    sizes
+}
+
+// Breaks XOR with a repeating key: for each of the `candidates` likeliest
+// key sizes, solves every column of bytes the key's position shares as a
+// single-byte XOR. Best English first; keys that mostly repeat a shorter
+// key are solved again at its length, and sizes that end up with the same
+// key are kept once.
+pub fn break_repeating_xor(data: &[u8], max_size: usize, candidates: usize) -> Vec<RepeatingXor> {
+    break_repeating_xor_with_budget(data, max_size, candidates, &Budget::unlimited())
+}
+
+// Like `break_repeating_xor`, one unit of `budget` per key size solved.
+pub fn break_repeating_xor_with_budget(
+    data: &[u8],
+    max_size: usize,
+    candidates: usize,
+    budget: &Budget,
+) -> Vec<RepeatingXor> {
+    let sizes: Vec<usize> = xor_key_sizes(data, max_size).into_iter().take(candidates).map(|k| k.size).collect();
+    budget.set_total(sizes.len() as u64);
+
+    let mut results: Vec<RepeatingXor> = Vec::new();
+    for size in sizes {
+        if !budget.spend() {
+            break;
+        }
+        let key: Vec<u8> = (0..size).map(|offset| solve_column(data, offset, size)).collect();
+        let period = key_period(&key);
+        let key = if period < size {
+            (0..period).map(|offset| solve_column(data, offset, period)).collect()
+        } else {
+            key
+        };
+        if results.iter().any(|result| result.key == key) {
+            continue;
+        }
+        let plaintext: Vec<u8> = data.iter().zip(key.iter().cycle()).map(|(b, k)| b ^ k).collect();
+        let score = english_byte_score(&plaintext);
+        budget.record_score(score);
+        results.push(RepeatingXor { key, plaintext, score });
+    }
+
+    budget.report();
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    results
+}
+
+// The best single-byte key for every `size`th byte from `offset`.
+fn solve_column(data: &[u8], offset: usize, size: usize) -> u8 {
+    let column: Vec<u8> = data.iter().skip(offset).step_by(size).copied().collect();
+    single_byte_brute_force(&column).first().map_or(0, |best| best.key)
+}
+
+// The period `key` best repeats at: the share of bytes past the first
+// period that match their column's commonest byte must be over half, and
+// the best share wins, shortest first on ties. A key solved at a multiple
+// of the real length repeats it apart from the odd column the fewer bytes
+// threw off, while a key's own bytes only match by chance (`SECRET` isn't
+// `SEC` twice).
+fn key_period(key: &[u8]) -> usize {
+    let len = key.len();
+    let repeats = |period: usize| -> usize {
+        (0..period)
+            .map(|offset| {
+                let column: Vec<u8> = key.iter().skip(offset).step_by(period).copied().collect();
+                column.iter().map(|b| column.iter().filter(|c| *c == b).count()).max().unwrap_or(1) - 1
+            })
+            .sum()
+    };
+    (1..len)
+        .filter(|&period| len.is_multiple_of(period))
+        .map(|period| (period, repeats(period) as f64 / (len - period) as f64))
+        .filter(|&(_, share)| share > 0.5)
+        .fold(None, |best: Option<(usize, f64)>, (period, share)| match best {
+            Some((_, best_share)) if best_share >= share => best,
+            _ => Some((period, share)),
+        })
+        .map_or(len, |(period, _)| period)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modern_toy::XorCipher;
+
+    const PLAIN: &str = "\
+It was the best of times, it was the worst of times, it was the age of wisdom, it was the age of foolishness, \
+it was the epoch of belief, it was the epoch of incredulity, it was the season of Light, it was the season of \
+Darkness, it was the spring of hope, it was the winter of despair, we had everything before us, we had nothing \
+before us, we were all going direct to Heaven, we were all going direct the other way.";
+
+    // From the Cryptopals repeating-key XOR exercise.
+    #[test]
+    fn hamming_distance_counts_differing_bits() {
+        assert_eq!(hamming_distance(b"this is a test", b"wokka wokka!!!"), 37);
+    }
+
+    #[test]
+    fn finds_the_key_length() {
+        let data = XorCipher::new(b"SECRET").unwrap().apply(PLAIN.as_bytes());
+        let best = xor_key_sizes(&data, DEFAULT_MAX_KEY_SIZE)[0].size;
+        assert!(best.is_multiple_of(6), "{}", best);
+    }
+
+    #[test]
+    fn recovers_a_known_key() {
+        let data = XorCipher::new(b"SECRET").unwrap().apply(PLAIN.as_bytes());
+        let results = break_repeating_xor(&data, DEFAULT_MAX_KEY_SIZE, DEFAULT_KEY_SIZE_CANDIDATES);
+        assert_eq!(results[0].key, b"SECRET");
+        assert_eq!(results[0].plaintext, PLAIN.as_bytes());
+    }
+
+    #[test]
+    fn keys_solved_at_a_multiple_fold_back() {
+        assert_eq!(key_period(b"SECRETSECRET"), 6);
+        assert_eq!(key_period(b"SECRETSECRXTSECRET"), 6);
+        assert_eq!(key_period(b"SECRET"), 6);
+        assert_eq!(key_period(b"AAAA"), 1);
+    }
+}
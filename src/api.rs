@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use crate::analysis::letter_frequencies;
+use crate::budget::Budget;
+use crate::encoding::{codec_by_name, CODEC_NAMES};
+use crate::json::Json;
+use crate::magic::{self, Magic, DEFAULT_MAX_DEPTH};
+use crate::registry::Registry;
+
+pub const METHODS: &[&str] = &["encrypt", "decrypt", "encode", "decode", "analyze", "solve"];
+
+// Clients can ask `solve` for less work than this, not more: each extra
+// layer multiplies the search.
+pub const MAX_SOLVE_DEPTH: usize = 8;
+pub const DEFAULT_SOLVE_TIME: Duration = Duration::from_secs(5);
+pub const MAX_SOLVE_TIME: Duration = Duration::from_secs(30);
+
+// Runs one request against the library. Shared by every front end that
+// speaks JSON (the HTTP server, the stdin protocol).
+pub fn dispatch(method: &str, params: &Json) -> Result<Json, String> {
+    match method {
+        "encrypt" | "decrypt" => crypt(method == "encrypt", params),
+        "encode" | "decode" => code(method == "encode", params),
+        "analyze" => analyze(params),
+        "solve" => solve(params),
+        _ => Err(format!("unknown method `{}`, expected one of: {}", method, METHODS.join(", "))),
+    }
+}
+
+fn text_param(params: &Json) -> Result<&str, String> {
+    params
+        .get("text")
+        .and_then(Json::as_str)
+        .ok_or_else(|| "missing string parameter `text`".to_string())
+}
+
+// Any cipher the registry knows, as in a recipe: `cipher` is its name,
+// caesar by default, and `key` (or `keyword`) what follows the colon, so
+// {"cipher": "affine", "key": "5,8"} is `affine:5,8`.
+fn crypt(encrypt: bool, params: &Json) -> Result<Json, String> {
+    let text = text_param(params)?;
+    let cipher = params.get("cipher").and_then(Json::as_str).unwrap_or("caesar");
+    let key = match params.get("key").or_else(|| params.get("keyword")) {
+        None => String::new(),
+        Some(key) => key
+            .as_i64()
+            .map(|key| key.to_string())
+            .or_else(|| key.as_str().map(str::to_string))
+            .ok_or_else(|| "parameter `key` must be a number or a string".to_string())?,
+    };
+    let spec = if key.is_empty() { cipher.to_string() } else { format!("{}:{}", cipher, key) };
+    let cipher = Registry::shared().create_cipher(&spec).map_err(|e| e.to_string())?;
+    let result = if encrypt { cipher.encrypt(text) } else { cipher.decrypt(text) };
+
+    Ok(Json::object(vec![("result", result.into())]))
+}
+
+fn code(encode: bool, params: &Json) -> Result<Json, String> {
+    let text = text_param(params)?;
+    let name = params
+        .get("codec")
+        .and_then(Json::as_str)
+        .ok_or_else(|| "missing string parameter `codec`".to_string())?;
+    let codec = codec_by_name(name)
+        .ok_or_else(|| format!("unknown codec `{}`, expected one of: {}", name, CODEC_NAMES.join(", ")))?;
+
+    let strict = params.get("strict").and_then(Json::as_bool).unwrap_or(false);
+
+    let result = if encode {
+        codec.encode(text)
+    } else if strict {
+        codec.try_decode(text).map_err(|e| e.to_string())?
+    } else {
+        codec.decode(text)
+    };
+
+    Ok(Json::object(vec![("result", result.into())]))
+}
+
+fn analyze(params: &Json) -> Result<Json, String> {
+    let text = text_param(params)?;
+    let frequencies = letter_frequencies(text)
+        .into_iter()
+        .map(|(letter, frequency)| (letter.to_string(), frequency.into()))
+        .collect();
+
+    Ok(Json::object(vec![
+        ("length", text.chars().count().into()),
+        ("letters", text.chars().filter(|c| c.is_ascii_alphabetic()).count().into()),
+        ("score", magic::score(text).into()),
+        ("frequencies", Json::Object(frequencies)),
+    ]))
+}
+
+// The depth and time `solve` gets: what the client asked for, within
+// `MAX_SOLVE_DEPTH` and `MAX_SOLVE_TIME`.
+fn solve_limits(params: &Json) -> (usize, Duration) {
+    let depth = params.get("max_depth").and_then(Json::as_i64).map_or(DEFAULT_MAX_DEPTH, |depth| depth.max(0) as usize);
+    let limit = params
+        .get("time_limit_ms")
+        .and_then(Json::as_i64)
+        .map_or(DEFAULT_SOLVE_TIME, |limit| Duration::from_millis(limit.max(0) as u64));
+    (depth.min(MAX_SOLVE_DEPTH), limit.min(MAX_SOLVE_TIME))
+}
+
+fn solve(params: &Json) -> Result<Json, String> {
+    let text = text_param(params)?;
+    let (depth, limit) = solve_limits(params);
+    let mut magic = Magic::new();
+    magic.set_max_depth(depth);
+    let budget = Budget::unlimited().with_time_limit(limit);
+
+    let tree = magic.decode_with_budget(text, &budget);
+    let (path, result) = tree.best_path();
+
+    Ok(Json::object(vec![
+        ("path", Json::Array(path.into_iter().map(Json::from).collect())),
+        ("score", tree.best_score().into()),
+        ("result", result.into()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(json: &str) -> Json {
+        Json::parse(json).unwrap()
+    }
+
+    #[test]
+    fn crypt_takes_any_registry_cipher() {
+        let result = |method, json| dispatch(method, &params(json)).unwrap().get("result").cloned();
+        assert_eq!(result("encrypt", r#"{"key": 3, "text": "abc"}"#), Some("def".into()));
+        let vigenere = r#"{"cipher": "vigenere", "keyword": "LEMON", "text": "ATTACK"}"#;
+        assert_eq!(result("encrypt", vigenere), Some("LXFOPV".into()));
+        assert_eq!(result("encrypt", r#"{"cipher": "affine", "key": "5,8", "text": "AFFINE"}"#), Some("IHHWVC".into()));
+        assert_eq!(result("decrypt", r#"{"cipher": "atbash", "text": "Draziw"}"#), Some("Wizard".into()));
+        assert!(dispatch("encrypt", &params(r#"{"cipher": "enigma", "text": "x"}"#)).is_err());
+    }
+
+    #[test]
+    fn solve_stays_within_its_limits() {
+        assert_eq!(solve_limits(&params(r#"{"text": "x"}"#)), (DEFAULT_MAX_DEPTH, DEFAULT_SOLVE_TIME));
+        let greedy = params(r#"{"text": "x", "max_depth": 1000000, "time_limit_ms": 100000000}"#);
+        assert_eq!(solve_limits(&greedy), (MAX_SOLVE_DEPTH, MAX_SOLVE_TIME));
+        let modest = params(r#"{"text": "x", "max_depth": 2, "time_limit_ms": 250}"#);
+        assert_eq!(solve_limits(&modest), (2, Duration::from_millis(250)));
+    }
+}
@@ -0,0 +1,270 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_REPORT_EVERY: u64 = 100;
+
+// Shared flag another thread (a GUI, a server handler) can flip to stop a
+// running search.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    pub tried: u64,
+    // Size of the search when the solver knows it up front.
+    pub total: Option<u64>,
+    pub best_score: Option<f64>,
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    pub fn eta(&self) -> Option<Duration> {
+        let total = self.total?;
+        if self.tried == 0 {
+            return None;
+        }
+
+        let remaining = total.saturating_sub(self.tried) as f64;
+        Some(self.elapsed.mul_f64(remaining / self.tried as f64))
+    }
+}
+
+// Counters for what a search did, from `Budget::metrics`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    // Units of budget spent: keys, or steps for the auto-solver.
+    pub keys_tried: u64,
+    pub texts_scored: u64,
+    pub elapsed: Duration,
+}
+
+impl Metrics {
+    pub fn keys_per_second(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            0.0 => 0.0,
+            seconds => self.keys_tried as f64 / seconds,
+        }
+    }
+}
+
+impl fmt::Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} keys tried, {} texts scored in {:.3}s ({:.0} keys/s)",
+            self.keys_tried,
+            self.texts_scored,
+            self.elapsed.as_secs_f64(),
+            self.keys_per_second()
+        )
+    }
+}
+
+// Where a solver's progress goes: a progress bar, a log, a GUI. Any
+// `Fn(&Progress)` closure is one.
+pub trait ProgressSink: Send + Sync {
+    fn report(&self, progress: &Progress);
+}
+
+impl<F> ProgressSink for F
+where
+    F: Fn(&Progress) + Send + Sync,
+{
+    fn report(&self, progress: &Progress) {
+        self(progress)
+    }
+}
+
+// Limits on how much work a solver may do, and the place it reports that
+// work. Solvers call `spend` once per candidate and stop, returning what
+// they have so far, once it says no.
+pub struct Budget {
+    max_iterations: Option<u64>,
+    deadline: Option<Instant>,
+    token: Option<CancellationToken>,
+    iterations: AtomicU64,
+    scored: AtomicU64,
+    started: Instant,
+    total: Mutex<Option<u64>>,
+    best_score: Mutex<Option<f64>>,
+    progress: Option<Box<dyn ProgressSink>>,
+    report_every: u64,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self {
+            max_iterations: None,
+            deadline: None,
+            token: None,
+            iterations: AtomicU64::new(0),
+            scored: AtomicU64::new(0),
+            started: Instant::now(),
+            total: Mutex::new(None),
+            best_score: Mutex::new(None),
+            progress: None,
+            report_every: DEFAULT_REPORT_EVERY,
+        }
+    }
+}
+
+impl Budget {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: u64) -> Self {
+        self.max_iterations = Some(max_iterations);
+        self
+    }
+
+    // The clock starts when this is called.
+    pub fn with_time_limit(mut self, limit: Duration) -> Self {
+        self.deadline = Some(Instant::now() + limit);
+        self
+    }
+
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    // Reports to `sink` every `every` candidates and once more when the
+    // solver finishes.
+    pub fn with_progress<S>(mut self, every: u64, sink: S) -> Self
+    where
+        S: ProgressSink + 'static,
+    {
+        self.report_every = every.max(1);
+        self.progress = Some(Box::new(sink));
+        self
+    }
+
+    pub fn iterations(&self) -> u64 {
+        self.iterations.load(Ordering::Relaxed)
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.max_iterations.is_some_and(|max| self.iterations() >= max)
+            || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            || self.token.as_ref().is_some_and(|token| token.is_cancelled())
+    }
+
+    // Records one unit of work; false means the solver should stop.
+    pub fn spend(&self) -> bool {
+        if self.is_exhausted() {
+            return false;
+        }
+
+        let tried = self.iterations.fetch_add(1, Ordering::Relaxed) + 1;
+        if tried.is_multiple_of(self.report_every) {
+            self.report();
+        }
+        true
+    }
+
+    pub fn set_total(&self, total: u64) {
+        *self.total.lock().unwrap() = Some(total);
+    }
+
+    pub fn record_score(&self, score: f64) {
+        self.scored.fetch_add(1, Ordering::Relaxed);
+        let mut best = self.best_score.lock().unwrap();
+        if best.is_none_or(|best| score > best) {
+            *best = Some(score);
+        }
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress {
+            tried: self.iterations(),
+            total: *self.total.lock().unwrap(),
+            best_score: *self.best_score.lock().unwrap(),
+            elapsed: self.started.elapsed(),
+        }
+    }
+
+    pub fn metrics(&self) -> Metrics {
+        Metrics {
+            keys_tried: self.iterations(),
+            texts_scored: self.scored.load(Ordering::Relaxed),
+            elapsed: self.started.elapsed(),
+        }
+    }
+
+    pub fn report(&self) {
+        if let Some(sink) = &self.progress {
+            sink.report(&self.progress());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spend_stops_at_the_iteration_cap() {
+        let budget = Budget::unlimited().with_max_iterations(3);
+        assert_eq!((0..10).take_while(|_| budget.spend()).count(), 3);
+        assert!(budget.is_exhausted());
+        assert_eq!(budget.iterations(), 3);
+    }
+
+    #[test]
+    fn cancelling_stops_the_next_spend() {
+        let token = CancellationToken::new();
+        let budget = Budget::unlimited().with_cancellation(token.clone());
+        assert!(budget.spend());
+        token.cancel();
+        assert!(!budget.spend());
+        assert_eq!(budget.iterations(), 1);
+    }
+
+    #[test]
+    fn a_spent_time_limit_refuses_work() {
+        let budget = Budget::unlimited().with_time_limit(Duration::ZERO);
+        assert!(!budget.spend());
+    }
+
+    #[test]
+    fn metrics_count_keys_and_scores() {
+        let budget = Budget::unlimited();
+        for score in [0.2, 0.9, 0.5] {
+            budget.spend();
+            budget.record_score(score);
+        }
+        let metrics = budget.metrics();
+        assert_eq!((metrics.keys_tried, metrics.texts_scored), (3, 3));
+        assert_eq!(budget.progress().best_score, Some(0.9));
+    }
+
+    #[test]
+    fn reports_progress_every_n_keys() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        let budget = Budget::unlimited().with_progress(2, move |progress: &Progress| {
+            sink.lock().unwrap().push(progress.tried);
+        });
+        for _ in 0..5 {
+            budget.spend();
+        }
+        assert_eq!(*reports.lock().unwrap(), [2, 4]);
+    }
+}
@@ -1,24 +1,34 @@
+// The original setter-based Caesar API, kept so existing callers still
+// build. It is a thin layer over `classical::CaesarCipher`.
+
+use crate::classical::{caesar, CaesarCipher, ClassicalCipher};
+
+#[deprecated(note = "use `classical::CaesarCipher` and the `ClassicalCipher` trait")]
 pub struct CeasarCipher {
     plain: String,
     encoded_text: String,
 }
 
+#[deprecated(note = "use `ClassicalCipher::encrypt`")]
 pub trait Encode {
     fn encode(&self,  key: i8) -> String;
 }
 
+#[deprecated(note = "use `ClassicalCipher::decrypt`")]
 pub trait Decode {
     fn decode(&self, key: i8) -> String;
 }
 
+#[deprecated(note = "use `classical::caesar::brute_force`")]
 pub trait BruteForce {
     fn brute_force(&self);
 }
 
+#[allow(deprecated)]
 impl CeasarCipher {
 
     pub fn new() -> Self {
-        return Self {plain: "".to_string(), encoded_text: "".to_string()}
+        Self {plain: "".to_string(), encoded_text: "".to_string()}
     }
 
     pub fn set_plain (&mut self, new_plain: String) {
@@ -30,69 +40,44 @@ impl CeasarCipher {
     }
 
     pub fn get_plain(&self) -> String {
-        return self.plain.clone();
+        self.plain.clone()
     }
 
     pub fn get_encoded_text(&self) -> String {
-        return self.encoded_text.clone();
+        self.encoded_text.clone()
     }
 }
 
+#[allow(deprecated)]
+impl Default for CeasarCipher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[allow(deprecated)]
 impl Encode for CeasarCipher {
     fn encode(&self, key: i8) -> String {
-        
-        let mut new_char_code: i8;
-        let mut encoded_text = String::from("");
-
-        for char in self.plain.chars() {
-            if char.is_alphabetic() {
-                if char.is_uppercase() {
-                    new_char_code = ((char as i8) - 65 + key) % 26 + 65;
-                }
-                else {
-                    new_char_code = ((char as i8) - 97 + key) % 26 + 97;
-                }
-            }
-            else {
-                new_char_code = char as i8;
-            }
-
-            encoded_text.push(new_char_code as u8 as char);
-        }
-
-        return encoded_text;
+        CaesarCipher::new(key as i64).encrypt(&self.plain)
     }
 }
 
+#[allow(deprecated)]
 impl Decode for CeasarCipher {
     fn decode(&self, key: i8) -> String {
-        let mut new_char_code: i8;
-        let mut decoded_text = String::from("");
-
-        for char in self.encoded_text.chars() {
-            if char.is_alphabetic() {
-                if char.is_uppercase() {
-                    new_char_code = ((char as i8) - 65 - key).rem_euclid(26) + 65;
-                }
-                else {
-                    new_char_code = ((char as i8) - 97 - key).rem_euclid(26) + 97;
-                }
-            }
-            else {
-                new_char_code = char as i8;
-            }
-
-            decoded_text.push(new_char_code as u8 as char);
-        }
-
-        return decoded_text;
+        CaesarCipher::new(key as i64).decrypt(&self.encoded_text)
     }
 }
 
+// Prints every key in order, as it always has; `caesar::brute_force`
+// returns them ranked instead.
+#[allow(deprecated)]
 impl BruteForce for CeasarCipher {
     fn brute_force(&self) {
-        for key in 0..26 {
-            print!("KEY: {0}\nDECODED TEXT: {1}\n\n", key, self.decode(key));
+        let mut results = caesar::brute_force(&self.encoded_text);
+        results.sort_by_key(|result| result.key.parse::<u8>().unwrap_or(0));
+        for result in results {
+            print!("KEY: {0}\nDECODED TEXT: {1}\n\n", result.key, result.text);
         }
     }
-}
\ No newline at end of file
+}
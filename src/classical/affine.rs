@@ -0,0 +1,133 @@
+use crate::analysis::{EnglishScorer, Scorer};
+use crate::budget::Budget;
+use crate::classical::brute::{self, BruteForceOptions, Keyspace};
+use crate::classical::{Alphabet, ClassicalCipher};
+use crate::error::{Error, Result};
+use crate::result::DecodedResult;
+
+// Values of `a` with an inverse mod 26; 12 of them times 26 shifts gives
+// the 312 usable keys.
+pub const VALID_A: [i32; 12] = [1, 3, 5, 7, 9, 11, 15, 17, 19, 21, 23, 25];
+
+// E(x) = (a * x + b) mod m, D(y) = a⁻¹ * (y - b) mod m, where m is the
+// alphabet's length (26 by default).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AffineCipher {
+    a: i32,
+    b: i32,
+    a_inverse: i32,
+    alphabet: Alphabet,
+}
+
+pub fn mod_inverse(a: i32, modulus: i32) -> Option<i32> {
+    let a = a.rem_euclid(modulus);
+    (1..modulus).find(|x| (a * x) % modulus == 1)
+}
+
+impl AffineCipher {
+    // None unless `a` is coprime to 26.
+    pub fn new(a: i32, b: i32) -> Option<Self> {
+        Self::try_new(a, b).ok()
+    }
+
+    pub fn try_new(a: i32, b: i32) -> Result<Self> {
+        Self::with_alphabet(a, b, Alphabet::latin())
+    }
+
+    // `a` must be coprime to the alphabet's length.
+    pub fn with_alphabet(a: i32, b: i32, alphabet: Alphabet) -> Result<Self> {
+        let m = alphabet.len() as i32;
+        let a_inverse = mod_inverse(a, m).ok_or_else(|| {
+            let valid: Vec<String> = (1..m).filter(|a| mod_inverse(*a, m).is_some()).map(|a| a.to_string()).collect();
+            Error::InvalidKey(format!("a = {} has no inverse mod {}; use one of {}", a, m, valid.join(", ")))
+        })?;
+        Ok(Self { a: a.rem_euclid(m), b: b.rem_euclid(m), a_inverse, alphabet })
+    }
+
+    pub fn a(&self) -> i32 {
+        self.a
+    }
+
+    pub fn b(&self) -> i32 {
+        self.b
+    }
+
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn map(&self, text: &str, f: impl Fn(i64) -> i64) -> String {
+        text.chars().map(|c| self.alphabet.map(c, &f).unwrap_or(c)).collect()
+    }
+}
+
+impl ClassicalCipher for AffineCipher {
+    fn encrypt(&self, plain: &str) -> String {
+        self.map(plain, |x| self.a as i64 * x + self.b as i64)
+    }
+
+    fn decrypt(&self, cipher: &str) -> String {
+        self.map(cipher, |y| self.a_inverse as i64 * (y - self.b as i64))
+    }
+
+    fn works_per_character(&self) -> bool {
+        true
+    }
+}
+
+impl Keyspace for AffineCipher {
+    fn keys(_len: usize) -> impl Iterator<Item = Self> {
+        VALID_A.iter().flat_map(|a| (0..26).filter_map(move |b| AffineCipher::new(*a, b)))
+    }
+
+    fn key_count(_len: usize) -> usize {
+        VALID_A.len() * 26
+    }
+
+    fn key_label(&self) -> String {
+        format!("a={},b={}", self.a, self.b)
+    }
+}
+
+// Tries all 312 keys, most English-looking first.
+pub fn brute_force(cipher: &str) -> Vec<DecodedResult> {
+    brute_force_with(cipher, &EnglishScorer)
+}
+
+// Like `brute_force`, ranked by `scorer`.
+pub fn brute_force_with(cipher: &str, scorer: &dyn Scorer) -> Vec<DecodedResult> {
+    brute_force_with_budget(cipher, scorer, &BruteForceOptions::default(), &Budget::unlimited())
+}
+
+// Like `brute_force`, scored in `options.language` and stopping or
+// filtering as `options` says.
+pub fn brute_force_with_options(cipher: &str, options: &BruteForceOptions) -> Vec<DecodedResult> {
+    brute_force_with_budget(cipher, options.scorer().as_ref(), options, &Budget::unlimited())
+}
+
+// One unit of `budget` per key.
+pub fn brute_force_with_budget(
+    cipher: &str,
+    scorer: &dyn Scorer,
+    options: &BruteForceOptions,
+    budget: &Budget,
+) -> Vec<DecodedResult> {
+    brute::brute_force_cipher::<AffineCipher>(cipher, scorer, options, budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_x_to_5x_plus_8() {
+        let affine = AffineCipher::new(5, 8).unwrap();
+        assert_eq!(affine.encrypt("AFFINE CIPHER"), "IHHWVC SWFRCP");
+        assert_eq!(affine.decrypt("IHHWVC SWFRCP"), "AFFINE CIPHER");
+    }
+
+    #[test]
+    fn rejects_a_multiplier_sharing_a_factor_with_26() {
+        assert!(AffineCipher::new(13, 1).is_none());
+    }
+}
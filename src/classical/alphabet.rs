@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+pub const LATIN: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+pub const DIGITS: &str = "0123456789";
+pub const HEX: &str = "0123456789ABCDEF";
+pub const CYRILLIC: &str = "АБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯ";
+pub const GREEK: &str = "ΑΒΓΔΕΖΗΘΙΚΛΜΝΞΟΠΡΣΤΥΦΧΨΩ";
+pub const VIETNAMESE: &str = "AĂÂBCDĐEÊGHIKLMNOÔƠPQRSTUƯVXY";
+
+// Accented Latin and Greek letters, and the bare letter each comes from.
+const ACCENTED: &str = "ÀÁÂÃÄÅàáâãäåÇçÈÉÊËèéêëÌÍÎÏìíîïÑñÒÓÔÕÖØòóôõöøÙÚÛÜùúûüÝýÿ\
+    ĀāĂăĄąĆćĈĉĊċČčĎďĒēĔĕĖėĘęĚěĜĝĞğĠġĢģĤĥĨĩĪīĬĭĮįİĴĵĶķĹĺĻļĽľŃńŅņŇňŌōŎŏŐőŔŕŖŗŘřŚśŜŝŞşŠšŢţŤťŨũŪūŬŭŮůŰűŲųŴŵŶŷŸŹźŻżŽž\
+    άέήίόύώϊϋΐΰΆΈΉΊΌΎΏΪΫ";
+const PLAIN: &str = "AAAAAAaaaaaaCcEEEEeeeeIIIIiiiiNnOOOOOOooooooUUUUuuuuYyy\
+    AaAaAaCcCcCcCcDdEeEeEeEeEeGgGgGgGgHhIiIiIiIiIJjKkLlLlLlNnNnNnOoOoOoRrRrRrSsSsSsSsTtTtUuUuUuUuUuUuWwYyYZzZzZz\
+    αεηιουωιυιυΑΕΗΙΟΥΩΙΥ";
+
+// Drops accents from Latin and Greek letters (é -> e, ά -> α); anything
+// else comes back unchanged.
+pub fn strip_diacritics(c: char) -> char {
+    match ACCENTED.chars().position(|a| a == c) {
+        Some(i) => PLAIN.chars().nth(i).unwrap_or(c),
+        None => c,
+    }
+}
+
+// Combining marks, which accent the character before them (e + U+0301 is
+// é written in two chars).
+pub fn is_combining(c: char) -> bool {
+    matches!(
+        c,
+        '\u{300}'..='\u{36f}' | '\u{1ab0}'..='\u{1aff}' | '\u{1dc0}'..='\u{1dff}' | '\u{20d0}'..='\u{20ff}'
+            | '\u{fe20}'..='\u{fe2f}'
+    )
+}
+
+// What a cipher does with a letter its alphabet doesn't cover. Spaces,
+// digits and punctuation always pass through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CharPolicy {
+    #[default]
+    Preserve,
+    Strip,
+    Error,
+}
+
+impl FromStr for CharPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "preserve" => Ok(CharPolicy::Preserve),
+            "strip" => Ok(CharPolicy::Strip),
+            "error" => Ok(CharPolicy::Error),
+            _ => Err(format!("unknown policy `{}`, expected preserve, strip or error", s)),
+        }
+    }
+}
+
+pub const ALPHABET_NAMES: &[&str] = &["latin", "digits", "hex", "alphanumeric", "cyrillic", "greek", "vietnamese"];
+
+// Ordered set of characters a substitution cipher works over. Characters
+// outside it pass through the cipher untouched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    chars: Vec<char>,
+    index: HashMap<char, usize>,
+    // Lowercase input is looked up as uppercase and comes back lowercase.
+    fold_case: bool,
+}
+
+impl Alphabet {
+    // Case-sensitive: `a` and `A` are different symbols, or only one of
+    // them is in the alphabet.
+    pub fn new(letters: &str) -> Result<Self> {
+        Self::build(letters.chars().collect(), false)
+    }
+
+    // Letters are stored uppercase and either case of the input matches.
+    pub fn case_insensitive(letters: &str) -> Result<Self> {
+        Self::build(letters.chars().map(upper).collect(), true)
+    }
+
+    fn build(chars: Vec<char>, fold_case: bool) -> Result<Self> {
+        if chars.len() < 2 {
+            return Err(Error::InvalidAlphabet("needs at least two characters".to_string()));
+        }
+
+        let mut index = HashMap::new();
+        for (i, c) in chars.iter().enumerate() {
+            if index.insert(*c, i).is_some() {
+                return Err(Error::InvalidAlphabet(format!("{:?} appears more than once", c)));
+            }
+        }
+
+        Ok(Self { chars, index, fold_case })
+    }
+
+    pub fn latin() -> Self {
+        Self::known(LATIN)
+    }
+
+    pub fn digits() -> Self {
+        Self::known(DIGITS)
+    }
+
+    // 0-9 then A-F, either case.
+    pub fn hex() -> Self {
+        Self::known(HEX)
+    }
+
+    // A-Z then 0-9.
+    pub fn alphanumeric() -> Self {
+        Self::known(&format!("{}{}", LATIN, DIGITS))
+    }
+
+    pub fn cyrillic() -> Self {
+        Self::known(CYRILLIC)
+    }
+
+    pub fn greek() -> Self {
+        Self::known(GREEK)
+    }
+
+    pub fn vietnamese() -> Self {
+        Self::known(VIETNAMESE)
+    }
+
+    // One of `ALPHABET_NAMES`.
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "latin" => Some(Self::latin()),
+            "digits" => Some(Self::digits()),
+            "hex" => Some(Self::hex()),
+            "alphanumeric" => Some(Self::alphanumeric()),
+            "cyrillic" => Some(Self::cyrillic()),
+            "greek" => Some(Self::greek()),
+            "vietnamese" => Some(Self::vietnamese()),
+            _ => None,
+        }
+    }
+
+    fn known(letters: &str) -> Self {
+        Self::case_insensitive(letters).expect("built-in alphabets have no repeats")
+    }
+
+    pub fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    pub fn folds_case(&self) -> bool {
+        self.fold_case
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        self.index_of(c).is_some()
+    }
+
+    pub fn index_of(&self, c: char) -> Option<usize> {
+        let key = if self.fold_case { upper(c) } else { c };
+        self.index.get(&key).copied()
+    }
+
+    // Wraps around in both directions.
+    pub fn char_at(&self, i: i64) -> char {
+        self.chars[i.rem_euclid(self.len() as i64) as usize]
+    }
+
+    // Moves `c` to the position `f` gives for its index, keeping its case;
+    // None if `c` isn't in the alphabet.
+    pub fn map(&self, c: char, f: impl FnOnce(i64) -> i64) -> Option<char> {
+        let mapped = self.char_at(f(self.index_of(c)? as i64));
+        if self.fold_case && c.is_lowercase() {
+            Some(lower(mapped))
+        } else {
+            Some(mapped)
+        }
+    }
+
+    pub fn shift(&self, c: char, by: i64) -> Option<char> {
+        self.map(c, |i| i + by)
+    }
+
+    // Positions of each of `key`'s characters, for keyword ciphers.
+    pub fn indices(&self, key: &str) -> Result<Vec<usize>> {
+        if key.is_empty() {
+            return Err(Error::InvalidKey("the keyword is empty".to_string()));
+        }
+
+        key.chars()
+            .map(|c| {
+                self.index_of(c)
+                    .ok_or_else(|| Error::InvalidKey(format!("keyword has {:?}, which is not in the alphabet", c)))
+            })
+            .collect()
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Self::latin()
+    }
+}
+
+// Single-character case mapping; characters whose other case is more than
+// one character (ß -> SS) stay as they are.
+fn upper(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(u), None) => u,
+        _ => c,
+    }
+}
+
+fn lower(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
@@ -0,0 +1,46 @@
+use crate::classical::ClassicalCipher;
+
+// A <-> Z, B <-> Y, ... within each case. Its own inverse.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AtbashCipher;
+
+impl AtbashCipher {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn mirror(text: &str) -> String {
+        text.chars()
+            .map(|c| match c {
+                'A'..='Z' => (b'Z' - (c as u8 - b'A')) as char,
+                'a'..='z' => (b'z' - (c as u8 - b'a')) as char,
+                _ => c,
+            })
+            .collect()
+    }
+}
+
+impl ClassicalCipher for AtbashCipher {
+    fn encrypt(&self, plain: &str) -> String {
+        AtbashCipher::mirror(plain)
+    }
+
+    fn decrypt(&self, cipher: &str) -> String {
+        AtbashCipher::mirror(cipher)
+    }
+
+    fn works_per_character(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reverses_the_alphabet() {
+        assert_eq!(AtbashCipher::new().encrypt("Wizard"), "Draziw");
+        assert_eq!(AtbashCipher::new().decrypt("Draziw"), "Wizard");
+    }
+}
@@ -0,0 +1,74 @@
+use crate::classical::vigenere::{keyword_shifts, letter_index, shift_letter};
+use crate::classical::ClassicalCipher;
+use crate::error::Result;
+
+// Vigenère whose key is the keyword followed by the plaintext itself, so
+// the key never repeats.
+pub struct AutokeyCipher {
+    keyword: String,
+    shifts: Vec<i32>,
+}
+
+impl AutokeyCipher {
+    pub fn new(keyword: &str) -> Option<Self> {
+        Self::try_new(keyword).ok()
+    }
+
+    pub fn try_new(keyword: &str) -> Result<Self> {
+        let shifts = keyword_shifts(keyword)?;
+        Ok(Self { keyword: keyword.to_ascii_uppercase(), shifts })
+    }
+
+    pub fn keyword(&self) -> &str {
+        &self.keyword
+    }
+}
+
+impl ClassicalCipher for AutokeyCipher {
+    fn encrypt(&self, plain: &str) -> String {
+        let mut key = self.shifts.clone();
+        let mut position = 0;
+
+        plain
+            .chars()
+            .map(|c| match shift_letter(c, key[position]) {
+                Some(shifted) => {
+                    key.push(letter_index(c));
+                    position += 1;
+                    shifted
+                }
+                None => c,
+            })
+            .collect()
+    }
+
+    // Each recovered plaintext letter becomes key for a later one.
+    fn decrypt(&self, cipher: &str) -> String {
+        let mut key = self.shifts.clone();
+        let mut position = 0;
+
+        cipher
+            .chars()
+            .map(|c| match shift_letter(c, -key[position]) {
+                Some(plain) => {
+                    key.push(letter_index(plain));
+                    position += 1;
+                    plain
+                }
+                None => c,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extends_the_key_with_the_plaintext() {
+        let autokey = AutokeyCipher::new("QUEENLY").unwrap();
+        assert_eq!(autokey.encrypt("ATTACKATDAWN"), "QNXEPVYTWTWP");
+        assert_eq!(autokey.decrypt("QNXEPVYTWTWP"), "ATTACKATDAWN");
+    }
+}
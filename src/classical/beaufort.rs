@@ -0,0 +1,73 @@
+use std::io::{self, Read, Write};
+
+use crate::classical::stream::transform_chunks;
+use crate::classical::vigenere::{keyword_shifts, letter_index, shift_letter};
+use crate::classical::ClassicalCipher;
+use crate::error::Result;
+
+// C = K - P (mod 26). Reciprocal: the same operation decrypts.
+pub struct BeaufortCipher {
+    keyword: String,
+    shifts: Vec<i32>,
+}
+
+impl BeaufortCipher {
+    pub fn new(keyword: &str) -> Option<Self> {
+        Self::try_new(keyword).ok()
+    }
+
+    pub fn try_new(keyword: &str) -> Result<Self> {
+        let shifts = keyword_shifts(keyword)?;
+        Ok(Self { keyword: keyword.to_ascii_uppercase(), shifts })
+    }
+
+    pub fn keyword(&self) -> &str {
+        &self.keyword
+    }
+
+    // `position` counts letters already handled, as in Vigenère.
+    fn apply(&self, text: &str, position: &mut usize) -> String {
+        text.chars()
+            .map(|c| {
+                if !c.is_ascii_alphabetic() {
+                    return c;
+                }
+                let key = self.shifts[*position % self.shifts.len()];
+                *position += 1;
+                // Mirror the letter (x -> -x) then shift by the key.
+                shift_letter(c, key - 2 * letter_index(c)).unwrap_or(c)
+            })
+            .collect()
+    }
+}
+
+impl ClassicalCipher for BeaufortCipher {
+    fn encrypt(&self, plain: &str) -> String {
+        self.apply(plain, &mut 0)
+    }
+
+    fn decrypt(&self, cipher: &str) -> String {
+        self.apply(cipher, &mut 0)
+    }
+
+    fn encrypt_reader(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> io::Result<()> {
+        let mut position = 0;
+        transform_chunks(reader, writer, |chunk| self.apply(chunk, &mut position))
+    }
+
+    fn decrypt_reader(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> io::Result<()> {
+        self.encrypt_reader(reader, writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subtracts_the_plaintext_from_the_key() {
+        let beaufort = BeaufortCipher::new("FORTIFICATION").unwrap();
+        assert_eq!(beaufort.encrypt("DEFENDTHEEASTWALLOFTHECASTLE"), "CKMPVCPVWPIWUJOGIUAPVWRIWUUK");
+        assert_eq!(beaufort.decrypt("CKMPVCPVWPIWUJOGIUAPVWRIWUUK"), "DEFENDTHEEASTWALLOFTHECASTLE");
+    }
+}
@@ -0,0 +1,65 @@
+use crate::classical::polybius::{fractionate, unfractionate, PolybiusSquare};
+use crate::classical::ClassicalCipher;
+
+// Delastelle's Bifid: letters become row and column in a keyed 5x5 square,
+// the rows and columns of each period are shuffled together, and the pairs
+// are read back as letters. Only letters are kept, in upper case, with J
+// written as I.
+pub struct BifidCipher {
+    square: PolybiusSquare,
+    period: usize,
+}
+
+impl BifidCipher {
+    pub fn new(keyword: &str) -> Self {
+        Self { square: PolybiusSquare::five_by_five(keyword), period: 0 }
+    }
+
+    // Letters fractionated together; 0, the default, takes the whole text
+    // as one block.
+    pub fn with_period(mut self, period: usize) -> Self {
+        self.period = period;
+        self
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    pub fn square(&self) -> &PolybiusSquare {
+        &self.square
+    }
+}
+
+impl ClassicalCipher for BifidCipher {
+    fn encrypt(&self, plain: &str) -> String {
+        fractionate(&self.square, plain, self.period)
+    }
+
+    fn decrypt(&self, cipher: &str) -> String {
+        unfractionate(&self.square, cipher, self.period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The square from Wikipedia's example, given in full as the keyword.
+    const SQUARE: &str = "BGWKZQPNDSIOAXEFCLUMTHYVR";
+
+    #[test]
+    fn matches_wikipedias_example() {
+        let bifid = BifidCipher::new(SQUARE);
+        assert_eq!(bifid.encrypt("FLEEATONCE"), "UAEOLWRINS");
+        assert_eq!(bifid.decrypt("UAEOLWRINS"), "FLEEATONCE");
+    }
+
+    // Ten letters in blocks of three leave a block of one.
+    #[test]
+    fn round_trips_a_partial_last_period() {
+        let bifid = BifidCipher::new(SQUARE).with_period(3);
+        assert_eq!(bifid.encrypt("Flee at once!"), "UIEARIOCOE");
+        assert_eq!(bifid.decrypt("UIEARIOCOE"), "FLEEATONCE");
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::classical::ClassicalCipher;
+use crate::diagnostics::{Diagnostics, Warning};
+use crate::error::{Error, Result};
+
+pub const WORD_SEPARATOR: &str = "/";
+
+// Pages of the key text are separated by form feeds; a text without any
+// is a single page.
+const PAGE_BREAK: char = '\x0c';
+
+// How a word of the key text is referred to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BookStyle {
+    // Position among all the words, counting from 1.
+    #[default]
+    WordIndex,
+    // Ottendorf's page.line.word, each counting from 1.
+    PageLineWord,
+}
+
+impl FromStr for BookStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "index" | "word" => Ok(BookStyle::WordIndex),
+            "page" | "ottendorf" => Ok(BookStyle::PageLineWord),
+            _ => Err(format!("unknown book style `{}`, expected index or page", s)),
+        }
+    }
+}
+
+// What a reference points to.
+enum Entry<'a> {
+    Word(&'a str),
+    Letter(char),
+}
+
+struct BookWord {
+    page: usize,
+    line: usize,
+    word: usize,
+    text: String,
+}
+
+// A book cipher: each plaintext word becomes a reference to the same word
+// in a shared key text. Words the key text lacks are spelled out with
+// references to single letters, `<word>:<letter>`, preferring words that
+// start with the letter.
+//
+// References within a word are separated by spaces and words by " / ".
+// Decrypted text comes out in lower case without punctuation.
+pub struct BookCipher {
+    words: Vec<BookWord>,
+    by_word: HashMap<String, Vec<usize>>,
+    by_letter: HashMap<char, Vec<(usize, usize)>>,
+    style: BookStyle,
+}
+
+fn normalize(word: &str) -> String {
+    word.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+impl BookCipher {
+    pub fn new(key_text: &str) -> Self {
+        let mut words = Vec::new();
+        for (page, text) in key_text.split(PAGE_BREAK).enumerate() {
+            for (line, text) in text.lines().enumerate() {
+                let line_words = text.split_whitespace().map(normalize).filter(|word| !word.is_empty());
+                for (word, text) in line_words.enumerate() {
+                    words.push(BookWord { page: page + 1, line: line + 1, word: word + 1, text });
+                }
+            }
+        }
+
+        let mut by_word: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut first_letters: HashMap<char, Vec<(usize, usize)>> = HashMap::new();
+        let mut other_letters: HashMap<char, Vec<(usize, usize)>> = HashMap::new();
+        for (i, word) in words.iter().enumerate() {
+            by_word.entry(word.text.clone()).or_default().push(i);
+            for (position, c) in word.text.chars().enumerate() {
+                let letters = if position == 0 { &mut first_letters } else { &mut other_letters };
+                letters.entry(c).or_default().push((i, position));
+            }
+        }
+        // Letters no word starts with fall back to letters inside words.
+        for (c, positions) in other_letters {
+            first_letters.entry(c).or_insert(positions);
+        }
+
+        Self { words, by_word, by_letter: first_letters, style: BookStyle::default() }
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::new(&fs::read_to_string(path)?))
+    }
+
+    pub fn with_style(mut self, style: BookStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.words.len()
+    }
+
+    fn reference(&self, index: usize) -> String {
+        let word = &self.words[index];
+        match self.style {
+            BookStyle::WordIndex => (index + 1).to_string(),
+            BookStyle::PageLineWord => format!("{}.{}.{}", word.page, word.line, word.word),
+        }
+    }
+
+    // Either style is accepted whatever the cipher's own style is. Well
+    // formed references past the end of the book are out of range.
+    fn lookup(&self, reference: &str) -> Result<Entry<'_>> {
+        let bad = || Error::Unsupported(format!("`{}` is not a book reference", reference));
+        let out_of_range = || Error::Unsupported(format!("reference `{}` is not in the key text", reference));
+        let (word, letter) = match reference.split_once(':') {
+            Some((word, letter)) => (word, Some(letter.parse::<usize>().map_err(|_| bad())?)),
+            None => (reference, None),
+        };
+
+        let numbers = word.split('.').map(|n| n.parse::<usize>().map_err(|_| bad())).collect::<Result<Vec<_>>>()?;
+        let found = match numbers[..] {
+            [index] => index.checked_sub(1).and_then(|i| self.words.get(i)),
+            [page, line, word] => self.words.iter().find(|w| w.page == page && w.line == line && w.word == word),
+            _ => return Err(bad()),
+        };
+        let found = found.ok_or_else(out_of_range)?;
+
+        match letter {
+            None => Ok(Entry::Word(&found.text)),
+            Some(letter) => letter
+                .checked_sub(1)
+                .and_then(|i| found.text.chars().nth(i))
+                .map(Entry::Letter)
+                .ok_or_else(out_of_range),
+        }
+    }
+
+    // References for one plaintext word. Occurrences are used in turn so
+    // repeated words and letters don't repeat the same reference.
+    fn encrypt_word(
+        &self,
+        word: &str,
+        used: &mut HashMap<String, usize>,
+        diagnostics: Option<&Diagnostics>,
+    ) -> Vec<String> {
+        if let Some(indices) = self.by_word.get(word) {
+            let turn = used.entry(word.to_string()).or_insert(0);
+            *turn += 1;
+            return vec![self.reference(indices[(*turn - 1) % indices.len()])];
+        }
+
+        word.chars()
+            .filter_map(|c| {
+                let Some(positions) = self.by_letter.get(&c) else {
+                    if let Some(diagnostics) = diagnostics {
+                        diagnostics.warn(Warning::SkippedCharacter(c));
+                    }
+                    return None;
+                };
+                // Kept apart from the turn of a one-letter word.
+                let turn = used.entry(format!(":{}", c)).or_insert(0);
+                *turn += 1;
+                let (index, position) = positions[(*turn - 1) % positions.len()];
+                Some(format!("{}:{}", self.reference(index), position + 1))
+            })
+            .collect()
+    }
+
+    fn encrypt_reporting(&self, plain: &str, diagnostics: Option<&Diagnostics>) -> String {
+        let mut used = HashMap::new();
+
+        plain
+            .split_whitespace()
+            .map(normalize)
+            .map(|word| self.encrypt_word(&word, &mut used, diagnostics).join(" "))
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<String>>()
+            .join(&format!(" {} ", WORD_SEPARATOR))
+    }
+
+    fn decrypt_word(&self, word: &str, mut on_error: impl FnMut(usize, &str, Error) -> Option<String>) -> String {
+        word.split_whitespace()
+            .enumerate()
+            .filter_map(|(i, reference)| match self.lookup(reference) {
+                Ok(Entry::Word(text)) => Some(text.to_string()),
+                Ok(Entry::Letter(c)) => Some(c.to_string()),
+                Err(e) => on_error(i, reference, e),
+            })
+            .collect()
+    }
+
+    pub fn decrypt_strict(&self, cipher: &str) -> Result<String> {
+        let mut words = Vec::new();
+
+        for word in cipher.split(WORD_SEPARATOR) {
+            let mut error = None;
+            let decoded = self.decrypt_word(word, |_, _, e| {
+                error.get_or_insert(e);
+                None
+            });
+            if let Some(e) = error {
+                return Err(e);
+            }
+            if !decoded.is_empty() {
+                words.push(decoded);
+            }
+        }
+
+        Ok(words.join(" "))
+    }
+
+    fn decrypt_reporting(&self, cipher: &str, diagnostics: Option<&Diagnostics>) -> String {
+        let mut position = 0;
+
+        cipher
+            .split(WORD_SEPARATOR)
+            .map(|word| {
+                let count = word.split_whitespace().count();
+                let decoded = self.decrypt_word(word, |i, reference, _| {
+                    if let Some(diagnostics) = diagnostics {
+                        let token = reference.to_string();
+                        diagnostics.warn(Warning::SkippedToken { position: position + i, token });
+                    }
+                    Some(format!("[?{}]", reference))
+                });
+                position += count;
+                decoded
+            })
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+impl ClassicalCipher for BookCipher {
+    // Characters that appear nowhere in the key text are dropped.
+    fn encrypt(&self, plain: &str) -> String {
+        self.encrypt_reporting(plain, None)
+    }
+
+    // References outside the key text come out as `[?<reference>]`.
+    fn decrypt(&self, cipher: &str) -> String {
+        self.decrypt_reporting(cipher, None)
+    }
+
+    fn encrypt_with_diagnostics(&self, plain: &str, diagnostics: &Diagnostics) -> String {
+        self.encrypt_reporting(plain, Some(diagnostics))
+    }
+
+    fn decrypt_with_diagnostics(&self, cipher: &str, diagnostics: &Diagnostics) -> String {
+        self.decrypt_reporting(cipher, Some(diagnostics))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOK: &str = "the cat sat\non the mat";
+
+    #[test]
+    fn encrypts_words_and_spells_the_rest() {
+        let book = BookCipher::new(BOOK);
+        // "hat" isn't in the book: h from the, a from cat, t from the.
+        assert_eq!(book.encrypt("cat mat hat"), "2 / 6 / 1:2 2:2 1:1");
+        assert_eq!(book.decrypt("2 / 6 / 1:2 2:2 1:1"), "cat mat hat");
+    }
+
+    #[test]
+    fn page_line_word_references() {
+        let book = BookCipher::new(BOOK).with_style(BookStyle::PageLineWord);
+        assert_eq!(book.encrypt("mat"), "1.2.3");
+        assert_eq!(book.decrypt("1.2.3"), "mat");
+    }
+}
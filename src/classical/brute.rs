@@ -0,0 +1,192 @@
+use crate::analysis::{EnglishScorer, Language, MultiLanguageScorer, Scorer, WordlistScorer};
+use crate::budget::Budget;
+use crate::classical::ClassicalCipher;
+use crate::random::Rng;
+use crate::result::{rank, DecodedResult};
+
+// How a brute force tries its keys and which results it keeps. The
+// defaults try every key and keep every result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BruteForceOptions {
+    // Results scoring below this are dropped.
+    pub threshold: f64,
+    // Scores English with `EnglishScorer`, other languages with their
+    // word list.
+    pub language: Language,
+    // When there are several, scores each candidate in whichever of them
+    // it reads best in, instead of `language`.
+    pub languages: Vec<Language>,
+    // Stops trying keys once one scores above this.
+    pub stop_at_first_above: Option<f64>,
+    pub max_results: Option<usize>,
+}
+
+impl Default for BruteForceOptions {
+    fn default() -> Self {
+        Self {
+            threshold: 0.0,
+            language: Language::English,
+            languages: Vec::new(),
+            stop_at_first_above: None,
+            max_results: None,
+        }
+    }
+}
+
+impl BruteForceOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    pub fn with_languages(mut self, languages: &[Language]) -> Self {
+        self.languages = languages.to_vec();
+        self
+    }
+
+    pub fn with_stop_at_first_above(mut self, score: f64) -> Self {
+        self.stop_at_first_above = Some(score);
+        self
+    }
+
+    pub fn with_max_results(mut self, max_results: usize) -> Self {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    pub fn scorer(&self) -> Box<dyn Scorer> {
+        let language = match self.languages[..] {
+            [] => self.language,
+            [language] => language,
+            _ => return Box::new(MultiLanguageScorer::new(&self.languages)),
+        };
+        match language {
+            Language::English => Box::new(EnglishScorer),
+            language => Box::new(WordlistScorer::for_language(language)),
+        }
+    }
+}
+
+// The keys of a cipher, so `brute_force_cipher`, or `brute_force_bytes`
+// for ciphers over raw bytes, can try them all. A
+// cipher's keyspace may depend on the length of the text, e.g. the grid
+// shapes a transposition can use.
+pub trait Keyspace: Sized {
+    // Every key worth trying on `len` characters, in a fixed order.
+    fn keys(len: usize) -> impl Iterator<Item = Self>;
+
+    // How many keys `keys` yields, for progress reports.
+    fn key_count(len: usize) -> usize;
+
+    // The key as brute force results show it.
+    fn key_label(&self) -> String;
+
+    // A key picked at random from `keys`, or None if there are none.
+    fn sample(len: usize, rng: &mut Rng) -> Option<Self> {
+        match Self::key_count(len) {
+            0 => None,
+            count => Self::keys(len).nth(rng.below(count as u64) as usize),
+        }
+    }
+}
+
+// Decrypts `cipher` with every key of `C` and returns the ones `options`
+// keeps, ranked by `scorer`. One unit of `budget` per key.
+pub fn brute_force_cipher<C: ClassicalCipher + Keyspace>(
+    cipher: &str,
+    scorer: &dyn Scorer,
+    options: &BruteForceOptions,
+    budget: &Budget,
+) -> Vec<DecodedResult> {
+    let len = cipher.chars().count();
+    brute_force_keys(cipher, C::keys(len), C::key_count(len), scorer, options, budget)
+}
+
+// Like `brute_force_cipher`, with only `keys`, `total` of them, out of the
+// keyspace, e.g. the column counts a caller asked for.
+pub fn brute_force_keys<C: ClassicalCipher + Keyspace>(
+    cipher: &str,
+    keys: impl Iterator<Item = C>,
+    total: usize,
+    scorer: &dyn Scorer,
+    options: &BruteForceOptions,
+    budget: &Budget,
+) -> Vec<DecodedResult> {
+    let candidates = keys.map(|key| (key.key_label(), key.decrypt(cipher)));
+    search(candidates, total, scorer, options, budget)
+}
+
+// Like `brute_force_cipher`, for ciphers over raw bytes, which needn't be
+// text: `decrypt` undoes a key and `score` rates the bytes it gives. Every
+// key tried, with its bytes and score, best first. One unit of `budget`
+// per key.
+pub fn brute_force_bytes<C: Keyspace>(
+    data: &[u8],
+    decrypt: impl Fn(&C, &[u8]) -> Vec<u8>,
+    score: impl Fn(&[u8]) -> f64,
+    budget: &Budget,
+) -> Vec<(C, Vec<u8>, f64)> {
+    let mut results = Vec::new();
+    budget.set_total(C::key_count(data.len()) as u64);
+
+    for key in C::keys(data.len()) {
+        if !budget.spend() {
+            break;
+        }
+        let bytes = decrypt(&key, data);
+        let score = score(&bytes);
+        budget.record_score(score);
+        results.push((key, bytes, score));
+    }
+
+    budget.report();
+    results.sort_by(|a, b| b.2.total_cmp(&a.2));
+    results
+}
+
+// Scores `candidates`, (key, plaintext) pairs produced as they are needed,
+// and returns the ones `options` keeps, best first. `total` is how many
+// there are, for progress reports.
+pub(crate) fn search(
+    candidates: impl Iterator<Item = (String, String)>,
+    total: usize,
+    scorer: &dyn Scorer,
+    options: &BruteForceOptions,
+    budget: &Budget,
+) -> Vec<DecodedResult> {
+    let mut results = Vec::new();
+    budget.set_total(total as u64);
+
+    for (key, text) in candidates {
+        if !budget.spend() {
+            break;
+        }
+        let score = f64::from(scorer.score(&text));
+        budget.record_score(score);
+        if score >= options.threshold {
+            results.push(DecodedResult::new(key, text, score).with_scorer(scorer.name()));
+        }
+        if options.stop_at_first_above.is_some_and(|stop| score > stop) {
+            break;
+        }
+    }
+
+    budget.report();
+    rank(&mut results);
+    if let Some(max_results) = options.max_results {
+        results.truncate(max_results);
+    }
+    for result in &mut results {
+        result.language = scorer.language(&result.text);
+    }
+    results
+}
@@ -0,0 +1,133 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::analysis::printable_ratio;
+use crate::budget::Budget;
+use crate::classical::brute::{self, Keyspace};
+use crate::classical::stream::transform_byte_chunks;
+
+// ROT-N over the whole byte range (add N mod 256), for binary data that
+// was obfuscated with a simple byte offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteShiftCipher {
+    key: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ByteCandidate {
+    pub key: u8,
+    pub bytes: Vec<u8>,
+    pub score: f64,
+}
+
+impl ByteShiftCipher {
+    pub fn new(key: u8) -> Self {
+        Self { key }
+    }
+
+    pub fn key(&self) -> u8 {
+        self.key
+    }
+
+    pub fn encrypt_bytes(&self, data: &[u8]) -> Vec<u8> {
+        data.iter().map(|b| b.wrapping_add(self.key)).collect()
+    }
+
+    pub fn decrypt_bytes(&self, data: &[u8]) -> Vec<u8> {
+        data.iter().map(|b| b.wrapping_sub(self.key)).collect()
+    }
+
+    // Like `ClassicalCipher::encrypt_reader`, but any bytes will do, UTF-8
+    // or not.
+    pub fn encrypt_reader(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> io::Result<()> {
+        transform_byte_chunks(reader, writer, |chunk| self.encrypt_bytes(chunk))
+    }
+
+    pub fn decrypt_reader(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> io::Result<()> {
+        transform_byte_chunks(reader, writer, |chunk| self.decrypt_bytes(chunk))
+    }
+
+    // A chunk at a time, so files of any size.
+    pub fn encrypt_file<P: AsRef<Path>, Q: AsRef<Path>>(&self, input: P, output: Q) -> io::Result<()> {
+        self.encrypt_reader(&mut File::open(input)?, &mut BufWriter::new(File::create(output)?))
+    }
+
+    pub fn decrypt_file<P: AsRef<Path>, Q: AsRef<Path>>(&self, input: P, output: Q) -> io::Result<()> {
+        self.decrypt_reader(&mut File::open(input)?, &mut BufWriter::new(File::create(output)?))
+    }
+}
+
+impl Keyspace for ByteShiftCipher {
+    // Key 0 changes nothing, so isn't tried.
+    fn keys(_len: usize) -> impl Iterator<Item = Self> {
+        (1..=255).map(ByteShiftCipher::new)
+    }
+
+    fn key_count(_len: usize) -> usize {
+        255
+    }
+
+    fn key_label(&self) -> String {
+        self.key.to_string()
+    }
+}
+
+// Printable ASCII first, then how much of it looks like words, 0.0 to 1.0.
+// Entropy is the same for every offset, so it can't rank keys on its own.
+pub fn score_bytes(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+
+    let wordy = data.iter().filter(|b| b.is_ascii_alphabetic() || **b == b' ').count();
+    (printable_ratio(data) + wordy as f64 / data.len() as f64) / 2.0
+}
+
+// Tries the 255 offsets that change anything and returns them best first.
+pub fn brute_force(data: &[u8]) -> Vec<ByteCandidate> {
+    brute_force_with_budget(data, &Budget::unlimited())
+}
+
+// Like `brute_force`, one unit of `budget` per key.
+pub fn brute_force_with_budget(data: &[u8], budget: &Budget) -> Vec<ByteCandidate> {
+    brute::brute_force_bytes(data, ByteShiftCipher::decrypt_bytes, score_bytes, budget)
+        .into_iter()
+        .map(|(cipher, bytes, score)| ByteCandidate { key: cipher.key, bytes, score })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_around_the_byte_range() {
+        let shift = ByteShiftCipher::new(1);
+        assert_eq!(shift.encrypt_bytes(&[0xff, 0x00, b'A']), [0x00, 0x01, b'B']);
+        assert_eq!(shift.decrypt_bytes(&[0x00, 0x01, b'B']), [0xff, 0x00, b'A']);
+    }
+
+    #[test]
+    fn streams_bytes_that_are_not_utf8() {
+        let data = [0xc3, 0x28, 0xff, b'a'];
+        let mut encrypted = Vec::new();
+        ByteShiftCipher::new(200).encrypt_reader(&mut &data[..], &mut encrypted).unwrap();
+        assert_eq!(encrypted, ByteShiftCipher::new(200).encrypt_bytes(&data));
+    }
+
+    #[test]
+    fn brute_force_finds_the_key() {
+        let cipher = ByteShiftCipher::new(131).encrypt_bytes(b"Attack at dawn, the bridge is out");
+        let best = &brute_force(&cipher)[0];
+        assert_eq!(best.key, 131);
+        assert_eq!(best.bytes, b"Attack at dawn, the bridge is out");
+    }
+
+    #[test]
+    fn brute_force_tries_every_key_but_zero() {
+        let candidates = brute_force(b"\x00\xff");
+        assert_eq!(candidates.len(), 255);
+        assert!(candidates.iter().all(|candidate| candidate.key != 0));
+    }
+}
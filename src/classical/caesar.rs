@@ -0,0 +1,351 @@
+use crate::analysis::{EnglishScorer, Scorer};
+use crate::budget::Budget;
+use crate::classical::alphabet::{is_combining, strip_diacritics, CharPolicy};
+use crate::classical::brute::{self, BruteForceOptions, Keyspace};
+use crate::classical::{replace_ascii, Alphabet, ClassicalCipher};
+use crate::error::{Error, Result};
+use crate::result::DecodedResult;
+
+// Shifts letters `key` places along the alphabet, A-Z by default, keeping
+// their case; every other char, including emoji, passes through. A letter
+// followed by combining marks counts as the accented letter it spells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaesarCipher {
+    key: i64,
+    alphabet: Alphabet,
+    // Unicode mode: each letter shifts within its own script's alphabet,
+    // accents dropped first, instead of within `alphabet`.
+    scripts: Option<Vec<Alphabet>>,
+    policy: CharPolicy,
+    // ROT5: digits the alphabet lacks turn 5 places, whatever the key.
+    rotate_digits: bool,
+}
+
+// Keys are never changed in place, so one cipher can be shared behind `&`
+// or an `Arc` across threads; this stops that from quietly breaking.
+const _: () = {
+    const fn shareable<T: Clone + Send + Sync>() {}
+    shareable::<CaesarCipher>();
+};
+
+impl CaesarCipher {
+    // Any integer works; it is taken mod 26.
+    pub fn new(key: i64) -> Self {
+        Self::with_alphabet(key, Alphabet::latin())
+    }
+
+    // `key` is taken mod the alphabet's length.
+    pub fn with_alphabet(key: i64, alphabet: Alphabet) -> Self {
+        Self { key, alphabet, scripts: None, policy: CharPolicy::default(), rotate_digits: false }
+    }
+
+    // ROT13 for letters with ROT5 for digits; its own inverse.
+    pub fn rot18() -> Self {
+        Self::new(13).with_rotated_digits(true)
+    }
+
+    // Shifts Latin (accents dropped), Greek and Cyrillic letters, each
+    // within its own alphabet.
+    pub fn with_unicode(mut self, unicode: bool) -> Self {
+        self.scripts = unicode.then(|| vec![Alphabet::latin(), Alphabet::greek(), Alphabet::cyrillic()]);
+        self
+    }
+
+    // Also turns 0-9 five places (ROT5), so digits are hidden too. Since
+    // 5 is half of 10 the same turn undoes itself on decryption.
+    pub fn with_rotated_digits(mut self, rotate: bool) -> Self {
+        self.rotate_digits = rotate;
+        self
+    }
+
+    pub fn rotates_digits(&self) -> bool {
+        self.rotate_digits
+    }
+
+    // What happens to letters the alphabet (or, in Unicode mode, every
+    // script) lacks. `encrypt` and `decrypt` treat `Error` as `Preserve`;
+    // use `try_encrypt` and `try_decrypt` to get the error.
+    pub fn with_policy(mut self, policy: CharPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn key(&self) -> usize {
+        self.key.rem_euclid(self.alphabet.len() as i64) as usize
+    }
+
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    pub fn try_encrypt(&self, plain: &str) -> Result<String> {
+        self.shift(plain, self.reduced_key(), self.policy)
+    }
+
+    pub fn try_decrypt(&self, cipher: &str) -> Result<String> {
+        self.shift(cipher, -self.reduced_key(), self.policy)
+    }
+
+    // `key` reduced by a multiple of every script's length, so it turns
+    // each one the same distance, and neither negating it nor adding a
+    // letter's index to it can overflow.
+    fn reduced_key(&self) -> i64 {
+        let lengths = self.scripts.iter().flatten().chain([&self.alphabet]).map(|alphabet| alphabet.len().max(1));
+        let period = lengths.fold(1, |period, len| period / gcd(period, len) * len);
+        self.key % period as i64
+    }
+
+    fn shift_char(&self, c: char, shift: i64) -> Option<char> {
+        let shifted = match &self.scripts {
+            Some(scripts) => scripts.iter().find_map(|script| script.shift(strip_diacritics(c), shift)),
+            None => self.alphabet.shift(c, shift),
+        };
+        shifted.or_else(|| self.rotate_digit(c))
+    }
+
+    fn rotate_digit(&self, c: char) -> Option<char> {
+        let digit = c.to_digit(10).filter(|_| self.rotate_digits && c.is_ascii_digit())?;
+        char::from_digit((digit + 5) % 10, 10)
+    }
+
+    fn shift(&self, text: &str, shift: i64, policy: CharPolicy) -> Result<String> {
+        // Every letter the policy could act on is ASCII, or left alone.
+        let plain_ascii = text.is_ascii() || (policy == CharPolicy::Preserve && !text.chars().any(is_combining));
+        if self.is_latin() && plain_ascii {
+            let table = shift_table(shift, self.rotate_digits);
+            let bytes = text.bytes().map(|byte| table[byte as usize]).collect();
+            return Ok(String::from_utf8(bytes).expect("only ASCII bytes change"));
+        }
+
+        let mut out = String::with_capacity(text.len());
+
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c.is_alphabetic() && chars.peek().is_some_and(|next| is_combining(*next)) {
+                let mut marks = String::new();
+                while let Some(mark) = chars.next_if(|next| is_combining(*next)) {
+                    marks.push(mark);
+                }
+                // Unicode mode drops accents; otherwise the accented letter
+                // is outside the alphabet, like a precomposed é.
+                match (self.scripts.is_some().then(|| self.shift_char(c, shift)).flatten(), policy) {
+                    (Some(shifted), _) => out.push(shifted),
+                    (None, CharPolicy::Preserve) => {
+                        out.push(c);
+                        out.push_str(&marks);
+                    }
+                    (None, CharPolicy::Strip) => {}
+                    (None, CharPolicy::Error) => return Err(Error::UnsupportedCharacter(c)),
+                }
+                continue;
+            }
+            match (self.shift_char(c, shift), policy) {
+                (Some(shifted), _) => out.push(shifted),
+                (None, _) if !c.is_alphabetic() => out.push(c),
+                (None, CharPolicy::Preserve) => out.push(c),
+                (None, CharPolicy::Strip) => {}
+                (None, CharPolicy::Error) => return Err(Error::UnsupportedCharacter(c)),
+            }
+        }
+
+        Ok(out)
+    }
+
+    // Plain A-Z with no Unicode scripts, so ASCII bytes can be shifted
+    // directly.
+    fn is_latin(&self) -> bool {
+        self.scripts.is_none()
+            && self.alphabet.folds_case()
+            && self.alphabet.len() == 26
+            && self.alphabet.chars().iter().copied().eq('A'..='Z')
+    }
+
+    fn shift_bytes(&self, buffer: &mut [u8], shift: i64) -> Result<()> {
+        if !self.is_latin() {
+            let transform = |text: &str| self.shift(text, shift, self.lenient_policy()).unwrap_or_default();
+            return replace_ascii(buffer, transform);
+        }
+        if let Some(byte) = buffer.iter().find(|b| !b.is_ascii()) {
+            return Err(Error::UnsupportedCharacter(*byte as char));
+        }
+
+        let table = shift_table(shift, self.rotate_digits);
+        for byte in buffer.iter_mut() {
+            *byte = table[*byte as usize];
+        }
+        Ok(())
+    }
+
+    fn lenient_policy(&self) -> CharPolicy {
+        match self.policy {
+            CharPolicy::Error => CharPolicy::Preserve,
+            policy => policy,
+        }
+    }
+}
+
+impl ClassicalCipher for CaesarCipher {
+    fn encrypt(&self, plain: &str) -> String {
+        self.shift(plain, self.reduced_key(), self.lenient_policy()).unwrap_or_default()
+    }
+
+    fn decrypt(&self, cipher: &str) -> String {
+        self.shift(cipher, -self.reduced_key(), self.lenient_policy()).unwrap_or_default()
+    }
+
+    // No allocation for the usual A-Z alphabet.
+    fn encrypt_in_place(&self, buffer: &mut [u8]) -> Result<()> {
+        self.shift_bytes(buffer, self.reduced_key())
+    }
+
+    fn decrypt_in_place(&self, buffer: &mut [u8]) -> Result<()> {
+        self.shift_bytes(buffer, -self.reduced_key())
+    }
+
+    fn works_per_character(&self) -> bool {
+        true
+    }
+}
+
+// What each byte becomes under a shift of A-Z and a-z, and ROT5 for
+// digits if `rotate_digits`. Other bytes, UTF-8 continuation bytes among
+// them, map to themselves, so shifted UTF-8 stays valid.
+fn shift_table(shift: i64, rotate_digits: bool) -> [u8; 256] {
+    let shift = shift.rem_euclid(26) as u8;
+    let mut table = [0; 256];
+    for (byte, entry) in (0..=u8::MAX).zip(table.iter_mut()) {
+        *entry = match byte {
+            b'A'..=b'Z' => b'A' + (byte - b'A' + shift) % 26,
+            b'a'..=b'z' => b'a' + (byte - b'a' + shift) % 26,
+            b'0'..=b'9' if rotate_digits => b'0' + (byte - b'0' + 5) % 10,
+            _ => byte,
+        };
+    }
+    table
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// Shifts the ASCII letters of `data` `key` places, whatever else it holds,
+// through one lookup per byte. For large inputs where going through
+// `&str` costs too much; `decrypt` is a shift of `-key`.
+pub fn caesar_shift_bytes(data: &[u8], key: i64) -> Vec<u8> {
+    let table = shift_table(key, false);
+    data.iter().map(|byte| table[*byte as usize]).collect()
+}
+
+// The 26 shifts of A-Z; `brute_force_alphabet` covers other alphabets.
+impl Keyspace for CaesarCipher {
+    fn keys(_len: usize) -> impl Iterator<Item = Self> {
+        (0..26).map(CaesarCipher::new)
+    }
+
+    fn key_count(_len: usize) -> usize {
+        26
+    }
+
+    fn key_label(&self) -> String {
+        self.key.to_string()
+    }
+}
+
+// Tries all 26 keys, most English-looking first.
+pub fn brute_force(cipher: &str) -> Vec<DecodedResult> {
+    brute_force_with(cipher, &EnglishScorer)
+}
+
+// Like `brute_force`, ranked by `scorer`.
+pub fn brute_force_with(cipher: &str, scorer: &dyn Scorer) -> Vec<DecodedResult> {
+    brute_force_alphabet(cipher, &Alphabet::latin(), scorer, &BruteForceOptions::default())
+}
+
+// Like `brute_force`, scored in `options.language` and stopping or
+// filtering as `options` says.
+pub fn brute_force_with_options(cipher: &str, options: &BruteForceOptions) -> Vec<DecodedResult> {
+    brute_force_alphabet(cipher, &Alphabet::latin(), options.scorer().as_ref(), options)
+}
+
+// Tries the shifts of `alphabet` in order, ranked by `scorer`.
+pub fn brute_force_alphabet(
+    cipher: &str,
+    alphabet: &Alphabet,
+    scorer: &dyn Scorer,
+    options: &BruteForceOptions,
+) -> Vec<DecodedResult> {
+    brute_force_with_budget(cipher, alphabet, scorer, options, &Budget::unlimited())
+}
+
+// Like `brute_force_alphabet`, one unit of `budget` per key.
+pub fn brute_force_with_budget(
+    cipher: &str,
+    alphabet: &Alphabet,
+    scorer: &dyn Scorer,
+    options: &BruteForceOptions,
+    budget: &Budget,
+) -> Vec<DecodedResult> {
+    let candidates = (0..alphabet.len() as i64)
+        .map(|key| (key.to_string(), CaesarCipher::with_alphabet(key, alphabet.clone()).decrypt(cipher)));
+    brute::search(candidates, alphabet.len(), scorer, options, budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shifts_ascii_letters_and_keeps_case() {
+        let caesar = CaesarCipher::new(3);
+        assert_eq!(caesar.encrypt("Hello, World!"), "Khoor, Zruog!");
+        assert_eq!(caesar.decrypt("Khoor, Zruog!"), "Hello, World!");
+    }
+
+    #[test]
+    fn other_characters_pass_through_outside_unicode_mode() {
+        let caesar = CaesarCipher::new(7);
+        for text in ["👍", "e\u{301}", "Привет", "Καλημέρα", "中文"] {
+            assert_eq!(caesar.encrypt(text), text);
+            assert_eq!(caesar.decrypt(text), text);
+        }
+        // Only the bare ASCII letters around them move.
+        assert_eq!(caesar.encrypt("a👍e\u{301}b"), "h👍e\u{301}i");
+        assert_eq!(caesar.decrypt("h👍e\u{301}i"), "a👍e\u{301}b");
+    }
+
+    #[test]
+    fn unicode_mode_shifts_greek_and_cyrillic() {
+        let caesar = CaesarCipher::new(1).with_unicode(true);
+        assert_eq!(caesar.encrypt("αβγ ΑΩ"), "βγδ ΒΑ");
+        assert_eq!(caesar.encrypt("Привет"), "Рсйгёу");
+        assert_eq!(caesar.decrypt("Рсйгёу"), "Привет");
+        assert_eq!(caesar.encrypt("zé"), "af");
+        assert_eq!(caesar.encrypt("ze\u{301}"), "af");
+    }
+
+    #[test]
+    fn extreme_keys_round_trip() {
+        for key in [i64::MIN, i64::MIN + 1, i64::MAX] {
+            let shift = key.rem_euclid(26) as u8;
+            let expected: String = "Hello".chars().map(|c| {
+                let base = if c.is_ascii_uppercase() { b'A' } else { b'a' };
+                ((c as u8 - base + shift) % 26 + base) as char
+            }).collect();
+            let caesar = CaesarCipher::new(key);
+            assert_eq!(caesar.encrypt("Hello"), expected, "key {}", key);
+            assert_eq!(caesar.decrypt(&expected), "Hello", "key {}", key);
+            assert_eq!(caesar.try_decrypt(&expected).unwrap(), "Hello", "key {}", key);
+
+            let mut buffer = expected.clone().into_bytes();
+            caesar.decrypt_in_place(&mut buffer).unwrap();
+            assert_eq!(buffer, b"Hello", "key {}", key);
+
+            let unicode = CaesarCipher::new(key).with_unicode(true);
+            assert_eq!(unicode.decrypt(&unicode.encrypt("Привет αβγ")), "Привет αβγ", "key {}", key);
+        }
+    }
+}
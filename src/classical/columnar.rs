@@ -0,0 +1,294 @@
+use crate::analysis::{LanguageModel, QuadgramModel, Scorer};
+use crate::budget::Budget;
+use crate::classical::brute::{self, BruteForceOptions, Keyspace};
+use crate::classical::ClassicalCipher;
+use crate::error::{Error, Result};
+use crate::result::{rank, DecodedResult};
+
+// Up to this many columns every column order is tried (7! = 5040); wider
+// grids fall back to hill climbing over column swaps.
+pub const EXHAUSTIVE_MAX_COLUMNS: usize = 7;
+
+pub struct ColumnarTransposition {
+    // Column indices in the order they are read out.
+    order: Vec<usize>,
+    // When set, the last row is filled up with this character so every
+    // column has the same length.
+    pad: Option<char>,
+}
+
+impl ColumnarTransposition {
+    // `order` must be a permutation of 0..n.
+    pub fn from_order(order: Vec<usize>) -> Option<Self> {
+        Self::try_from_order(order).ok()
+    }
+
+    pub fn try_from_order(order: Vec<usize>) -> Result<Self> {
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+
+        if sorted.is_empty() || sorted.iter().enumerate().any(|(i, c)| i != *c) {
+            return Err(Error::InvalidKey(format!("column order {:?} is not a permutation of 0..n", order)));
+        }
+
+        Ok(Self { order, pad: None })
+    }
+
+    // Columns are read in alphabetical order of the keyword's letters;
+    // repeated letters are taken left to right.
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        Self::try_from_keyword(keyword).ok()
+    }
+
+    pub fn try_from_keyword(keyword: &str) -> Result<Self> {
+        if keyword.is_empty() {
+            return Err(Error::InvalidKey("the keyword is empty".to_string()));
+        }
+        if let Some(c) = keyword.chars().find(|c| !c.is_alphanumeric()) {
+            return Err(Error::InvalidKey(format!("keyword has {:?}; use letters or digits", c)));
+        }
+
+        let letters: Vec<char> = keyword.to_uppercase().chars().collect();
+        let mut order: Vec<usize> = (0..letters.len()).collect();
+        order.sort_by_key(|i| letters[*i]);
+
+        Ok(Self { order, pad: None })
+    }
+
+    pub fn with_padding(mut self, pad: char) -> Self {
+        self.pad = Some(pad);
+        self
+    }
+
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    fn column_len(&self, column: usize, text_len: usize) -> usize {
+        let columns = self.order.len();
+        text_len / columns + usize::from(column < text_len % columns)
+    }
+}
+
+impl ClassicalCipher for ColumnarTransposition {
+    fn encrypt(&self, plain: &str) -> String {
+        let mut chars: Vec<char> = plain.chars().collect();
+        let columns = self.order.len();
+
+        if let Some(pad) = self.pad {
+            while !chars.len().is_multiple_of(columns) {
+                chars.push(pad);
+            }
+        }
+
+        self.order
+            .iter()
+            .flat_map(|column| chars.iter().skip(*column).step_by(columns))
+            .collect()
+    }
+
+    fn decrypt(&self, cipher: &str) -> String {
+        let chars: Vec<char> = cipher.chars().collect();
+        let mut grid: Vec<&[char]> = vec![&[]; self.order.len()];
+        let mut start = 0;
+
+        for column in &self.order {
+            let len = self.column_len(*column, chars.len());
+            grid[*column] = &chars[start..start + len];
+            start += len;
+        }
+
+        let rows = grid.iter().map(|column| column.len()).max().unwrap_or(0);
+        let mut plain: String = (0..rows)
+            .flat_map(|row| grid.iter().filter_map(move |column| column.get(row)))
+            .collect();
+
+        // Padding never fills a whole row, so strip at most one row's worth.
+        if let Some(pad) = self.pad {
+            for _ in 1..self.order.len() {
+                if !plain.ends_with(pad) {
+                    break;
+                }
+                plain.pop();
+            }
+        }
+
+        plain
+    }
+}
+
+impl Keyspace for ColumnarTransposition {
+    // Every column order, for up to `EXHAUSTIVE_MAX_COLUMNS` columns and
+    // no more than there are characters.
+    fn keys(len: usize) -> impl Iterator<Item = Self> {
+        (1..=len.min(EXHAUSTIVE_MAX_COLUMNS))
+            .flat_map(permutations)
+            .map(|order| ColumnarTransposition { order, pad: None })
+    }
+
+    fn key_count(len: usize) -> usize {
+        (1..=len.min(EXHAUSTIVE_MAX_COLUMNS)).map(orders).sum()
+    }
+
+    fn key_label(&self) -> String {
+        key_string(&self.order)
+    }
+}
+
+// How many orders `columns` columns can be read in.
+fn orders(columns: usize) -> usize {
+    (1..=columns).product()
+}
+
+// The English quadgram model's log probability, as a `Scorer`. Every key
+// gives the same letters, so the scores compare without the scaling
+// `NgramScorer` does, and don't tie at its limits.
+struct Quadgrams;
+
+impl Scorer for Quadgrams {
+    fn score(&self, text: &str) -> f32 {
+        QuadgramModel::english().score(text) as f32
+    }
+
+    fn name(&self) -> &str {
+        "quadgram"
+    }
+}
+
+fn key_string(order: &[usize]) -> String {
+    order.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(",")
+}
+
+fn candidate(cipher: &str, order: &[usize], model: &dyn LanguageModel, budget: &Budget) -> DecodedResult {
+    let text = ColumnarTransposition { order: order.to_vec(), pad: None }.decrypt(cipher);
+    let score = model.score(&text);
+    budget.record_score(score);
+
+    DecodedResult::new(key_string(order), text, score).with_scorer("quadgram")
+}
+
+// Every permutation of 0..n, via Heap's algorithm.
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    let mut current: Vec<usize> = (0..n).collect();
+    let mut counters = vec![0; n];
+    let mut all = vec![current.clone()];
+    let mut i = 0;
+
+    while i < n {
+        if counters[i] < i {
+            let swap = if i % 2 == 0 { 0 } else { counters[i] };
+            current.swap(swap, i);
+            all.push(current.clone());
+            counters[i] += 1;
+            i = 0;
+        } else {
+            counters[i] = 0;
+            i += 1;
+        }
+    }
+
+    all
+}
+
+fn hill_climb(cipher: &str, start: Vec<usize>, model: &dyn LanguageModel, budget: &Budget) -> DecodedResult {
+    let mut order = start;
+    let mut best = candidate(cipher, &order, model, budget);
+
+    loop {
+        let mut improved = false;
+
+        for i in 0..order.len() {
+            for j in i + 1..order.len() {
+                if !budget.spend() {
+                    return best;
+                }
+
+                order.swap(i, j);
+                let next = candidate(cipher, &order, model, budget);
+                if next.score > best.score {
+                    best = next;
+                    improved = true;
+                } else {
+                    order.swap(i, j);
+                }
+            }
+        }
+
+        if !improved {
+            return best;
+        }
+    }
+}
+
+// Tries every column count in `min_columns..=max_columns` and returns the
+// best `max_results` decryptions ranked by the English quadgram model.
+pub fn brute_force(cipher: &str, min_columns: usize, max_columns: usize, max_results: usize) -> Vec<DecodedResult> {
+    brute_force_with_budget(cipher, min_columns, max_columns, max_results, &Budget::unlimited())
+}
+
+// Like `brute_force`, but stops early once the budget runs out and ranks
+// whatever was found by then.
+pub fn brute_force_with_budget(
+    cipher: &str,
+    min_columns: usize,
+    max_columns: usize,
+    max_results: usize,
+    budget: &Budget,
+) -> Vec<DecodedResult> {
+    let len = cipher.chars().count();
+    let columns = min_columns.max(1)..=max_columns.min(len);
+
+    let keys = ColumnarTransposition::keys(len).filter(|key| columns.contains(&key.order.len()));
+    let total = columns.clone().filter(|&n| n <= EXHAUSTIVE_MAX_COLUMNS).map(orders).sum();
+    let options = BruteForceOptions::default().with_threshold(f64::NEG_INFINITY);
+    let mut results = brute::brute_force_keys(cipher, keys, total, &Quadgrams, &options, budget);
+
+    let model = QuadgramModel::english();
+    'columns: for columns in columns.filter(|&n| n > EXHAUSTIVE_MAX_COLUMNS) {
+        for shift in 0..columns {
+            if budget.is_exhausted() {
+                break 'columns;
+            }
+            let start = (0..columns).map(|c| (c + shift) % columns).collect();
+            results.push(hill_climb(cipher, start, model, budget));
+        }
+    }
+
+    budget.report();
+    rank(&mut results);
+    results.dedup_by(|a, b| a.text == b.text);
+    results.truncate(max_results);
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_columns_in_keyword_order() {
+        let columnar = ColumnarTransposition::from_keyword("ZEBRAS").unwrap();
+        assert_eq!(columnar.encrypt("WEAREDISCOVEREDFLEEATONCE"), "EVLNACDTESEAROFODEECWIREE");
+        assert_eq!(columnar.decrypt("EVLNACDTESEAROFODEECWIREE"), "WEAREDISCOVEREDFLEEATONCE");
+    }
+
+    #[test]
+    fn pads_the_last_row() {
+        let columnar = ColumnarTransposition::from_keyword("ZEBRAS").unwrap().with_padding('Q');
+        assert_eq!(columnar.encrypt("WEAREDISCOVEREDFLEEATONCE"), "EVLNQACDTQESEAQROFOQDEECQWIREE");
+    }
+
+    #[test]
+    fn keyspace_holds_every_order() {
+        assert_eq!(ColumnarTransposition::keys(100).count(), ColumnarTransposition::key_count(100));
+        assert_eq!(ColumnarTransposition::key_count(3), 1 + 2 + 6);
+    }
+
+    #[test]
+    fn brute_force_finds_the_order() {
+        let plain = "the quick brown fox jumps over the lazy dog and then runs far away";
+        let cipher = ColumnarTransposition::try_from_keyword("CAB").unwrap().encrypt(plain);
+        let best = &brute_force(&cipher, 2, 6, 1)[0];
+        assert_eq!((best.key.as_str(), best.text.as_str()), ("1,2,0", plain));
+    }
+}
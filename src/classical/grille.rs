@@ -0,0 +1,180 @@
+use crate::classical::ClassicalCipher;
+use crate::error::{Error, Result};
+use crate::random::Rng;
+
+const HOLE: char = 'X';
+const SOLID: char = '.';
+
+// A Cardan turning grille: a card with holes over a `size` by `size`
+// grid. The plaintext is written into the holes row by row, the card is
+// turned a quarter clockwise, and so on four times; the holes of the four
+// turns must uncover every cell exactly once. The ciphertext is the grid
+// read row by row. Longer texts take a fresh grid for every size² letters;
+// a short last grid is left unfilled where writing stopped, and those
+// cells are skipped when reading.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TurningGrille {
+    size: usize,
+    // (row, column), row by row.
+    holes: Vec<(usize, usize)>,
+    // Cells in the order they are written, over all four turns.
+    order: Vec<usize>,
+}
+
+impl TurningGrille {
+    pub fn new(size: usize, holes: Vec<(usize, usize)>) -> Option<Self> {
+        Self::try_new(size, holes).ok()
+    }
+
+    // `size` must be even and the holes' four turns must cover the grid.
+    pub fn try_new(size: usize, mut holes: Vec<(usize, usize)>) -> Result<Self> {
+        if size == 0 || size % 2 == 1 {
+            return Err(Error::InvalidKey(format!("a turning grille needs an even size, not {}", size)));
+        }
+        if let Some((row, column)) = holes.iter().find(|(row, column)| *row >= size || *column >= size) {
+            return Err(Error::InvalidKey(format!("hole ({}, {}) is outside the {}x{} grid", row, column, size, size)));
+        }
+        holes.sort_unstable();
+        holes.dedup();
+
+        let mut covered = vec![false; size * size];
+        let mut order = Vec::with_capacity(size * size);
+        let mut turned = holes.clone();
+        for _ in 0..4 {
+            turned.sort_unstable();
+            for (row, column) in &turned {
+                let cell = row * size + column;
+                if covered[cell] {
+                    return Err(Error::InvalidKey(format!("cell ({}, {}) is uncovered twice", row, column)));
+                }
+                covered[cell] = true;
+                order.push(cell);
+            }
+            turned = turned.iter().map(|(row, column)| (*column, size - 1 - row)).collect();
+        }
+        if let Some(cell) = covered.iter().position(|c| !c) {
+            return Err(Error::InvalidKey(format!("cell ({}, {}) is never uncovered", cell / size, cell % size)));
+        }
+
+        Ok(Self { size, holes, order })
+    }
+
+    // Rows of `X` for holes and `.` for card, separated by `/` or new
+    // lines: `X.../X..X/..X./....`. `#`, `O` and `1` also mark holes.
+    pub fn from_mask(mask: &str) -> Option<Self> {
+        Self::try_from_mask(mask).ok()
+    }
+
+    pub fn try_from_mask(mask: &str) -> Result<Self> {
+        let rows: Vec<&str> = mask.split(['/', '\n']).map(str::trim).filter(|row| !row.is_empty()).collect();
+        let size = rows.len();
+        let mut holes = Vec::new();
+
+        for (r, row) in rows.iter().enumerate() {
+            if row.chars().count() != size {
+                return Err(Error::InvalidKey(format!("grille row `{}` should have {} cells", row, size)));
+            }
+            for (c, cell) in row.chars().enumerate() {
+                match cell.to_ascii_uppercase() {
+                    HOLE | '#' | 'O' | '1' => holes.push((r, c)),
+                    SOLID | '-' | '0' => {}
+                    other => return Err(Error::InvalidKey(format!("{:?} is neither a hole nor card", other))),
+                }
+            }
+        }
+        Self::try_new(size, holes)
+    }
+
+    // A random valid grille: each cell of the top-left quarter picks which
+    // of its four turns is the hole.
+    pub fn random(size: usize, rng: &mut Rng) -> Result<Self> {
+        if size == 0 || size % 2 == 1 {
+            return Err(Error::InvalidKey(format!("a turning grille needs an even size, not {}", size)));
+        }
+        let half = size / 2;
+        let holes = (0..half)
+            .flat_map(|row| (0..half).map(move |column| (row, column)))
+            .map(|(mut row, mut column)| {
+                for _ in 0..rng.below(4) {
+                    (row, column) = (column, size - 1 - row);
+                }
+                (row, column)
+            })
+            .collect();
+        Self::try_new(size, holes)
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn holes(&self) -> &[(usize, usize)] {
+        &self.holes
+    }
+
+    // The form `from_mask` reads.
+    pub fn mask(&self) -> String {
+        let rows: Vec<String> = (0..self.size)
+            .map(|row| {
+                (0..self.size).map(|column| if self.holes.contains(&(row, column)) { HOLE } else { SOLID }).collect()
+            })
+            .collect();
+        rows.join("/")
+    }
+
+    // The cells a grid of `len` letters fills, in writing order.
+    fn written(&self, len: usize) -> &[usize] {
+        &self.order[..len]
+    }
+}
+
+impl ClassicalCipher for TurningGrille {
+    fn encrypt(&self, plain: &str) -> String {
+        let chars: Vec<char> = plain.chars().collect();
+        let mut cipher = String::with_capacity(plain.len());
+
+        for block in chars.chunks(self.size * self.size) {
+            let mut grid = vec![None; self.size * self.size];
+            for (c, cell) in block.iter().zip(self.written(block.len())) {
+                grid[*cell] = Some(*c);
+            }
+            cipher.extend(grid.into_iter().flatten());
+        }
+        cipher
+    }
+
+    fn decrypt(&self, cipher: &str) -> String {
+        let chars: Vec<char> = cipher.chars().collect();
+        let mut plain = String::with_capacity(cipher.len());
+
+        for block in chars.chunks(self.size * self.size) {
+            let written = self.written(block.len());
+            let mut filled = written.to_vec();
+            filled.sort_unstable();
+
+            let mut grid = vec![' '; self.size * self.size];
+            for (c, cell) in block.iter().zip(filled) {
+                grid[cell] = *c;
+            }
+            plain.extend(written.iter().map(|cell| grid[*cell]));
+        }
+        plain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_the_grid_in_four_turns() {
+        let grille = TurningGrille::try_from_mask("X.../X..X/..X./....").unwrap();
+        assert_eq!(grille.encrypt("ABCDEFGHIJKLMNOP"), "AMEFBINCJGDKOPHL");
+        assert_eq!(grille.decrypt("AMEFBINCJGDKOPHL"), "ABCDEFGHIJKLMNOP");
+    }
+
+    #[test]
+    fn rejects_holes_that_overlap_when_turned() {
+        assert!(TurningGrille::try_from_mask("X..X/..../..../....").is_err());
+    }
+}
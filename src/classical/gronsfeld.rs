@@ -0,0 +1,80 @@
+use std::io::{self, Read, Write};
+
+use crate::classical::stream::transform_chunks;
+use crate::classical::vigenere::shift_letter;
+use crate::classical::ClassicalCipher;
+use crate::error::{Error, Result};
+
+// Vigenère with a numeric key: each digit is a shift of 0 to 9.
+pub struct GronsfeldCipher {
+    key: String,
+    shifts: Vec<i32>,
+}
+
+impl GronsfeldCipher {
+    pub fn new(key: &str) -> Option<Self> {
+        Self::try_new(key).ok()
+    }
+
+    pub fn try_new(key: &str) -> Result<Self> {
+        if key.is_empty() {
+            return Err(Error::InvalidKey("the key is empty".to_string()));
+        }
+        let shifts = key
+            .chars()
+            .map(|c| c.to_digit(10).map(|d| d as i32))
+            .collect::<Option<Vec<i32>>>()
+            .ok_or_else(|| Error::InvalidKey(format!("`{}` is not a string of digits", key)))?;
+        Ok(Self { key: key.to_string(), shifts })
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    // `position` counts letters already handled, as in Vigenère.
+    fn apply(&self, text: &str, direction: i32, position: &mut usize) -> String {
+        text.chars()
+            .map(|c| {
+                if !c.is_ascii_alphabetic() {
+                    return c;
+                }
+                let key = self.shifts[*position % self.shifts.len()];
+                *position += 1;
+                shift_letter(c, direction * key).unwrap_or(c)
+            })
+            .collect()
+    }
+}
+
+impl ClassicalCipher for GronsfeldCipher {
+    fn encrypt(&self, plain: &str) -> String {
+        self.apply(plain, 1, &mut 0)
+    }
+
+    fn decrypt(&self, cipher: &str) -> String {
+        self.apply(cipher, -1, &mut 0)
+    }
+
+    fn encrypt_reader(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> io::Result<()> {
+        let mut position = 0;
+        transform_chunks(reader, writer, |chunk| self.apply(chunk, 1, &mut position))
+    }
+
+    fn decrypt_reader(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> io::Result<()> {
+        let mut position = 0;
+        transform_chunks(reader, writer, |chunk| self.apply(chunk, -1, &mut position))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shifts_by_the_digits() {
+        let gronsfeld = GronsfeldCipher::try_new("31415").unwrap();
+        assert_eq!(gronsfeld.encrypt("GRONSFELD"), "JSSOXIFPE");
+        assert_eq!(gronsfeld.decrypt("JSSOXIFPE"), "GRONSFELD");
+    }
+}
@@ -0,0 +1,119 @@
+use std::io::{self, Read, Write};
+
+use crate::diagnostics::Diagnostics;
+use crate::error::{Error, Result};
+
+pub mod affine;
+pub mod alphabet;
+pub mod atbash;
+pub mod autokey;
+pub mod beaufort;
+pub mod bifid;
+pub mod book;
+pub mod brute;
+pub mod byte_shift;
+pub mod caesar;
+pub mod columnar;
+pub mod grille;
+pub mod gronsfeld;
+pub mod nomenclator;
+pub mod playfair;
+pub mod polybius;
+pub mod porta;
+pub mod route;
+mod stream;
+pub mod substitution;
+pub mod transform;
+pub mod trifid;
+pub mod vigenere;
+
+pub use affine::AffineCipher;
+pub use alphabet::{Alphabet, CharPolicy};
+pub use atbash::AtbashCipher;
+pub use autokey::AutokeyCipher;
+pub use beaufort::BeaufortCipher;
+pub use bifid::BifidCipher;
+pub use book::{BookCipher, BookStyle};
+pub use brute::{brute_force_cipher, BruteForceOptions, Keyspace};
+pub use byte_shift::{ByteCandidate, ByteShiftCipher};
+pub use caesar::CaesarCipher;
+pub use columnar::ColumnarTransposition;
+pub use grille::TurningGrille;
+pub use gronsfeld::GronsfeldCipher;
+pub use nomenclator::Nomenclator;
+pub use playfair::PlayfairCipher;
+pub use polybius::PolybiusSquare;
+pub use porta::PortaCipher;
+pub use route::{Route, RouteCipher};
+pub use substitution::SubstitutionCipher;
+pub use transform::CharTransform;
+pub use trifid::TrifidCipher;
+pub use vigenere::{VigenereCipher, VigenereSolver};
+
+pub trait ClassicalCipher {
+    fn encrypt(&self, plain: &str) -> String;
+    fn decrypt(&self, cipher: &str) -> String;
+
+    // Like `encrypt` and `decrypt`, reporting dropped or unreadable input
+    // to `diagnostics`. Ciphers that pass everything through needn't
+    // override them.
+    fn encrypt_with_diagnostics(&self, plain: &str, _diagnostics: &Diagnostics) -> String {
+        self.encrypt(plain)
+    }
+
+    fn decrypt_with_diagnostics(&self, cipher: &str, _diagnostics: &Diagnostics) -> String {
+        self.decrypt(cipher)
+    }
+
+    // True when every character encrypts the same way wherever it is, so
+    // any piece of a text can be handled on its own and the reader methods
+    // can stream.
+    fn works_per_character(&self) -> bool {
+        false
+    }
+
+    // Encrypts everything `reader` yields into `writer`: in fixed-size
+    // chunks when the cipher works per character or overrides this, and
+    // by reading the whole input first otherwise.
+    fn encrypt_reader(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> io::Result<()> {
+        if self.works_per_character() {
+            stream::transform_chunks(reader, writer, |chunk| self.encrypt(chunk))
+        } else {
+            stream::transform_all(reader, writer, |text| self.encrypt(text))
+        }
+    }
+
+    fn decrypt_reader(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> io::Result<()> {
+        if self.works_per_character() {
+            stream::transform_chunks(reader, writer, |chunk| self.decrypt(chunk))
+        } else {
+            stream::transform_all(reader, writer, |text| self.decrypt(text))
+        }
+    }
+
+    // Encrypts an ASCII buffer where it is. The default goes through
+    // `encrypt` and copies the result back, so it still allocates; ciphers
+    // that map bytes to bytes override it. Fails, leaving the buffer as it
+    // was, on non-ASCII input or when the cipher changes the length.
+    fn encrypt_in_place(&self, buffer: &mut [u8]) -> Result<()> {
+        replace_ascii(buffer, |text| self.encrypt(text))
+    }
+
+    fn decrypt_in_place(&self, buffer: &mut [u8]) -> Result<()> {
+        replace_ascii(buffer, |text| self.decrypt(text))
+    }
+}
+
+pub(crate) fn replace_ascii(buffer: &mut [u8], f: impl FnOnce(&str) -> String) -> Result<()> {
+    if let Some(byte) = buffer.iter().find(|b| !b.is_ascii()) {
+        return Err(Error::UnsupportedCharacter(*byte as char));
+    }
+
+    let text = std::str::from_utf8(buffer).expect("ASCII is valid UTF-8");
+    let result = f(text);
+    if result.len() != buffer.len() {
+        return Err(Error::Unsupported("the cipher changes the text's length".to_string()));
+    }
+    buffer.copy_from_slice(result.as_bytes());
+    Ok(())
+}
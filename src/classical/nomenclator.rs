@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::classical::ClassicalCipher;
+use crate::diagnostics::{Diagnostics, Warning};
+use crate::error::{Error, Result};
+
+pub const WORD_SEPARATOR: &str = "/";
+
+// A codebook of words, syllables and single letters, each with one or more
+// code groups. Single letters make up the homophone alphabet used to spell
+// anything the codebook has no entry for.
+//
+// Codebook files have one entry per line, `<plain> <group> [<group> ...]`;
+// blank lines and lines starting with `#` are ignored.
+pub struct Nomenclator {
+    groups: HashMap<String, Vec<String>>,
+    plain: HashMap<String, String>,
+    longest_entry: usize,
+    unknown_marker: String,
+}
+
+impl Nomenclator {
+    // Codebook mistakes are `Error::InvalidKey`, naming the line.
+    pub fn from_codebook(codebook: &str) -> Result<Self> {
+        let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+        let mut plain = HashMap::new();
+
+        for (i, line) in codebook.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let entry = fields.next().unwrap_or_default().to_lowercase();
+            let codes: Vec<String> = fields.map(str::to_string).collect();
+            if codes.is_empty() {
+                return Err(Error::InvalidKey(format!("codebook line {}: `{}` has no code groups", i + 1, entry)));
+            }
+
+            for code in &codes {
+                if let Some(existing) = plain.insert(code.clone(), entry.clone()) {
+                    let message = format!("group `{}` already stands for `{}`", code, existing);
+                    return Err(Error::InvalidKey(format!("codebook line {}: {}", i + 1, message)));
+                }
+            }
+            groups.entry(entry).or_default().extend(codes);
+        }
+
+        let longest_entry = groups.keys().map(|entry| entry.chars().count()).max().unwrap_or(0);
+
+        Ok(Self { groups, plain, longest_entry, unknown_marker: "[?{}]".to_string() })
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_codebook(&fs::read_to_string(path)?)
+    }
+
+    // `{}` in the marker is replaced with the unknown group.
+    pub fn set_unknown_marker(&mut self, marker: &str) {
+        self.unknown_marker = marker.to_string();
+    }
+
+    pub fn entries(&self) -> usize {
+        self.groups.len()
+    }
+
+    // Greedy longest match against the codebook; homophones are used in
+    // turn so repeated entries don't repeat the same group.
+    fn encrypt_word(
+        &self,
+        word: &str,
+        used: &mut HashMap<String, usize>,
+        diagnostics: Option<&Diagnostics>,
+    ) -> Vec<String> {
+        let chars: Vec<char> = word.to_lowercase().chars().collect();
+        let mut codes = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let longest = self.longest_entry.min(chars.len() - i);
+            let found = (1..=longest).rev().find_map(|len| {
+                let piece: String = chars[i..i + len].iter().collect();
+                self.groups.get(&piece).map(|groups| (piece, groups, len))
+            });
+
+            match found {
+                Some((piece, groups, len)) => {
+                    let turn = used.entry(piece).or_insert(0);
+                    codes.push(groups[*turn % groups.len()].clone());
+                    *turn += 1;
+                    i += len;
+                }
+                None => {
+                    if let Some(diagnostics) = diagnostics {
+                        diagnostics.warn(Warning::SkippedCharacter(chars[i]));
+                    }
+                    i += 1;
+                }
+            }
+        }
+
+        codes
+    }
+
+    pub fn decrypt_strict(&self, cipher: &str) -> Result<String> {
+        let mut words = Vec::new();
+
+        for word in cipher.split(WORD_SEPARATOR) {
+            let mut decoded = String::new();
+            for group in word.split_whitespace() {
+                let unknown = || Error::Unsupported(format!("code group `{}` is not in the codebook", group));
+                let entry = self.plain.get(group).ok_or_else(unknown)?;
+                decoded.push_str(entry);
+            }
+            if !decoded.is_empty() {
+                words.push(decoded);
+            }
+        }
+
+        Ok(words.join(" "))
+    }
+}
+
+impl Nomenclator {
+    fn encrypt_reporting(&self, plain: &str, diagnostics: Option<&Diagnostics>) -> String {
+        let mut used = HashMap::new();
+
+        plain
+            .split_whitespace()
+            .map(|word| self.encrypt_word(word, &mut used, diagnostics).join(" "))
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<String>>()
+            .join(&format!(" {} ", WORD_SEPARATOR))
+    }
+
+    fn decrypt_reporting(&self, cipher: &str, diagnostics: Option<&Diagnostics>) -> String {
+        let mut position = 0;
+
+        cipher
+            .split(WORD_SEPARATOR)
+            .map(|word| {
+                word.split_whitespace()
+                    .map(|group| {
+                        let entry = match self.plain.get(group) {
+                            Some(entry) => entry.clone(),
+                            None => {
+                                if let Some(diagnostics) = diagnostics {
+                                    diagnostics.warn(Warning::SkippedToken { position, token: group.to_string() });
+                                }
+                                self.unknown_marker.replace("{}", group)
+                            }
+                        };
+                        position += 1;
+                        entry
+                    })
+                    .collect::<String>()
+            })
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+impl ClassicalCipher for Nomenclator {
+    // Groups within a word are separated by spaces and words by " / ".
+    // Characters with no entry and no alphabet letter are dropped.
+    fn encrypt(&self, plain: &str) -> String {
+        self.encrypt_reporting(plain, None)
+    }
+
+    // Partial decode: groups missing from the codebook become the unknown
+    // marker instead of failing the whole message.
+    fn decrypt(&self, cipher: &str) -> String {
+        self.decrypt_reporting(cipher, None)
+    }
+
+    fn encrypt_with_diagnostics(&self, plain: &str, diagnostics: &Diagnostics) -> String {
+        self.encrypt_reporting(plain, Some(diagnostics))
+    }
+
+    fn decrypt_with_diagnostics(&self, cipher: &str, diagnostics: &Diagnostics) -> String {
+        self.decrypt_reporting(cipher, Some(diagnostics))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODEBOOK: &str = "# plain groups\nking 101 102\ne 11\nh 12\nt 13";
+
+    #[test]
+    fn uses_codewords_and_spells_the_rest() {
+        let nomenclator = Nomenclator::from_codebook(CODEBOOK).unwrap();
+        assert_eq!(nomenclator.decrypt("13 12 11 / 101"), "the king");
+        assert_eq!(nomenclator.decrypt(&nomenclator.encrypt("the king")), "the king");
+    }
+
+    #[test]
+    fn reports_the_bad_line() {
+        match Nomenclator::from_codebook("king 101\nqueen") {
+            Err(Error::InvalidKey(message)) => assert_eq!(message, "codebook line 2: `queen` has no code groups"),
+            other => panic!("expected a parse error, got {:?}", other.map(|n| n.entries())),
+        }
+    }
+}
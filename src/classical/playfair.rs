@@ -0,0 +1,131 @@
+use crate::classical::polybius::PolybiusSquare;
+use crate::classical::ClassicalCipher;
+
+const PAD: char = 'X';
+// Used instead of PAD when the doubled letter is PAD itself.
+const ALTERNATE_PAD: char = 'Q';
+
+// 5x5 key square with I and J sharing a cell. Only letters are kept, in
+// upper case, and J is written as I.
+pub struct PlayfairCipher {
+    square: PolybiusSquare,
+}
+
+impl PlayfairCipher {
+    pub fn new(keyword: &str) -> Self {
+        Self { square: PolybiusSquare::five_by_five(keyword) }
+    }
+
+    pub fn square(&self) -> Vec<String> {
+        self.square.rows()
+    }
+
+    fn position(&self, c: char) -> (usize, usize) {
+        match self.square.coordinates(c).as_deref() {
+            Some(&[row, column]) => (row, column),
+            _ => (0, 0),
+        }
+    }
+
+    fn at(&self, row: usize, column: usize) -> char {
+        self.square.at(&[row, column])
+    }
+
+    // Same row: shift right; same column: shift down; otherwise swap
+    // columns. `step` is 1 to encrypt and 4 (one back) to decrypt.
+    fn pair(&self, a: char, b: char, step: usize) -> [char; 2] {
+        let (ra, ca) = self.position(a);
+        let (rb, cb) = self.position(b);
+
+        if ra == rb {
+            [self.at(ra, ca + step), self.at(rb, cb + step)]
+        } else if ca == cb {
+            [self.at(ra + step, ca), self.at(rb + step, cb)]
+        } else {
+            [self.at(ra, cb), self.at(rb, ca)]
+        }
+    }
+}
+
+fn normalize(c: char) -> Option<char> {
+    match c.to_ascii_uppercase() {
+        'J' => Some('I'),
+        c if c.is_ascii_uppercase() => Some(c),
+        _ => None,
+    }
+}
+
+// Splits letters into digraphs, padding doubled letters and an odd tail.
+pub fn digraphs(text: &str) -> Vec<[char; 2]> {
+    let letters: Vec<char> = text.chars().filter_map(normalize).collect();
+    let mut pairs = Vec::new();
+    let mut i = 0;
+
+    while i < letters.len() {
+        let a = letters[i];
+        let pad = if a == PAD { ALTERNATE_PAD } else { PAD };
+
+        match letters.get(i + 1) {
+            Some(b) if *b != a => {
+                pairs.push([a, *b]);
+                i += 2;
+            }
+            _ => {
+                pairs.push([a, pad]);
+                i += 1;
+            }
+        }
+    }
+
+    pairs
+}
+
+// Undoes what `digraphs` inserted: a pad between two equal letters and a
+// pad at the very end.
+pub fn strip_padding(pairs: &[[char; 2]]) -> String {
+    let mut text = String::new();
+
+    for (i, [a, b]) in pairs.iter().enumerate() {
+        text.push(*a);
+
+        let is_pad = *b == PAD || *b == ALTERNATE_PAD;
+        let doubled = pairs.get(i + 1).is_some_and(|next| next[0] == *a);
+        let last = i + 1 == pairs.len();
+        if !(is_pad && (doubled || last)) {
+            text.push(*b);
+        }
+    }
+
+    text
+}
+
+impl ClassicalCipher for PlayfairCipher {
+    fn encrypt(&self, plain: &str) -> String {
+        digraphs(plain)
+            .iter()
+            .flat_map(|[a, b]| self.pair(*a, *b, 1))
+            .collect()
+    }
+
+    fn decrypt(&self, cipher: &str) -> String {
+        let letters: Vec<char> = cipher.chars().filter_map(normalize).collect();
+        let pairs: Vec<[char; 2]> = letters
+            .chunks(2)
+            .map(|pair| self.pair(pair[0], *pair.get(1).unwrap_or(&PAD), 4))
+            .collect();
+
+        strip_padding(&pairs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_wikipedias_example() {
+        let playfair = PlayfairCipher::new("PLAYFAIR EXAMPLE");
+        assert_eq!(playfair.encrypt("Hide the gold in the tree stump"), "BMODZBXDNABEKUDMUIXMMOUVIF");
+        assert_eq!(playfair.decrypt("BMODZBXDNABEKUDMUIXMMOUVIF"), "HIDETHEGOLDINTHETREESTUMP");
+    }
+}
@@ -0,0 +1,121 @@
+// Keyed Polybius squares, and cubes, shared by the ciphers that turn
+// letters into coordinates: Playfair, Bifid and Trifid.
+
+// The 27th symbol of a Trifid cube.
+pub const TRIFID_EXTRA: char = '+';
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolybiusSquare {
+    cells: Vec<char>,
+    side: usize,
+    dimensions: usize,
+    // Letters folded into another before lookup (J into I in a 5x5 square).
+    merged: Option<(char, char)>,
+}
+
+impl PolybiusSquare {
+    // 5x5 with I and J sharing a cell: the keyword's letters first, then
+    // the rest of the alphabet.
+    pub fn five_by_five(keyword: &str) -> Self {
+        Self::keyed(keyword, 5, 2, Some(('J', 'I')))
+    }
+
+    // 3x3x3 holding A-Z and `TRIFID_EXTRA`.
+    pub fn cube(keyword: &str) -> Self {
+        Self::keyed(keyword, 3, 3, None)
+    }
+
+    fn keyed(keyword: &str, side: usize, dimensions: usize, merged: Option<(char, char)>) -> Self {
+        let mut square = Self { cells: Vec::new(), side, dimensions, merged };
+        let size = side.pow(dimensions as u32);
+        let fill = ('A'..='Z').chain((size > 26).then_some(TRIFID_EXTRA));
+
+        let letters: Vec<char> = keyword.chars().chain(fill).filter_map(|c| square.normalize(c)).collect();
+        for c in letters {
+            if !square.cells.contains(&c) {
+                square.cells.push(c);
+            }
+        }
+        square
+    }
+
+    pub fn side(&self) -> usize {
+        self.side
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    // Upper case, with merged letters folded; None for anything the square
+    // has no cell for.
+    pub fn normalize(&self, c: char) -> Option<char> {
+        let c = c.to_ascii_uppercase();
+        let c = match self.merged {
+            Some((from, to)) if c == from => to,
+            _ => c,
+        };
+        let extra = self.side.pow(self.dimensions as u32) > 26 && c == TRIFID_EXTRA;
+        (c.is_ascii_uppercase() || extra).then_some(c)
+    }
+
+    // Row, column (and layer, for a cube), most significant first.
+    pub fn coordinates(&self, c: char) -> Option<Vec<usize>> {
+        let c = self.normalize(c)?;
+        let mut index = self.cells.iter().position(|cell| *cell == c)?;
+        let mut coordinates = vec![0; self.dimensions];
+        for coordinate in coordinates.iter_mut().rev() {
+            *coordinate = index % self.side;
+            index /= self.side;
+        }
+        Some(coordinates)
+    }
+
+    // Coordinates wrap around, so Playfair can step off an edge.
+    pub fn at(&self, coordinates: &[usize]) -> char {
+        let index = coordinates.iter().fold(0, |index, c| index * self.side + c % self.side);
+        self.cells[index]
+    }
+
+    // The rows, top to bottom; a cube's layers follow one another.
+    pub fn rows(&self) -> Vec<String> {
+        self.cells.chunks(self.side).map(|row| row.iter().collect()).collect()
+    }
+}
+
+// Delastelle's fractionation, as used by Bifid and Trifid. Each block of
+// `period` letters (the whole text when 0) is written as its coordinates,
+// one line per axis, and the lines are read back in groups as new letters.
+// Characters the square has no cell for are dropped.
+pub(crate) fn fractionate(square: &PolybiusSquare, text: &str, period: usize) -> String {
+    let letters: Vec<Vec<usize>> = text.chars().filter_map(|c| square.coordinates(c)).collect();
+    let period = if period == 0 { letters.len().max(1) } else { period };
+
+    letters
+        .chunks(period)
+        .flat_map(|block| {
+            let axes = 0..square.dimensions();
+            let digits: Vec<usize> = axes.flat_map(|axis| block.iter().map(move |c| c[axis])).collect();
+            digits.chunks(square.dimensions()).map(|c| square.at(c)).collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+pub(crate) fn unfractionate(square: &PolybiusSquare, text: &str, period: usize) -> String {
+    let letters: Vec<Vec<usize>> = text.chars().filter_map(|c| square.coordinates(c)).collect();
+    let period = if period == 0 { letters.len().max(1) } else { period };
+
+    letters
+        .chunks(period)
+        .flat_map(|block| {
+            let digits: Vec<usize> = block.iter().flatten().copied().collect();
+            let n = block.len();
+            (0..n)
+                .map(|i| {
+                    let coordinates: Vec<usize> = (0..square.dimensions()).map(|axis| digits[axis * n + i]).collect();
+                    square.at(&coordinates)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
@@ -0,0 +1,76 @@
+use std::io::{self, Read, Write};
+
+use crate::classical::stream::transform_chunks;
+use crate::classical::vigenere::{keyword_shifts, letter_index, shift_letter};
+use crate::classical::ClassicalCipher;
+use crate::error::Result;
+
+// Della Porta's reciprocal tableau: each pair of key letters (AB, CD, ...)
+// picks one of 13 alphabets that swap A-M with a rotation of N-Z, so the
+// same operation decrypts.
+pub struct PortaCipher {
+    keyword: String,
+    shifts: Vec<i32>,
+}
+
+impl PortaCipher {
+    pub fn new(keyword: &str) -> Option<Self> {
+        Self::try_new(keyword).ok()
+    }
+
+    pub fn try_new(keyword: &str) -> Result<Self> {
+        let shifts = keyword_shifts(keyword)?.into_iter().map(|shift| shift / 2).collect();
+        Ok(Self { keyword: keyword.to_ascii_uppercase(), shifts })
+    }
+
+    pub fn keyword(&self) -> &str {
+        &self.keyword
+    }
+
+    // `position` counts letters already handled, as in Vigenère.
+    fn apply(&self, text: &str, position: &mut usize) -> String {
+        text.chars()
+            .map(|c| {
+                if !c.is_ascii_alphabetic() {
+                    return c;
+                }
+                let key = self.shifts[*position % self.shifts.len()];
+                *position += 1;
+                let index = letter_index(c);
+                let target = if index < 13 { 13 + (index + key) % 13 } else { (index - 13 - key).rem_euclid(13) };
+                shift_letter(c, target - index).unwrap_or(c)
+            })
+            .collect()
+    }
+}
+
+impl ClassicalCipher for PortaCipher {
+    fn encrypt(&self, plain: &str) -> String {
+        self.apply(plain, &mut 0)
+    }
+
+    fn decrypt(&self, cipher: &str) -> String {
+        self.apply(cipher, &mut 0)
+    }
+
+    fn encrypt_reader(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> io::Result<()> {
+        let mut position = 0;
+        transform_chunks(reader, writer, |chunk| self.apply(chunk, &mut position))
+    }
+
+    fn decrypt_reader(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> io::Result<()> {
+        self.encrypt_reader(reader, writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swaps_alphabet_halves_by_key_letter() {
+        let porta = PortaCipher::try_new("FORTIFICATION").unwrap();
+        assert_eq!(porta.encrypt("DEFENDTHEEASTWALLOFTHECASTLE"), "SYNNJSCVRNRLAHUTUKUCVRYRLANY");
+        assert_eq!(porta.decrypt("SYNNJSCVRNRLAHUTUKUCVRYRLANY"), "DEFENDTHEEASTWALLOFTHECASTLE");
+    }
+}
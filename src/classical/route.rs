@@ -0,0 +1,265 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::analysis::{NgramScorer, Scorer};
+use crate::budget::Budget;
+use crate::classical::brute::{self, BruteForceOptions, Keyspace};
+use crate::classical::ClassicalCipher;
+use crate::error::{Error, Result};
+use crate::result::DecodedResult;
+
+pub const ROUTE_NAMES: &[&str] =
+    &["columns", "boustrophedon", "column-boustrophedon", "spiral", "counter-spiral", "diagonal"];
+
+// The order a route cipher reads its grid in, always from the top left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Route {
+    // Down each column, left to right: the Caesar box.
+    #[default]
+    Columns,
+    // Along the rows, turning back at the end of each.
+    Boustrophedon,
+    // Down the first column, up the next, and so on.
+    ColumnBoustrophedon,
+    // Clockwise round the edge and inwards.
+    Spiral,
+    // Anticlockwise round the edge and inwards.
+    CounterSpiral,
+    // Along the diagonals running up and right, each read from the top.
+    Diagonal,
+}
+
+impl Route {
+    pub const ALL: [Route; 6] = [
+        Route::Columns,
+        Route::Boustrophedon,
+        Route::ColumnBoustrophedon,
+        Route::Spiral,
+        Route::CounterSpiral,
+        Route::Diagonal,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Route::Columns => "columns",
+            Route::Boustrophedon => "boustrophedon",
+            Route::ColumnBoustrophedon => "column-boustrophedon",
+            Route::Spiral => "spiral",
+            Route::CounterSpiral => "counter-spiral",
+            Route::Diagonal => "diagonal",
+        }
+    }
+
+    // The cells of a `rows` by `columns` grid in route order, numbered
+    // row by row from 0.
+    pub fn path(&self, rows: usize, columns: usize) -> Vec<usize> {
+        let cell = |row: usize, column: usize| row * columns + column;
+
+        match self {
+            Route::Columns => (0..columns).flat_map(|c| (0..rows).map(move |r| cell(r, c))).collect(),
+            Route::Boustrophedon => (0..rows)
+                .flat_map(|r| {
+                    let row: Vec<usize> = (0..columns).map(|c| cell(r, c)).collect();
+                    if r % 2 == 0 { row } else { row.into_iter().rev().collect() }
+                })
+                .collect(),
+            Route::ColumnBoustrophedon => (0..columns)
+                .flat_map(|c| {
+                    let column: Vec<usize> = (0..rows).map(|r| cell(r, c)).collect();
+                    if c % 2 == 0 { column } else { column.into_iter().rev().collect() }
+                })
+                .collect(),
+            Route::Spiral | Route::CounterSpiral => {
+                let mut path = Vec::with_capacity(rows * columns);
+                let (mut top, mut left) = (0, 0);
+                let (mut bottom, mut right) = (rows, columns);
+
+                while top < bottom && left < right {
+                    // The ring between the bounds; a single row or column
+                    // is walked once.
+                    let mut ring: Vec<usize> = (left..right).map(|c| cell(top, c)).collect();
+                    ring.extend((top + 1..bottom).map(|r| cell(r, right - 1)));
+                    if bottom - top > 1 && right - left > 1 {
+                        ring.extend((left..right - 1).rev().map(|c| cell(bottom - 1, c)));
+                        ring.extend((top + 1..bottom - 1).rev().map(|r| cell(r, left)));
+                    }
+                    // The same ring the other way round, still from its corner.
+                    if *self == Route::CounterSpiral {
+                        ring[1..].reverse();
+                    }
+                    path.extend(ring);
+
+                    (top, left, bottom, right) = (top + 1, left + 1, bottom - 1, right - 1);
+                }
+                path
+            }
+            Route::Diagonal => (0..rows + columns - 1)
+                .flat_map(|d| (0..rows).filter(move |r| d >= *r && d - r < columns).map(move |r| cell(r, d - r)))
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Route {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Route {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Route::ALL
+            .into_iter()
+            .find(|route| route.name() == s.to_ascii_lowercase())
+            .ok_or_else(|| format!("unknown route `{}`, expected one of: {}", s, ROUTE_NAMES.join(", ")))
+    }
+}
+
+// Writes the text into a grid row by row and reads it out along a route.
+// Text longer than the grid is handled a grid at a time; a last, short
+// piece gets only as many rows as it fills, and the empty cells at the
+// end of its last row are skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteCipher {
+    rows: usize,
+    columns: usize,
+    route: Route,
+}
+
+impl RouteCipher {
+    pub fn new(rows: usize, columns: usize, route: Route) -> Option<Self> {
+        Self::try_new(rows, columns, route).ok()
+    }
+
+    pub fn try_new(rows: usize, columns: usize, route: Route) -> Result<Self> {
+        if rows == 0 || columns == 0 {
+            return Err(Error::InvalidKey(format!("a {}x{} grid has no cells", rows, columns)));
+        }
+        Ok(Self { rows, columns, route })
+    }
+
+    // The classic Caesar box: a `size` by `size` square read by columns.
+    pub fn caesar_box(size: usize) -> Option<Self> {
+        Self::new(size, size, Route::Columns)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn route(&self) -> Route {
+        self.route
+    }
+
+    // The route through a block of `len` characters, as positions in it.
+    fn block_path(&self, len: usize) -> Vec<usize> {
+        let rows = len.div_ceil(self.columns).min(self.rows);
+        self.route.path(rows, self.columns).into_iter().filter(|cell| *cell < len).collect()
+    }
+
+    fn blocks(&self, text: &str) -> Vec<Vec<char>> {
+        let chars: Vec<char> = text.chars().collect();
+        chars.chunks(self.rows * self.columns).map(<[char]>::to_vec).collect()
+    }
+}
+
+impl ClassicalCipher for RouteCipher {
+    fn encrypt(&self, plain: &str) -> String {
+        self.blocks(plain)
+            .iter()
+            .flat_map(|block| self.block_path(block.len()).into_iter().map(|cell| block[cell]))
+            .collect()
+    }
+
+    fn decrypt(&self, cipher: &str) -> String {
+        let mut plain = String::with_capacity(cipher.len());
+        for block in self.blocks(cipher) {
+            let mut cells = vec![' '; block.len()];
+            for (c, cell) in block.iter().zip(self.block_path(block.len())) {
+                cells[cell] = *c;
+            }
+            plain.extend(cells);
+        }
+        plain
+    }
+}
+
+// Every grid that holds the text in one block with no empty rows, as
+// (rows, columns), from two columns up to two rows.
+fn grid_shapes(len: usize) -> Vec<(usize, usize)> {
+    (2..=len / 2)
+        .map(|columns| (len.div_ceil(columns), columns))
+        .filter(|(rows, columns)| *rows >= 2 && (rows - 1) * columns < len)
+        .collect()
+}
+
+// Every route over every grid shape for the text.
+impl Keyspace for RouteCipher {
+    fn keys(len: usize) -> impl Iterator<Item = Self> {
+        grid_shapes(len)
+            .into_iter()
+            .flat_map(|(rows, columns)| Route::ALL.into_iter().map(move |route| RouteCipher { rows, columns, route }))
+    }
+
+    fn key_count(len: usize) -> usize {
+        grid_shapes(len).len() * Route::ALL.len()
+    }
+
+    fn key_label(&self) -> String {
+        format!("{},{},{}", self.rows, self.columns, self.route)
+    }
+}
+
+// Tries every route over every grid shape, ranked by English quadgrams;
+// letters keep their frequencies under transposition, so word and letter
+// counts can't tell the keys apart.
+pub fn brute_force(cipher: &str) -> Vec<DecodedResult> {
+    brute_force_with_options(cipher, &BruteForceOptions::default())
+}
+
+pub fn brute_force_with_options(cipher: &str, options: &BruteForceOptions) -> Vec<DecodedResult> {
+    brute_force_with_budget(cipher, &NgramScorer, options, &Budget::unlimited())
+}
+
+// One unit of `budget` per grid shape and route.
+pub fn brute_force_with_budget(
+    cipher: &str,
+    scorer: &dyn Scorer,
+    options: &BruteForceOptions,
+    budget: &Budget,
+) -> Vec<DecodedResult> {
+    let mut results = brute::brute_force_cipher::<RouteCipher>(cipher, scorer, options, budget);
+    // Small grids read the same along several routes.
+    results.dedup_by(|a, b| a.text == b.text);
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_grid_along_each_route() {
+        let cases = [
+            (Route::Spiral, "ABCDHLKJIEFG"),
+            (Route::Boustrophedon, "ABCDHGFEIJKL"),
+            (Route::Diagonal, "ABECFIDGJHKL"),
+        ];
+        for (route, expected) in cases {
+            let cipher = RouteCipher::try_new(3, 4, route).unwrap();
+            assert_eq!(cipher.encrypt("ABCDEFGHIJKL"), expected, "{}", route.name());
+            assert_eq!(cipher.decrypt(expected), "ABCDEFGHIJKL", "{}", route.name());
+        }
+    }
+
+    #[test]
+    fn caesar_box_reads_columns() {
+        assert_eq!(RouteCipher::caesar_box(3).unwrap().encrypt("ABCDEFGHI"), "ADGBEHCFI");
+    }
+}
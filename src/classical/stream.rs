@@ -0,0 +1,76 @@
+use std::io::{self, ErrorKind, Read, Write};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+// Hands `reader`'s text to `f` a chunk at a time, never splitting a UTF-8
+// character, and writes out what `f` returns. Memory use stays at about one
+// chunk whatever the input size.
+pub(crate) fn transform_chunks(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    mut f: impl FnMut(&str) -> String,
+) -> io::Result<()> {
+    let mut buffer = vec![0; CHUNK_SIZE];
+    // Bytes of a character cut off at the end of the last chunk.
+    let mut pending = 0;
+
+    loop {
+        let read = match reader.read(&mut buffer[pending..]) {
+            Ok(read) => read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if read == 0 {
+            if pending > 0 {
+                return Err(io::Error::new(ErrorKind::InvalidData, "input ends inside a UTF-8 character"));
+            }
+            return writer.flush();
+        }
+
+        let filled = pending + read;
+        let valid = match std::str::from_utf8(&buffer[..filled]) {
+            Ok(_) => filled,
+            // Only the last character is incomplete; the rest of it comes
+            // with the next read.
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => return Err(io::Error::new(ErrorKind::InvalidData, e)),
+        };
+
+        let text = std::str::from_utf8(&buffer[..valid]).expect("checked above");
+        writer.write_all(f(text).as_bytes())?;
+        buffer.copy_within(valid..filled, 0);
+        pending = filled - valid;
+    }
+}
+
+// The whole input at once, for ciphers that need all of it.
+pub(crate) fn transform_all(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    f: impl FnOnce(&str) -> String,
+) -> io::Result<()> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    writer.write_all(f(&text).as_bytes())?;
+    writer.flush()
+}
+
+// `transform_chunks` for raw bytes, which may not be UTF-8 at all.
+pub(crate) fn transform_byte_chunks(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    mut f: impl FnMut(&[u8]) -> Vec<u8>,
+) -> io::Result<()> {
+    let mut buffer = vec![0; CHUNK_SIZE];
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(read) => read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if read == 0 {
+            return writer.flush();
+        }
+        writer.write_all(&f(&buffer[..read]))?;
+    }
+}
@@ -0,0 +1,103 @@
+use crate::classical::ClassicalCipher;
+use crate::error::{Error, Result};
+
+// Maps A-Z onto an arbitrary permutation of the alphabet, keeping case.
+// Caesar, Atbash and keyword ciphers are all special cases of this.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubstitutionCipher {
+    forward: [u8; 26],
+    backward: [u8; 26],
+}
+
+impl SubstitutionCipher {
+    // `alphabet` gives the cipher letter for A, B, ... Z; None unless it is
+    // a permutation of the 26 letters (case-insensitive).
+    pub fn new(alphabet: &str) -> Option<Self> {
+        Self::try_new(alphabet).ok()
+    }
+
+    pub fn try_new(alphabet: &str) -> Result<Self> {
+        if let Some(c) = alphabet.chars().find(|c| !c.is_ascii_alphabetic()) {
+            return Err(Error::InvalidAlphabet(format!("{:?} is not a letter A-Z", c)));
+        }
+        let letters: Vec<u8> = alphabet.bytes().map(|b| b.to_ascii_uppercase()).collect();
+        if letters.len() != 26 {
+            return Err(Error::InvalidAlphabet(format!("expected 26 letters, got {}", letters.len())));
+        }
+
+        let mut forward = [0; 26];
+        let mut backward = [u8::MAX; 26];
+        for (plain, cipher) in letters.iter().enumerate() {
+            let cipher = cipher - b'A';
+            if backward[cipher as usize] != u8::MAX {
+                return Err(Error::InvalidAlphabet(format!("{} appears more than once", (cipher + b'A') as char)));
+            }
+            forward[plain] = cipher;
+            backward[cipher as usize] = plain as u8;
+        }
+
+        Ok(Self { forward, backward })
+    }
+
+    // The keyword's distinct letters, followed by the rest of the alphabet.
+    pub fn from_keyword(keyword: &str) -> Self {
+        Self::new(&keyword_alphabet(keyword)).expect("keyword alphabets are always permutations")
+    }
+
+    pub fn alphabet(&self) -> String {
+        self.forward.iter().map(|b| (b + b'A') as char).collect()
+    }
+
+    fn map(table: &[u8; 26], text: &str) -> String {
+        text.chars()
+            .map(|c| match c {
+                'A'..='Z' => (table[(c as u8 - b'A') as usize] + b'A') as char,
+                'a'..='z' => (table[(c as u8 - b'a') as usize] + b'a') as char,
+                _ => c,
+            })
+            .collect()
+    }
+}
+
+pub fn keyword_alphabet(keyword: &str) -> String {
+    let mut alphabet = String::with_capacity(26);
+
+    for c in keyword.chars().map(|c| c.to_ascii_uppercase()).chain('A'..='Z') {
+        if c.is_ascii_uppercase() && !alphabet.contains(c) {
+            alphabet.push(c);
+        }
+    }
+
+    alphabet
+}
+
+impl ClassicalCipher for SubstitutionCipher {
+    fn encrypt(&self, plain: &str) -> String {
+        SubstitutionCipher::map(&self.forward, plain)
+    }
+
+    fn decrypt(&self, cipher: &str) -> String {
+        SubstitutionCipher::map(&self.backward, cipher)
+    }
+
+    fn works_per_character(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_through_the_keyword_alphabet() {
+        let substitution = SubstitutionCipher::from_keyword("ZEBRAS");
+        assert_eq!(substitution.encrypt("flee at once. we are discovered!"), "siaa zq lkba. va zoa rfpbluaoar!");
+        assert_eq!(substitution.decrypt("siaa zq lkba. va zoa rfpbluaoar!"), "flee at once. we are discovered!");
+    }
+
+    #[test]
+    fn rejects_alphabets_with_repeats() {
+        assert!(SubstitutionCipher::new("AACDEFGHIJKLMNOPQRSTUVWXYZ").is_none());
+    }
+}
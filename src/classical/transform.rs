@@ -0,0 +1,88 @@
+use crate::classical::vigenere::shift_letter;
+use crate::classical::VigenereCipher;
+
+// Lazy, allocation-free versions of the simple ciphers for any iterator of
+// chars: `"hello".chars().caesar_shift(3).collect::<String>()`. Like the
+// ciphers, they only touch A-Z and a-z.
+pub trait CharTransform: Iterator<Item = char> + Sized {
+    // Negative keys shift back, so `caesar_shift(-3)` decrypts.
+    fn caesar_shift(self, key: i64) -> CaesarShift<Self> {
+        CaesarShift { chars: self, key: key.rem_euclid(26) as i32 }
+    }
+
+    fn atbash(self) -> Atbash<Self> {
+        Atbash { chars: self }
+    }
+
+    fn vigenere_encrypt(self, cipher: &VigenereCipher) -> Vigenere<'_, Self> {
+        Vigenere { chars: self, cipher, direction: 1, position: 0 }
+    }
+
+    fn vigenere_decrypt(self, cipher: &VigenereCipher) -> Vigenere<'_, Self> {
+        Vigenere { chars: self, cipher, direction: -1, position: 0 }
+    }
+}
+
+impl<I: Iterator<Item = char>> CharTransform for I {}
+
+#[derive(Debug, Clone)]
+pub struct CaesarShift<I> {
+    chars: I,
+    key: i32,
+}
+
+impl<I: Iterator<Item = char>> Iterator for CaesarShift<I> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        Some(shift_letter(c, self.key).unwrap_or(c))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chars.size_hint()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Atbash<I> {
+    chars: I,
+}
+
+impl<I: Iterator<Item = char>> Iterator for Atbash<I> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        Some(match self.chars.next()? {
+            c @ 'A'..='Z' => (b'Z' - (c as u8 - b'A')) as char,
+            c @ 'a'..='z' => (b'z' - (c as u8 - b'a')) as char,
+            c => c,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chars.size_hint()
+    }
+}
+
+// Keeps its place in the keyword across calls to `next`, so the key only
+// advances on letters, as with `VigenereCipher` itself.
+pub struct Vigenere<'a, I> {
+    chars: I,
+    cipher: &'a VigenereCipher,
+    direction: i64,
+    position: usize,
+}
+
+impl<I: Iterator<Item = char>> Iterator for Vigenere<'_, I> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        Some(self.cipher.apply_char(c, self.direction, &mut self.position))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chars.size_hint()
+    }
+}
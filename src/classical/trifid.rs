@@ -0,0 +1,61 @@
+use crate::classical::polybius::{fractionate, unfractionate, PolybiusSquare};
+use crate::classical::ClassicalCipher;
+
+// Delastelle's Trifid: Bifid in three dimensions, with a keyed 3x3x3 cube
+// holding A-Z and `+`. Other characters are dropped and letters come out
+// in upper case.
+pub struct TrifidCipher {
+    cube: PolybiusSquare,
+    period: usize,
+}
+
+impl TrifidCipher {
+    pub fn new(keyword: &str) -> Self {
+        Self { cube: PolybiusSquare::cube(keyword), period: 0 }
+    }
+
+    // Letters fractionated together; 0, the default, takes the whole text
+    // as one block. Delastelle used 5.
+    pub fn with_period(mut self, period: usize) -> Self {
+        self.period = period;
+        self
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    pub fn cube(&self) -> &PolybiusSquare {
+        &self.cube
+    }
+}
+
+impl ClassicalCipher for TrifidCipher {
+    fn encrypt(&self, plain: &str) -> String {
+        fractionate(&self.cube, plain, self.period)
+    }
+
+    fn decrypt(&self, cipher: &str) -> String {
+        unfractionate(&self.cube, cipher, self.period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_delastelles_example() {
+        let trifid = TrifidCipher::new("FELIX MARIE DELASTELLE").with_period(5);
+        assert_eq!(trifid.encrypt("Aide-toi, le ciel t'aidera"), "FMJFVOISSUFTFPUFEQQC");
+        assert_eq!(trifid.decrypt("FMJFVOISSUFTFPUFEQQC"), "AIDETOILECIELTAIDERA");
+    }
+
+    // Seventeen letters in blocks of five leave a block of two.
+    #[test]
+    fn round_trips_a_partial_last_period() {
+        let trifid = TrifidCipher::new("FELIX MARIE DELASTELLE").with_period(5);
+        assert_eq!(trifid.encrypt("AIDETOILECIELTAID"), "FMJFVOISSUFTFPUEQ");
+        assert_eq!(trifid.decrypt("FMJFVOISSUFTFPUEQ"), "AIDETOILECIELTAID");
+    }
+}
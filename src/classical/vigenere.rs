@@ -0,0 +1,259 @@
+use std::io::{self, Read, Write};
+
+use crate::analysis::{chi_squared_english, kasiski, key_lengths, LanguageModel, QuadgramModel};
+use crate::budget::Budget;
+use crate::classical::stream::transform_chunks;
+use crate::classical::{Alphabet, ClassicalCipher};
+use crate::error::{Error, Result};
+use crate::result::{rank, DecodedResult};
+
+// Shifts an ASCII letter by `shift` places within its case; anything else
+// comes back as None so callers can pass it through untouched.
+pub(crate) fn shift_letter(c: char, shift: i32) -> Option<char> {
+    let base = if c.is_ascii_uppercase() {
+        b'A'
+    } else if c.is_ascii_lowercase() {
+        b'a'
+    } else {
+        return None;
+    };
+
+    let offset = (c as u8 - base) as i32 + shift;
+    Some((base + offset.rem_euclid(26) as u8) as char)
+}
+
+pub(crate) fn letter_index(c: char) -> i32 {
+    (c.to_ascii_uppercase() as u8 - b'A') as i32
+}
+
+// Keyword letters as shifts (A = 0 ... Z = 25); fails if the keyword is
+// empty or has anything but ASCII letters.
+pub(crate) fn keyword_shifts(keyword: &str) -> Result<Vec<i32>> {
+    if keyword.is_empty() {
+        return Err(Error::InvalidKey("the keyword is empty".to_string()));
+    }
+    if let Some(c) = keyword.chars().find(|c| !c.is_ascii_alphabetic()) {
+        return Err(Error::InvalidKey(format!("keyword has {:?}; use letters A-Z only", c)));
+    }
+
+    Ok(keyword.chars().map(letter_index).collect())
+}
+
+pub struct VigenereCipher {
+    keyword: String,
+    shifts: Vec<usize>,
+    alphabet: Alphabet,
+}
+
+impl VigenereCipher {
+    pub fn new(keyword: &str) -> Option<Self> {
+        Self::try_new(keyword).ok()
+    }
+
+    pub fn try_new(keyword: &str) -> Result<Self> {
+        Self::with_alphabet(keyword, Alphabet::latin())
+    }
+
+    // Keyword letters count from 0 at the alphabet's first character.
+    pub fn with_alphabet(keyword: &str, alphabet: Alphabet) -> Result<Self> {
+        let shifts = alphabet.indices(keyword)?;
+        Ok(Self { keyword: keyword.to_uppercase(), shifts, alphabet })
+    }
+
+    pub fn keyword(&self) -> &str {
+        &self.keyword
+    }
+
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    // The key only advances on letters, so spaces and punctuation don't
+    // throw the keyword out of step. `position` counts letters already
+    // handled, so a long text can be fed in pieces.
+    fn apply(&self, text: &str, direction: i64, position: &mut usize) -> String {
+        text.chars().map(|c| self.apply_char(c, direction, position)).collect()
+    }
+
+    pub(crate) fn apply_char(&self, c: char, direction: i64, position: &mut usize) -> char {
+        match self.alphabet.shift(c, direction * self.shifts[*position % self.shifts.len()] as i64) {
+            Some(shifted) => {
+                *position += 1;
+                shifted
+            }
+            None => c,
+        }
+    }
+}
+
+impl ClassicalCipher for VigenereCipher {
+    fn encrypt(&self, plain: &str) -> String {
+        self.apply(plain, 1, &mut 0)
+    }
+
+    fn decrypt(&self, cipher: &str) -> String {
+        self.apply(cipher, -1, &mut 0)
+    }
+
+    fn encrypt_reader(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> io::Result<()> {
+        let mut position = 0;
+        transform_chunks(reader, writer, |chunk| self.apply(chunk, 1, &mut position))
+    }
+
+    fn decrypt_reader(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> io::Result<()> {
+        let mut position = 0;
+        transform_chunks(reader, writer, |chunk| self.apply(chunk, -1, &mut position))
+    }
+}
+
+// Recovers the keyword of Latin-alphabet Vigenère ciphertext: guesses key
+// lengths from the index of coincidence and Kasiski repeats, then picks
+// each key letter as the Caesar shift whose column looks most like English.
+pub struct VigenereSolver {
+    max_key_length: usize,
+    // How many of the likeliest key lengths get a full decryption.
+    lengths_to_try: usize,
+}
+
+impl VigenereSolver {
+    pub fn new() -> Self {
+        Self { max_key_length: 20, lengths_to_try: 6 }
+    }
+
+    pub fn with_max_key_length(mut self, max_key_length: usize) -> Self {
+        self.max_key_length = max_key_length.max(1);
+        self
+    }
+
+    pub fn with_lengths_to_try(mut self, lengths_to_try: usize) -> Self {
+        self.lengths_to_try = lengths_to_try.max(1);
+        self
+    }
+
+    // Ranked candidates, best quadgram score first; `key` is the keyword
+    // and `text` the plaintext it gives.
+    pub fn solve(&self, cipher: &str) -> Vec<DecodedResult> {
+        self.solve_with_budget(cipher, &Budget::unlimited())
+    }
+
+    // Like `solve`, one unit of `budget` per key length tried.
+    pub fn solve_with_budget(&self, cipher: &str, budget: &Budget) -> Vec<DecodedResult> {
+        let letters: Vec<char> = cipher.chars().filter(|c| c.is_ascii_alphabetic()).collect();
+        if letters.is_empty() {
+            return Vec::new();
+        }
+
+        let model = QuadgramModel::english();
+        let lengths = self.candidate_lengths(cipher);
+        budget.set_total(lengths.len() as u64);
+        let mut keywords: Vec<String> = Vec::new();
+        for length in lengths {
+            if !budget.spend() {
+                break;
+            }
+            let keyword = shortest_period(&refine(&letters, solve_columns(&letters, length), model));
+            if !keywords.contains(&keyword) {
+                keywords.push(keyword);
+            }
+        }
+
+        let mut results: Vec<DecodedResult> = keywords
+            .into_iter()
+            .filter_map(|keyword| {
+                let text = VigenereCipher::new(&keyword)?.decrypt(cipher);
+                let score = model.score(&text) / letters.len() as f64;
+                budget.record_score(score);
+                Some(DecodedResult::new(keyword, text, score).with_scorer("quadgram"))
+            })
+            .collect();
+
+        budget.report();
+        rank(&mut results);
+        results
+    }
+
+    // The best lengths by column IC, topped up with Kasiski's favourites.
+    fn candidate_lengths(&self, cipher: &str) -> Vec<usize> {
+        let by_ic = key_lengths(cipher, self.max_key_length).into_iter().map(|k| k.length);
+        let by_kasiski = kasiski(cipher, self.max_key_length).into_iter().map(|(length, _)| length);
+
+        let mut lengths: Vec<usize> = Vec::new();
+        for length in by_ic.take(self.lengths_to_try).chain(by_kasiski.take(self.lengths_to_try)) {
+            if !lengths.contains(&length) {
+                lengths.push(length);
+            }
+        }
+        lengths
+    }
+}
+
+impl Default for VigenereSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// One key letter per column: the shift whose decryption of that column is
+// closest to English letter frequencies.
+fn solve_columns(letters: &[char], length: usize) -> Vec<i32> {
+    (0..length)
+        .map(|column| {
+            let column: Vec<char> = letters.iter().skip(column).step_by(length).copied().collect();
+            (0..26)
+                .min_by(|a, b| column_distance(&column, *a).total_cmp(&column_distance(&column, *b)))
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+// Column frequencies are unreliable on short texts, so each key letter is
+// then set to whichever shift gives the best quadgram score overall.
+fn refine(letters: &[char], mut shifts: Vec<i32>, model: &QuadgramModel) -> String {
+    let decrypt = |shifts: &[i32]| -> String {
+        letters
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| shift_letter(*c, -shifts[i % shifts.len()]))
+            .collect()
+    };
+
+    for position in 0..shifts.len() {
+        let mut best = (model.score(&decrypt(&shifts)), shifts[position]);
+        for shift in 0..26 {
+            shifts[position] = shift;
+            let score = model.score(&decrypt(&shifts));
+            if score > best.0 {
+                best = (score, shift);
+            }
+        }
+        shifts[position] = best.1;
+    }
+
+    shifts.iter().map(|shift| (b'A' + *shift as u8) as char).collect()
+}
+
+fn column_distance(column: &[char], shift: i32) -> f32 {
+    let shifted: String = column.iter().filter_map(|c| shift_letter(*c, -shift)).collect();
+    chi_squared_english(&shifted)
+}
+
+// "KEYKEY" decrypts the same as "KEY".
+fn shortest_period(keyword: &str) -> String {
+    let bytes = keyword.as_bytes();
+    let period = (1..=bytes.len())
+        .find(|p| bytes.len().is_multiple_of(*p) && bytes.chunks(*p).all(|chunk| chunk == &bytes[..*p]))
+        .unwrap_or(bytes.len());
+    keyword[..period].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_the_lemon_example() {
+        let vigenere = VigenereCipher::new("LEMON").unwrap();
+        assert_eq!(vigenere.encrypt("ATTACKATDAWN"), "LXFOPVEFRNHR");
+        assert_eq!(vigenere.decrypt("LXFOPVEFRNHR"), "ATTACKATDAWN");
+    }
+}
@@ -0,0 +1,125 @@
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::error::{Error, Result};
+use crate::json::Json;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    // Input character the encoder has no mapping for, dropped from the output.
+    SkippedCharacter(char),
+    // Token the decoder couldn't read; `position` counts tokens from 0.
+    SkippedToken { position: usize, token: String },
+    // Input the decoder read as something else, `count` times.
+    Substituted { from: String, to: String, count: usize },
+    InvalidInput(String),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Warning::SkippedCharacter(c) => write!(f, "skipped character {:?}", c),
+            Warning::SkippedToken { position, token } => write!(f, "skipped token `{}` at position {}", token, position),
+            Warning::Substituted { from, to, count } => {
+                write!(f, "read `{}` as `{}` {} time{}", from, to, count, if *count == 1 { "" } else { "s" })
+            }
+            Warning::InvalidInput(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<&Warning> for Json {
+    fn from(warning: &Warning) -> Self {
+        match warning {
+            Warning::SkippedCharacter(c) => {
+                Json::object(vec![("kind", "skipped_character".into()), ("character", c.to_string().into())])
+            }
+            Warning::SkippedToken { position, token } => Json::object(vec![
+                ("kind", "skipped_token".into()),
+                ("position", (*position).into()),
+                ("token", token.as_str().into()),
+            ]),
+            Warning::Substituted { from, to, count } => Json::object(vec![
+                ("kind", "substituted".into()),
+                ("from", from.as_str().into()),
+                ("to", to.as_str().into()),
+                ("count", (*count).into()),
+            ]),
+            Warning::InvalidInput(message) => {
+                Json::object(vec![("kind", "invalid_input".into()), ("message", message.as_str().into())])
+            }
+        }
+    }
+}
+
+impl TryFrom<&Json> for Warning {
+    type Error = Error;
+
+    fn try_from(json: &Json) -> Result<Self> {
+        match json.str_field("kind")? {
+            "skipped_character" => {
+                let mut chars = json.str_field("character")?.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(Warning::SkippedCharacter(c)),
+                    _ => Err(Error::InvalidJson("`character` should be a single character".to_string())),
+                }
+            }
+            "skipped_token" => Ok(Warning::SkippedToken {
+                position: json.usize_field("position")?,
+                token: json.str_field("token")?.to_string(),
+            }),
+            "substituted" => Ok(Warning::Substituted {
+                from: json.str_field("from")?.to_string(),
+                to: json.str_field("to")?.to_string(),
+                count: json.usize_field("count")?,
+            }),
+            "invalid_input" => Ok(Warning::InvalidInput(json.str_field("message")?.to_string())),
+            kind => Err(Error::InvalidJson(format!("unknown warning kind `{}`", kind))),
+        }
+    }
+}
+
+type WarningCallback = Box<dyn Fn(&Warning) + Send + Sync>;
+
+// Where codecs and ciphers report input they skipped or patched up, instead
+// of printing it. Warnings are kept for `warnings` and also handed to the
+// callback as they happen.
+#[derive(Default)]
+pub struct Diagnostics {
+    warnings: Mutex<Vec<Warning>>,
+    callback: Option<WarningCallback>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&Warning) + Send + Sync + 'static,
+    {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+
+    pub fn warn(&self, warning: Warning) {
+        if let Some(callback) = &self.callback {
+            callback(&warning);
+        }
+        self.warnings.lock().unwrap().push(warning);
+    }
+
+    pub fn warnings(&self) -> Vec<Warning> {
+        self.warnings.lock().unwrap().clone()
+    }
+
+    // Returns the warnings so far and starts a fresh list.
+    pub fn take(&self) -> Vec<Warning> {
+        std::mem::take(&mut *self.warnings.lock().unwrap())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.warnings.lock().unwrap().is_empty()
+    }
+}
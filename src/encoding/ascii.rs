@@ -0,0 +1,124 @@
+use crate::diagnostics::{Diagnostics, Warning};
+use crate::encoding::{Codec, CodecError};
+
+// UTF-8 bytes as space-separated 8-bit binary, e.g. "01001000 01101001".
+// Decoding also takes an unbroken run of bits and splits it every 8.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BinaryAscii;
+
+// UTF-8 bytes as space-separated decimal values, e.g. "72 105". Decoding
+// accepts spaces or commas between values and skips anything above 255.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecimalAscii;
+
+fn bytes_to_text(bytes: Vec<u8>) -> String {
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn try_bytes_to_text<'a>(
+    tokens: impl Iterator<Item = &'a str>,
+    parse: fn(&str) -> Option<u8>,
+) -> Result<String, CodecError> {
+    let bytes = tokens
+        .enumerate()
+        .map(|(position, token)| parse(token).ok_or_else(|| CodecError::InvalidToken { position, token: token.to_string() }))
+        .collect::<Result<Vec<u8>, CodecError>>()?;
+
+    String::from_utf8(bytes).map_err(|_| CodecError::InvalidInput("decoded bytes are not valid UTF-8".to_string()))
+}
+
+// Lenient decoding that reports what it skips.
+fn report_bytes_to_text<'a>(
+    tokens: impl Iterator<Item = &'a str>,
+    parse: fn(&str) -> Option<u8>,
+    diagnostics: &Diagnostics,
+) -> String {
+    let bytes = tokens
+        .enumerate()
+        .filter_map(|(position, token)| {
+            let byte = parse(token);
+            if byte.is_none() {
+                diagnostics.warn(Warning::SkippedToken { position, token: token.to_string() });
+            }
+            byte
+        })
+        .collect();
+
+    report_invalid_utf8(bytes, diagnostics)
+}
+
+pub(crate) fn report_invalid_utf8(bytes: Vec<u8>, diagnostics: &Diagnostics) -> String {
+    if std::str::from_utf8(&bytes).is_err() {
+        diagnostics.warn(Warning::InvalidInput("invalid UTF-8 in the decoded bytes was replaced".to_string()));
+    }
+    bytes_to_text(bytes)
+}
+
+impl BinaryAscii {
+    fn groups(input: &str) -> Vec<String> {
+        let groups: Vec<&str> = input.split_whitespace().collect();
+        match groups.as_slice() {
+            [run] if run.len() > 8 => run.chars().collect::<Vec<char>>().chunks(8).map(|c| c.iter().collect()).collect(),
+            _ => groups.iter().map(|group| group.to_string()).collect(),
+        }
+    }
+}
+
+impl Codec for BinaryAscii {
+    fn encode(&self, input: &str) -> String {
+        input.bytes().map(|b| format!("{:08b}", b)).collect::<Vec<String>>().join(" ")
+    }
+
+    fn decode(&self, input: &str) -> String {
+        let groups = Self::groups(input);
+        bytes_to_text(groups.iter().filter_map(|group| u8::from_str_radix(group, 2).ok()).collect())
+    }
+
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        let groups = Self::groups(input);
+        try_bytes_to_text(groups.iter().map(String::as_str), |group| u8::from_str_radix(group, 2).ok())
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        let groups = Self::groups(input);
+        report_bytes_to_text(groups.iter().map(String::as_str), |group| u8::from_str_radix(group, 2).ok(), diagnostics)
+    }
+}
+
+impl Codec for DecimalAscii {
+    fn encode(&self, input: &str) -> String {
+        input.bytes().map(|b| b.to_string()).collect::<Vec<String>>().join(" ")
+    }
+
+    fn decode(&self, input: &str) -> String {
+        let bytes = input
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter_map(|value| value.parse::<u8>().ok())
+            .collect();
+
+        bytes_to_text(bytes)
+    }
+
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        let values = input.split(|c: char| c.is_whitespace() || c == ',').filter(|value| !value.is_empty());
+        try_bytes_to_text(values, |value| value.parse().ok())
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        let values = input.split(|c: char| c.is_whitespace() || c == ',').filter(|value| !value.is_empty());
+        report_bytes_to_text(values, |value| value.parse().ok(), diagnostics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_each_byte_as_a_number() {
+        assert_eq!(BinaryAscii.encode("A"), "01000001");
+        assert_eq!(BinaryAscii.decode("01000001"), "A");
+        assert_eq!(DecimalAscii.encode("Hi"), "72 105");
+        assert_eq!(DecimalAscii.decode("72 105"), "Hi");
+    }
+}
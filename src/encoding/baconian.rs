@@ -0,0 +1,187 @@
+use crate::diagnostics::{Diagnostics, Warning};
+use crate::encoding::{Codec, CodecError};
+
+// Bacon's original alphabet has 24 letters: I/J and U/V share a code.
+const CLASSIC_ALPHABET: &str = "ABCDEFGHIKLMNOPQRSTUWXYZ";
+const FULL_ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BaconVariant {
+    #[default]
+    Classic24,
+    Full26,
+}
+
+// Each letter becomes five symbols, written as A/B or as 0/1.
+pub struct Baconian {
+    variant: BaconVariant,
+    symbols: (char, char),
+}
+
+impl Baconian {
+    pub fn new(variant: BaconVariant) -> Self {
+        Self { variant, symbols: ('A', 'B') }
+    }
+
+    pub fn binary(variant: BaconVariant) -> Self {
+        Self { variant, symbols: ('0', '1') }
+    }
+
+    fn alphabet(&self) -> &'static str {
+        match self.variant {
+            BaconVariant::Classic24 => CLASSIC_ALPHABET,
+            BaconVariant::Full26 => FULL_ALPHABET,
+        }
+    }
+
+    fn index_of(&self, c: char) -> Option<usize> {
+        let c = match (self.variant, c.to_ascii_uppercase()) {
+            (BaconVariant::Classic24, 'J') => 'I',
+            (BaconVariant::Classic24, 'V') => 'U',
+            (_, c) => c,
+        };
+        self.alphabet().find(c)
+    }
+
+    // Five bits per letter, most significant first; false = first symbol.
+    fn bits(&self, text: &str) -> Vec<bool> {
+        text.chars()
+            .filter_map(|c| self.index_of(c))
+            .flat_map(|index| (0..5).rev().map(move |bit| index >> bit & 1 == 1))
+            .collect()
+    }
+
+    fn letters(&self, bits: &[bool]) -> String {
+        self.letters_reporting(bits, |_, _| {})
+    }
+
+    // Groups past the end of the alphabet, and a short last group, go to
+    // `on_error` with their position and symbols.
+    fn letters_reporting(&self, bits: &[bool], mut on_error: impl FnMut(usize, String)) -> String {
+        let (zero, one) = self.symbols;
+        let mut out = String::with_capacity(bits.len() / 5);
+
+        for (position, group) in bits.chunks(5).enumerate() {
+            let index = group.iter().fold(0, |n, bit| n << 1 | usize::from(*bit));
+            match self.alphabet().chars().nth(index).filter(|_| group.len() == 5) {
+                Some(letter) => out.push(letter),
+                None => on_error(position, group.iter().map(|bit| if *bit { one } else { zero }).collect()),
+            }
+        }
+        out
+    }
+
+    // Anything but the two symbols and whitespace goes to `on_error` too,
+    // at the position of the group it turned up in.
+    fn decode_reporting(&self, input: &str, mut on_error: impl FnMut(usize, String)) -> String {
+        let (zero, one) = self.symbols;
+        let mut bits = Vec::new();
+
+        for c in input.chars() {
+            let upper = c.to_ascii_uppercase();
+            if upper == zero || upper == one {
+                bits.push(upper == one);
+            } else if !c.is_whitespace() {
+                on_error(bits.len() / 5, c.to_string());
+            }
+        }
+
+        self.letters_reporting(&bits, on_error)
+    }
+
+    // Hides the message in the capitalization of the cover text: lower case
+    // letters carry the first symbol, upper case the second. None if the
+    // cover has too few letters.
+    pub fn hide(&self, message: &str, cover: &str) -> Option<String> {
+        let bits = self.bits(message);
+        if cover.chars().filter(|c| c.is_alphabetic()).count() < bits.len() {
+            return None;
+        }
+
+        let mut bits = bits.into_iter();
+        Some(
+            cover
+                .chars()
+                .map(|c| match c.is_alphabetic().then(|| bits.next()).flatten() {
+                    Some(true) => c.to_ascii_uppercase(),
+                    Some(false) => c.to_ascii_lowercase(),
+                    None => c,
+                })
+                .collect(),
+        )
+    }
+
+    // Cover letters past the end of the message read as trailing A's.
+    pub fn reveal(&self, text: &str) -> String {
+        let bits: Vec<bool> = text.chars().filter(|c| c.is_alphabetic()).map(|c| c.is_uppercase()).collect();
+        self.letters(&bits)
+    }
+}
+
+impl Default for Baconian {
+    fn default() -> Self {
+        Self::new(BaconVariant::default())
+    }
+}
+
+impl Codec for Baconian {
+    fn encode(&self, input: &str) -> String {
+        let (zero, one) = self.symbols;
+
+        self.bits(input)
+            .chunks(5)
+            .map(|group| group.iter().map(|bit| if *bit { one } else { zero }).collect::<String>())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    fn decode(&self, input: &str) -> String {
+        self.decode_reporting(input, |_, _| {})
+    }
+
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        let mut error = None;
+        let decoded = self.decode_reporting(input, |position, token| {
+            error.get_or_insert(CodecError::InvalidToken { position, token });
+        });
+        match error {
+            Some(error) => Err(error),
+            None => Ok(decoded),
+        }
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        self.decode_reporting(input, |position, token| {
+            diagnostics.warn(Warning::SkippedToken { position, token });
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_both_alphabets() {
+        let classic = Baconian::new(BaconVariant::Classic24);
+        assert_eq!(classic.encode("AB"), "AAAAA AAAAB");
+        assert_eq!(classic.decode("AAAAA AAAAB"), "AB");
+        let full = Baconian::new(BaconVariant::Full26);
+        assert_eq!(full.encode("JV"), "ABAAB BABAB");
+        assert_eq!(full.decode("ABAAB BABAB"), "JV");
+    }
+
+    #[test]
+    fn strict_decoding_rejects_other_characters() {
+        let bacon = Baconian::default();
+        assert_eq!(
+            bacon.try_decode("ZZZZZ"),
+            Err(CodecError::InvalidToken { position: 0, token: "Z".to_string() })
+        );
+        assert_eq!(
+            bacon.try_decode("AAAAA AAAAB, AAABA"),
+            Err(CodecError::InvalidToken { position: 2, token: ",".to_string() })
+        );
+        assert_eq!(bacon.decode("AAAAA AAAAB, AAABA"), "ABC");
+    }
+}
@@ -0,0 +1,604 @@
+use crate::diagnostics::{Diagnostics, Warning};
+use crate::encoding::{Codec, CodecError};
+
+pub const BASE64_ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+pub const BASE64_URL_ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+pub const BASE32_ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+// Bitcoin's: no 0, O, I or l, which are easy to mix up.
+pub const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+// `!` to `u`.
+pub const ASCII85_ALPHABET: &str =
+    "!\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstu";
+// ZeroMQ's, which leaves out quotes and backslashes so the text can go in
+// source code.
+pub const Z85_ALPHABET: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+const PAD: char = '=';
+
+// Packs bytes into `bits`-wide symbols from `alphabet`, padding the output
+// to a multiple of `block` symbols when asked to.
+fn encode_bits(data: &[u8], alphabet: &str, bits: u32, block: usize, padding: bool) -> String {
+    let symbols: Vec<char> = alphabet.chars().collect();
+    let mask = (1u32 << bits) - 1;
+    let mut out = String::new();
+    let mut buffer = 0u32;
+    let mut held = 0;
+
+    for byte in data {
+        buffer = buffer << 8 | *byte as u32;
+        held += 8;
+        while held >= bits {
+            held -= bits;
+            out.push(symbols[(buffer >> held & mask) as usize]);
+        }
+        buffer &= (1 << held) - 1;
+    }
+    if held > 0 {
+        out.push(symbols[(buffer << (bits - held) & mask) as usize]);
+    }
+
+    if padding {
+        while !out.len().is_multiple_of(block) {
+            out.push(PAD);
+        }
+    }
+    out
+}
+
+// Inverse of `encode_bits`. Whitespace is ignored, padding is optional, and
+// leftover bits that don't make a whole byte are dropped. None if a symbol
+// isn't in the alphabet.
+fn decode_bits(text: &str, alphabet: &str, bits: u32) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut buffer = 0u32;
+    let mut held = 0;
+
+    for c in text.chars().filter(|c| !c.is_whitespace()).take_while(|c| *c != PAD) {
+        let value = alphabet.find(c)? as u32;
+        buffer = buffer << bits | value;
+        held += bits;
+        if held >= 8 {
+            held -= 8;
+            out.push((buffer >> held) as u8);
+            buffer &= (1 << held) - 1;
+        }
+    }
+
+    Some(out)
+}
+
+pub fn hex_encode(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Whitespace is ignored; None on odd length or non-hex characters.
+pub fn hex_decode(text: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = text.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    if !digits.len().is_multiple_of(2) {
+        return None;
+    }
+
+    digits
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+pub fn base64_encode(data: &[u8]) -> String {
+    encode_bits(data, BASE64_ALPHABET, 6, 4, true)
+}
+
+pub fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    decode_bits(text, BASE64_ALPHABET, 6)
+}
+
+pub fn base32_encode(data: &[u8]) -> String {
+    encode_bits(data, BASE32_ALPHABET, 5, 8, true)
+}
+
+pub fn base32_decode(text: &str) -> Option<Vec<u8>> {
+    decode_bits(&text.to_ascii_uppercase(), BASE32_ALPHABET, 5)
+}
+
+// The data as one big-endian number written in base 58, with a `1` for
+// each leading zero byte.
+pub fn base58_encode(data: &[u8]) -> String {
+    let symbols = BASE58_ALPHABET.as_bytes();
+    let zeros = data.iter().take_while(|b| **b == 0).count();
+
+    // Base-58 digits, least significant first.
+    let mut digits: Vec<u8> = Vec::new();
+    for byte in &data[zeros..] {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let leading = std::iter::repeat_n('1', zeros);
+    leading.chain(digits.iter().rev().map(|digit| symbols[*digit as usize] as char)).collect()
+}
+
+// Whitespace is ignored; None on a symbol outside the alphabet.
+pub fn base58_decode(text: &str) -> Option<Vec<u8>> {
+    let symbols: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let zeros = symbols.iter().take_while(|c| **c == '1').count();
+
+    // Bytes, least significant first.
+    let mut bytes: Vec<u8> = Vec::new();
+    for c in &symbols[zeros..] {
+        let mut carry = BASE58_ALPHABET.find(*c)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut out = vec![0; zeros];
+    out.extend(bytes.iter().rev());
+    Some(out)
+}
+
+// Each 4 bytes become 5 symbols; a short last group of n bytes is padded
+// with zeros and written as n + 1 symbols. With `zero_group`, four zero
+// bytes are written as that one symbol instead.
+fn encode_base85(data: &[u8], alphabet: &str, zero_group: Option<char>) -> String {
+    let symbols = alphabet.as_bytes();
+    let mut out = String::new();
+
+    for chunk in data.chunks(4) {
+        let mut group = [0u8; 4];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let mut value = u32::from_be_bytes(group);
+
+        match zero_group {
+            Some(zero) if chunk.len() == 4 && value == 0 => out.push(zero),
+            _ => {
+                let mut digits = [0u8; 5];
+                for digit in digits.iter_mut().rev() {
+                    *digit = symbols[(value % 85) as usize];
+                    value /= 85;
+                }
+                out.extend(digits[..chunk.len() + 1].iter().map(|b| *b as char));
+            }
+        }
+    }
+
+    out
+}
+
+// Inverse of `encode_base85`. Whitespace is ignored; None on a symbol
+// outside the alphabet, a group too big for 4 bytes, or a lone symbol at
+// the end.
+fn decode_base85(text: &str, alphabet: &str, zero_group: Option<char>) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut group: Vec<u32> = Vec::with_capacity(5);
+
+    for c in text.chars().filter(|c| !c.is_whitespace()) {
+        if Some(c) == zero_group && group.is_empty() {
+            out.extend([0; 4]);
+            continue;
+        }
+        group.push(alphabet.find(c)? as u32);
+        if group.len() == 5 {
+            out.extend(base85_group(&group)?);
+            group.clear();
+        }
+    }
+
+    match group.len() {
+        0 => {}
+        1 => return None,
+        n => {
+            // Padding with the highest digit rounds the partial group back
+            // up to the bytes it came from.
+            group.resize(5, 84);
+            out.extend(&base85_group(&group)?[..n - 1]);
+        }
+    }
+    Some(out)
+}
+
+fn base85_group(digits: &[u32]) -> Option<[u8; 4]> {
+    let value = digits.iter().fold(0u64, |value, digit| value * 85 + *digit as u64);
+    Some(u32::try_from(value).ok()?.to_be_bytes())
+}
+
+// Adobe's Ascii85 without the `<~ ~>` delimiters; `z` stands for four
+// zero bytes.
+pub fn ascii85_encode(data: &[u8]) -> String {
+    encode_base85(data, ASCII85_ALPHABET, Some('z'))
+}
+
+// The `<~ ~>` delimiters are optional.
+pub fn ascii85_decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim();
+    let text = text.strip_prefix("<~").unwrap_or(text);
+    let text = text.strip_suffix("~>").unwrap_or(text);
+    decode_base85(text, ASCII85_ALPHABET, Some('z'))
+}
+
+// ZeroMQ's Z85. The spec only allows multiples of 4 bytes; other lengths
+// get a short last group, as in Ascii85.
+pub fn z85_encode(data: &[u8]) -> String {
+    encode_base85(data, Z85_ALPHABET, None)
+}
+
+pub fn z85_decode(text: &str) -> Option<Vec<u8>> {
+    decode_base85(text, Z85_ALPHABET, None)
+}
+
+// The `Codec` impls below treat text as UTF-8 bytes. Decoding invalid input
+// gives an empty string, and invalid UTF-8 in the result is replaced.
+fn bytes_to_text(bytes: Option<Vec<u8>>) -> String {
+    String::from_utf8_lossy(&bytes.unwrap_or_default()).into_owned()
+}
+
+fn report_bytes_to_text(bytes: Option<Vec<u8>>, name: &str, diagnostics: &Diagnostics) -> String {
+    match &bytes {
+        None => diagnostics.warn(Warning::InvalidInput(format!("not valid {}", name))),
+        Some(bytes) if std::str::from_utf8(bytes).is_err() => {
+            diagnostics.warn(Warning::InvalidInput("invalid UTF-8 in the decoded bytes was replaced".to_string()))
+        }
+        Some(_) => {}
+    }
+    bytes_to_text(bytes)
+}
+
+fn try_bytes_to_text(bytes: Option<Vec<u8>>, name: &str) -> Result<String, CodecError> {
+    let bytes = bytes.ok_or_else(|| CodecError::InvalidInput(format!("not valid {}", name)))?;
+    String::from_utf8(bytes).map_err(|_| CodecError::InvalidInput("decoded bytes are not valid UTF-8".to_string()))
+}
+
+// Whether anything but more padding follows the first `=`, which
+// `decode_bits` ignores.
+fn data_after_padding(text: &str) -> bool {
+    text.chars().filter(|c| !c.is_whitespace()).skip_while(|c| *c != PAD).any(|c| c != PAD)
+}
+
+fn try_padded_to_text(input: &str, bytes: Option<Vec<u8>>, name: &str) -> Result<String, CodecError> {
+    if data_after_padding(input) {
+        return Err(CodecError::InvalidInput(format!("data after the {} padding", name)));
+    }
+    try_bytes_to_text(bytes, name)
+}
+
+fn report_padded_to_text(input: &str, bytes: Option<Vec<u8>>, name: &str, diagnostics: &Diagnostics) -> String {
+    if data_after_padding(input) {
+        diagnostics.warn(Warning::InvalidInput(format!("data after the {} padding was ignored", name)));
+    }
+    report_bytes_to_text(bytes, name, diagnostics)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Base64 {
+    url_safe: bool,
+    padding: bool,
+}
+
+impl Base64 {
+    pub fn new() -> Self {
+        Self { url_safe: false, padding: true }
+    }
+
+    // `-` and `_` instead of `+` and `/`, and no padding (RFC 4648 §5).
+    pub fn url_safe() -> Self {
+        Self { url_safe: true, padding: false }
+    }
+
+    pub fn with_padding(mut self, padding: bool) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn is_url_safe(&self) -> bool {
+        self.url_safe
+    }
+
+    fn alphabet(&self) -> &'static str {
+        if self.url_safe {
+            BASE64_URL_ALPHABET
+        } else {
+            BASE64_ALPHABET
+        }
+    }
+
+    pub fn encode_bytes(&self, data: &[u8]) -> String {
+        encode_bits(data, self.alphabet(), 6, 4, self.padding)
+    }
+
+    pub fn decode_bytes(&self, text: &str) -> Option<Vec<u8>> {
+        decode_bits(text, self.alphabet(), 6)
+    }
+}
+
+impl Default for Base64 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Codec for Base64 {
+    fn encode(&self, input: &str) -> String {
+        self.encode_bytes(input.as_bytes())
+    }
+
+    fn decode(&self, input: &str) -> String {
+        bytes_to_text(self.decode_bytes(input))
+    }
+
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        try_padded_to_text(input, self.decode_bytes(input), "base64")
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        report_padded_to_text(input, self.decode_bytes(input), "base64", diagnostics)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Base32 {
+    padding: bool,
+}
+
+impl Base32 {
+    pub fn new() -> Self {
+        Self { padding: true }
+    }
+
+    pub fn with_padding(mut self, padding: bool) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn encode_bytes(&self, data: &[u8]) -> String {
+        encode_bits(data, BASE32_ALPHABET, 5, 8, self.padding)
+    }
+
+    pub fn decode_bytes(&self, text: &str) -> Option<Vec<u8>> {
+        base32_decode(text)
+    }
+}
+
+impl Default for Base32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Codec for Base32 {
+    fn encode(&self, input: &str) -> String {
+        self.encode_bytes(input.as_bytes())
+    }
+
+    fn decode(&self, input: &str) -> String {
+        bytes_to_text(self.decode_bytes(input))
+    }
+
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        try_padded_to_text(input, self.decode_bytes(input), "base32")
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        report_padded_to_text(input, self.decode_bytes(input), "base32", diagnostics)
+    }
+}
+
+// Base16. Decoding accepts either case and ignores whitespace.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Hex {
+    uppercase: bool,
+}
+
+impl Hex {
+    pub fn new() -> Self {
+        Self { uppercase: false }
+    }
+
+    pub fn uppercase() -> Self {
+        Self { uppercase: true }
+    }
+
+    pub fn encode_bytes(&self, data: &[u8]) -> String {
+        let hex = hex_encode(data);
+        if self.uppercase {
+            hex.to_ascii_uppercase()
+        } else {
+            hex
+        }
+    }
+
+    pub fn decode_bytes(&self, text: &str) -> Option<Vec<u8>> {
+        hex_decode(text)
+    }
+}
+
+impl Codec for Hex {
+    fn encode(&self, input: &str) -> String {
+        self.encode_bytes(input.as_bytes())
+    }
+
+    fn decode(&self, input: &str) -> String {
+        bytes_to_text(self.decode_bytes(input))
+    }
+
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        try_bytes_to_text(self.decode_bytes(input), "hex")
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        report_bytes_to_text(self.decode_bytes(input), "hex", diagnostics)
+    }
+}
+
+// Bitcoin-style Base58, as used for addresses and IPFS hashes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Base58;
+
+impl Base58 {
+    pub fn encode_bytes(&self, data: &[u8]) -> String {
+        base58_encode(data)
+    }
+
+    pub fn decode_bytes(&self, text: &str) -> Option<Vec<u8>> {
+        base58_decode(text)
+    }
+}
+
+impl Codec for Base58 {
+    fn encode(&self, input: &str) -> String {
+        self.encode_bytes(input.as_bytes())
+    }
+
+    fn decode(&self, input: &str) -> String {
+        bytes_to_text(self.decode_bytes(input))
+    }
+
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        try_bytes_to_text(self.decode_bytes(input), "base58")
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        report_bytes_to_text(self.decode_bytes(input), "base58", diagnostics)
+    }
+}
+
+// Base85 as in PostScript and PDF. Encoding can add the `<~ ~>`
+// delimiters; decoding takes text with or without them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ascii85 {
+    delimiters: bool,
+}
+
+impl Ascii85 {
+    pub fn new() -> Self {
+        Self { delimiters: false }
+    }
+
+    pub fn with_delimiters(mut self, delimiters: bool) -> Self {
+        self.delimiters = delimiters;
+        self
+    }
+
+    pub fn has_delimiters(&self) -> bool {
+        self.delimiters
+    }
+
+    pub fn encode_bytes(&self, data: &[u8]) -> String {
+        let text = ascii85_encode(data);
+        if self.delimiters {
+            format!("<~{}~>", text)
+        } else {
+            text
+        }
+    }
+
+    pub fn decode_bytes(&self, text: &str) -> Option<Vec<u8>> {
+        ascii85_decode(text)
+    }
+}
+
+impl Codec for Ascii85 {
+    fn encode(&self, input: &str) -> String {
+        self.encode_bytes(input.as_bytes())
+    }
+
+    fn decode(&self, input: &str) -> String {
+        bytes_to_text(self.decode_bytes(input))
+    }
+
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        try_bytes_to_text(self.decode_bytes(input), "ascii85")
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        report_bytes_to_text(self.decode_bytes(input), "ascii85", diagnostics)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Z85;
+
+impl Z85 {
+    pub fn encode_bytes(&self, data: &[u8]) -> String {
+        z85_encode(data)
+    }
+
+    pub fn decode_bytes(&self, text: &str) -> Option<Vec<u8>> {
+        z85_decode(text)
+    }
+}
+
+impl Codec for Z85 {
+    fn encode(&self, input: &str) -> String {
+        self.encode_bytes(input.as_bytes())
+    }
+
+    fn decode(&self, input: &str) -> String {
+        bytes_to_text(self.decode_bytes(input))
+    }
+
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        try_bytes_to_text(self.decode_bytes(input), "z85")
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        report_bytes_to_text(self.decode_bytes(input), "z85", diagnostics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 4648.
+    #[test]
+    fn matches_the_published_vectors() {
+        assert_eq!(Base64::new().encode("Man"), "TWFu");
+        assert_eq!(Base64::new().encode("?>>"), "Pz4+");
+        assert_eq!(Base64::url_safe().encode("?>>"), "Pz4-");
+        assert_eq!(Base32::new().encode("foobar"), "MZXW6YTBOI======");
+        assert_eq!(Hex::new().encode("hi"), "6869");
+    }
+
+    #[test]
+    fn decodes_the_published_vectors() {
+        assert_eq!(Base64::new().decode("TWFu"), "Man");
+        assert_eq!(Base32::new().decode("MZXW6YTBOI======"), "foobar");
+        assert_eq!(hex_decode("6869").unwrap(), b"hi");
+        assert_eq!(hex_decode("686"), None);
+    }
+
+    #[test]
+    fn data_after_padding_is_an_error() {
+        assert_eq!(Base64::new().try_decode("SGVsbG8="), Ok("Hello".to_string()));
+        assert!(Base64::new().try_decode("SGVsbG8=extra").is_err());
+        assert!(Base32::new().try_decode("MZXW6===MZXW6===").is_err());
+
+        let diagnostics = Diagnostics::new();
+        assert_eq!(Base64::new().decode_with_diagnostics("SGVsbG8=extra", &diagnostics), "Hello");
+        assert_eq!(diagnostics.take().len(), 1);
+    }
+
+    // The Bitcoin Base58 alphabet, Adobe Ascii85 and the ZeroMQ Z85 spec.
+    #[test]
+    fn matches_the_base58_and_base85_vectors() {
+        assert_eq!(Base58.encode("Hello World!"), "2NEpo7TZRRrLZSi2U");
+        assert_eq!(Base58.decode("2NEpo7TZRRrLZSi2U"), "Hello World!");
+        assert_eq!(Ascii85::new().encode("Man "), "9jqo^");
+        assert_eq!(Ascii85::new().decode("9jqo^"), "Man ");
+        assert_eq!(Z85.encode_bytes(&[0x86, 0x4f, 0xd2, 0x6f, 0xb5, 0x59, 0xf7, 0x5b]), "HelloWorld");
+        assert_eq!(Z85.decode_bytes("HelloWorld").unwrap(), [0x86, 0x4f, 0xd2, 0x6f, 0xb5, 0x59, 0xf7, 0x5b]);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::diagnostics::{Diagnostics, Warning};
+use crate::encoding::{Codec, CodecError};
+
+// The empty cell; each raised dot adds its bit (dot 1 = 1, ... dot 6 = 32).
+const BLANK: u32 = 0x2800;
+
+const CAPITAL_SIGN: char = '⠠';
+const NUMBER_SIGN: char = '⠼';
+// Ends a number, so a-j after it are letters again.
+const LETTER_SIGN: char = '⠰';
+
+// Grade 1 English braille, letters a to z.
+const LETTERS: [char; 26] = [
+    '⠁', '⠃', '⠉', '⠙', '⠑', '⠋', '⠛', '⠓', '⠊', '⠚', '⠅', '⠇', '⠍', '⠝', '⠕', '⠏', '⠟', '⠗', '⠎', '⠞', '⠥', '⠧',
+    '⠺', '⠭', '⠽', '⠵',
+];
+
+const PUNCTUATION: &[(char, char)] = &[
+    (',', '⠂'),
+    (';', '⠆'),
+    (':', '⠒'),
+    ('.', '⠲'),
+    ('!', '⠖'),
+    ('?', '⠦'),
+    ('\'', '⠄'),
+    ('-', '⠤'),
+];
+
+pub fn is_braille(c: char) -> bool {
+    (BLANK..=BLANK + 0xFF).contains(&(c as u32))
+}
+
+// Uncontracted (grade 1) English braille in Unicode braille patterns.
+// Capitals get the capital sign and digits the number sign, so both come
+// back on decoding; characters without a cell pass through.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Braille;
+
+impl Braille {
+    pub fn new() -> Self {
+        Self
+    }
+
+    // Cells that mean nothing here are kept and passed to `on_error` with
+    // their position.
+    fn decode_reporting(&self, input: &str, mut on_error: impl FnMut(usize, char)) -> String {
+        let mut out = String::with_capacity(input.len());
+        let mut capital = false;
+        let mut number = false;
+
+        for (position, c) in input.chars().enumerate() {
+            match c {
+                CAPITAL_SIGN => capital = true,
+                NUMBER_SIGN => number = true,
+                LETTER_SIGN => number = false,
+                _ => {
+                    let letter = LETTERS.iter().position(|cell| *cell == c);
+                    match letter {
+                        // a to j are the digits 1 to 9 and 0.
+                        Some(i) if number && i < 10 => out.push(char::from_digit((i as u32 + 1) % 10, 10).unwrap()),
+                        Some(i) => {
+                            let letter = (b'a' + i as u8) as char;
+                            out.push(if capital { letter.to_ascii_uppercase() } else { letter });
+                            number = false;
+                        }
+                        None => {
+                            match PUNCTUATION.iter().find(|(_, cell)| *cell == c) {
+                                Some((punctuation, _)) => out.push(*punctuation),
+                                None if c as u32 == BLANK => out.push(' '),
+                                None if is_braille(c) => {
+                                    on_error(position, c);
+                                    out.push(c);
+                                }
+                                None => out.push(c),
+                            }
+                            number = false;
+                        }
+                    }
+                    capital = false;
+                }
+            }
+        }
+
+        out
+    }
+}
+
+impl Codec for Braille {
+    fn encode(&self, input: &str) -> String {
+        let mut out = String::with_capacity(input.len() * 3);
+        let mut number = false;
+
+        for c in input.chars() {
+            if c.is_ascii_digit() {
+                if !number {
+                    out.push(NUMBER_SIGN);
+                    number = true;
+                }
+                let i = (c.to_digit(10).unwrap() + 9) % 10;
+                out.push(LETTERS[i as usize]);
+                continue;
+            }
+            // A letter straight after a number would read as a digit.
+            if number && matches!(c.to_ascii_lowercase(), 'a'..='j') {
+                out.push(LETTER_SIGN);
+            }
+            number = false;
+
+            if c.is_ascii_alphabetic() {
+                if c.is_ascii_uppercase() {
+                    out.push(CAPITAL_SIGN);
+                }
+                out.push(LETTERS[(c.to_ascii_lowercase() as u8 - b'a') as usize]);
+            } else if let Some((_, cell)) = PUNCTUATION.iter().find(|(p, _)| *p == c) {
+                out.push(*cell);
+            } else {
+                out.push(c);
+            }
+        }
+
+        out
+    }
+
+    fn decode(&self, input: &str) -> String {
+        self.decode_reporting(input, |_, _| {})
+    }
+
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        let mut error = None;
+        let decoded = self.decode_reporting(input, |position, cell| {
+            error.get_or_insert(CodecError::InvalidToken { position, token: cell.to_string() });
+        });
+        match error {
+            Some(error) => Err(error),
+            None => Ok(decoded),
+        }
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        self.decode_reporting(input, |position, cell| {
+            diagnostics.warn(Warning::SkippedToken { position, token: cell.to_string() });
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_letters_as_cells() {
+        assert_eq!(Braille.encode("abc"), "⠁⠃⠉");
+        assert_eq!(Braille.decode("⠁⠃⠉"), "abc");
+    }
+}
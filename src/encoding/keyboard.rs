@@ -0,0 +1,149 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use crate::encoding::Codec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    Qwerty,
+    Dvorak,
+    Azerty,
+}
+
+// Unshifted and shifted characters per row, key for key in the same
+// physical positions across layouts. A space marks a key that has no
+// character at that shift level.
+const QWERTY: [(&str, &str); 4] = [
+    ("`1234567890-=", "~!@#$%^&*()_+"),
+    ("qwertyuiop[]\\", "QWERTYUIOP{}|"),
+    ("asdfghjkl;'", "ASDFGHJKL:\""),
+    ("zxcvbnm,./", "ZXCVBNM<>?"),
+];
+
+const DVORAK: [(&str, &str); 4] = [
+    ("`1234567890[]", "~!@#$%^&*(){}"),
+    ("',.pyfgcrl/=\\", "\"<>PYFGCRL?+|"),
+    ("aoeuidhtns-", "AOEUIDHTNS_"),
+    (";qjkxbmwvz", ":QJKXBMWVZ"),
+];
+
+const AZERTY: [(&str, &str); 4] = [
+    ("²&é\"'(-è_çà)=", " 1234567890°+"),
+    ("azertyuiop^$*", "AZERTYUIOP¨£µ"),
+    ("qsdfghjklmù", "QSDFGHJKLM%"),
+    ("wxcvbn,;:!", "WXCVBN?./§"),
+];
+
+impl Layout {
+    fn rows(&self) -> &'static [(&'static str, &'static str); 4] {
+        match self {
+            Layout::Qwerty => &QWERTY,
+            Layout::Dvorak => &DVORAK,
+            Layout::Azerty => &AZERTY,
+        }
+    }
+
+    fn keys(&self) -> Vec<char> {
+        self.rows()
+            .iter()
+            .flat_map(|(lower, upper)| lower.chars().chain(upper.chars()))
+            .collect()
+    }
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "qwerty" => Ok(Layout::Qwerty),
+            "dvorak" => Ok(Layout::Dvorak),
+            "azerty" => Ok(Layout::Azerty),
+            _ => Err(format!("unknown keyboard layout `{}`", s)),
+        }
+    }
+}
+
+// Encoding turns text typed on `from` into what the same key presses would
+// have produced on `to`; decoding goes the other way. Characters that aren't
+// on both layouts pass through both ways, and so do any whose key would
+// only lead to one of those, so decoding always undoes encoding.
+pub struct KeyboardRemap {
+    forward: HashMap<char, char>,
+    backward: HashMap<char, char>,
+}
+
+impl KeyboardRemap {
+    pub fn new(from: Layout, to: Layout) -> Self {
+        let mut pairs: Vec<(char, char)> =
+            from.keys().into_iter().zip(to.keys()).filter(|&(a, b)| a != ' ' && b != ' ').collect();
+
+        // Keep only pairs that chain into cycles: each character changed
+        // into must itself be changed, and each one changed must be what
+        // something else changes into. Azerty's `é` has no Qwerty key, so
+        // Qwerty's `2` can't become it without `é` becoming something too.
+        loop {
+            let sources: HashSet<char> = pairs.iter().map(|&(a, _)| a).collect();
+            let targets: HashSet<char> = pairs.iter().map(|&(_, b)| b).collect();
+            let before = pairs.len();
+            pairs.retain(|(a, b)| targets.contains(a) && sources.contains(b));
+            if pairs.len() == before {
+                break;
+            }
+        }
+
+        let forward = pairs.iter().copied().collect();
+        let backward = pairs.iter().map(|&(a, b)| (b, a)).collect();
+        Self { forward, backward }
+    }
+
+    fn remap(map: &HashMap<char, char>, input: &str) -> String {
+        input.chars().map(|c| *map.get(&c).unwrap_or(&c)).collect()
+    }
+}
+
+impl Codec for KeyboardRemap {
+    fn encode(&self, input: &str) -> String {
+        KeyboardRemap::remap(&self.forward, input)
+    }
+
+    fn decode(&self, input: &str) -> String {
+        KeyboardRemap::remap(&self.backward, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAYOUTS: [Layout; 3] = [Layout::Qwerty, Layout::Dvorak, Layout::Azerty];
+
+    #[test]
+    fn types_the_same_keys_on_the_other_layout() {
+        let remap = KeyboardRemap::new(Layout::Qwerty, Layout::Dvorak);
+        assert_eq!(remap.encode("hello"), "d.nnr");
+        assert_eq!(remap.decode("d.nnr"), "hello");
+        let remap = KeyboardRemap::new(Layout::Qwerty, Layout::Azerty);
+        assert_eq!(remap.encode("qwam"), "azq,");
+    }
+
+    #[test]
+    fn characters_missing_from_a_layout_pass_through() {
+        let remap = KeyboardRemap::new(Layout::Qwerty, Layout::Azerty);
+        assert_eq!(remap.decode("é"), "é");
+        assert_eq!(remap.encode("é"), "é");
+        assert_eq!(remap.encode("2"), "2");
+    }
+
+    #[test]
+    fn every_pair_of_layouts_round_trips() {
+        let text: String = (' '..='~').chain("²éèçàù°¨£µ§ñ€👍".chars()).collect();
+        for from in LAYOUTS {
+            for to in LAYOUTS {
+                let remap = KeyboardRemap::new(from, to);
+                assert_eq!(remap.decode(&remap.encode(&text)), text, "{:?} to {:?}", from, to);
+                assert_eq!(remap.encode(&remap.decode(&text)), text, "{:?} to {:?}", from, to);
+            }
+        }
+    }
+}
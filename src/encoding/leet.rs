@@ -0,0 +1,203 @@
+use crate::analysis::{LanguageModel, QuadgramModel, Scorer};
+use crate::encoding::Codec;
+
+// Letters and what leet writes for them, the usual one first.
+pub const LEET_TABLE: &[(char, &[&str])] = &[
+    ('a', &["4", "@", "/\\"]),
+    ('b', &["8", "|3"]),
+    ('c', &["(", "<"]),
+    ('e', &["3"]),
+    ('g', &["6", "9"]),
+    ('h', &["#", "|-|"]),
+    ('i', &["1", "|"]),
+    ('k', &["|<"]),
+    ('l', &["1", "|_"]),
+    ('m', &["|\\/|"]),
+    ('n', &["|\\|"]),
+    ('o', &["0", "()"]),
+    ('s', &["5", "$"]),
+    ('t', &["7", "+"]),
+    ('v', &["\\/"]),
+    ('w', &["\\/\\/"]),
+    ('z', &["2"]),
+];
+
+// Readings of one word `normalize` tries before settling for the first.
+const MAX_READINGS: usize = 256;
+
+// Leet speak as a homophonic substitution: each letter has one or more
+// stand-ins. Encoding writes the first, or takes turns through all of
+// them; decoding reads the longest stand-in at each point and, where one
+// stands for several letters (`1` is i or l), picks the first listed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeetSpeak {
+    table: Vec<(char, Vec<String>)>,
+    homophones: bool,
+}
+
+impl LeetSpeak {
+    pub fn new() -> Self {
+        let table = LEET_TABLE.iter().map(|(c, subs)| (*c, subs.iter().map(|s| s.to_string()).collect())).collect();
+        Self { table, homophones: false }
+    }
+
+    // No substitutions; build a table with `with_substitution`.
+    pub fn empty() -> Self {
+        Self { table: Vec::new(), homophones: false }
+    }
+
+    // Replaces the stand-ins for `letter`; none removes it from the table.
+    pub fn with_substitution(mut self, letter: char, substitutes: &[&str]) -> Self {
+        let letter = letter.to_ascii_lowercase();
+        self.table.retain(|(c, _)| *c != letter);
+        let substitutes: Vec<String> = substitutes.iter().filter(|s| !s.is_empty()).map(|s| s.to_string()).collect();
+        if !substitutes.is_empty() {
+            self.table.push((letter, substitutes));
+        }
+        self
+    }
+
+    // Whether encoding takes turns through every stand-in for a letter,
+    // flattening its frequency, instead of always writing the first.
+    pub fn with_homophones(mut self, homophones: bool) -> Self {
+        self.homophones = homophones;
+        self
+    }
+
+    pub fn table(&self) -> &[(char, Vec<String>)] {
+        &self.table
+    }
+
+    // Every way to read the start of `text` as leet, as (letter, length in
+    // bytes), longest first; empty if it isn't a stand-in.
+    fn readings_at(&self, text: &str) -> Vec<(char, usize)> {
+        let mut readings: Vec<(char, usize)> = self
+            .table
+            .iter()
+            .flat_map(|(letter, subs)| subs.iter().filter(|s| text.starts_with(s.as_str())).map(|s| (*letter, s.len())))
+            .collect();
+        let longest = readings.iter().map(|(_, len)| *len).max().unwrap_or(0);
+        readings.retain(|(_, len)| *len == longest);
+        readings
+    }
+
+    // A word as a list of choices: the letters each stand-in could be, or
+    // the character itself.
+    fn parse_word(&self, word: &str) -> Vec<Vec<char>> {
+        let mut choices = Vec::new();
+        let mut rest = word;
+
+        while let Some(c) = rest.chars().next() {
+            let readings = self.readings_at(rest);
+            match readings.first() {
+                Some((_, len)) => {
+                    let len = *len;
+                    choices.push(readings.into_iter().map(|(letter, _)| letter).collect());
+                    rest = &rest[len..];
+                }
+                None => {
+                    choices.push(vec![c]);
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
+        }
+        choices
+    }
+
+    fn decode_words(&self, input: &str, mut choose: impl FnMut(&[Vec<char>]) -> String) -> String {
+        let mut out = String::with_capacity(input.len());
+        let mut rest = input;
+
+        while !rest.is_empty() {
+            let start = rest.find(|c: char| !c.is_whitespace()).unwrap_or(rest.len());
+            out.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let word = &rest[..end];
+
+            out.push_str(&choose(&self.parse_word(word)));
+            rest = &rest[end..];
+        }
+
+        out
+    }
+
+    // Decodes the leet in `text`, and where a stand-in could be more than
+    // one letter, keeps the reading of each word `scorer` likes best, or
+    // on a tie the one with the likelier English letter sequences. For
+    // cleaning leet up before scoring or analysing it.
+    pub fn normalize(&self, text: &str, scorer: &dyn Scorer) -> String {
+        let rate = |reading: &str| (scorer.score(reading), QuadgramModel::english().score(reading));
+
+        self.decode_words(text, |choices| {
+            let first: String = choices.iter().map(|c| c[0]).collect();
+            let count = choices.iter().try_fold(1usize, |n, c| n.checked_mul(c.len()).filter(|n| *n <= MAX_READINGS));
+            let Some(count) = count else {
+                return first;
+            };
+
+            let mut best = (rate(&first), first);
+            for mut n in 1..count {
+                let reading: String = choices
+                    .iter()
+                    .map(|c| {
+                        let letter = c[n % c.len()];
+                        n /= c.len();
+                        letter
+                    })
+                    .collect();
+                let rating = rate(&reading);
+                if rating > best.0 {
+                    best = (rating, reading);
+                }
+            }
+            best.1
+        })
+    }
+}
+
+impl Default for LeetSpeak {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Codec for LeetSpeak {
+    fn encode(&self, input: &str) -> String {
+        let mut turns = vec![0; self.table.len()];
+
+        input
+            .chars()
+            .map(|c| {
+                let lower = c.to_ascii_lowercase();
+                match self.table.iter().position(|(letter, _)| *letter == lower) {
+                    Some(i) => {
+                        let subs = &self.table[i].1;
+                        let sub = &subs[turns[i] % subs.len()];
+                        if self.homophones {
+                            turns[i] += 1;
+                        }
+                        sub.clone()
+                    }
+                    None => c.to_string(),
+                }
+            })
+            .collect()
+    }
+
+    fn decode(&self, input: &str) -> String {
+        self.decode_words(input, |choices| choices.iter().map(|c| c[0]).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_the_usual_stand_ins() {
+        let leet = LeetSpeak::new();
+        assert_eq!(leet.encode("leet speak"), "1337 5p34|<");
+        assert_eq!(leet.decode("h3ll0 5p34|<"), "hello speak");
+    }
+}
@@ -0,0 +1,131 @@
+use std::fmt;
+
+use crate::diagnostics::{Diagnostics, Warning};
+use crate::registry::Registry;
+
+pub mod ascii;
+pub mod baconian;
+pub mod base_n;
+pub mod braille;
+pub mod ebcdic;
+pub mod keyboard;
+pub mod leet;
+pub mod morse;
+#[cfg(feature = "audio")]
+pub mod morse_audio;
+pub mod morse_lang;
+pub mod ogham;
+pub mod pigpen;
+#[cfg(feature = "svg")]
+pub mod pigpen_svg;
+pub mod rot;
+pub mod semaphore;
+pub mod web;
+
+pub use ascii::{BinaryAscii, DecimalAscii};
+pub use baconian::{BaconVariant, Baconian};
+pub use base_n::{Ascii85, Base32, Base58, Base64, Hex, Z85};
+pub use braille::Braille;
+pub use ebcdic::{CodePage, Ebcdic};
+pub use keyboard::{KeyboardRemap, Layout};
+pub use leet::LeetSpeak;
+pub use morse::{Morse, MorseBuilder, MorseError, MorseSignal, Substitution, TimingUnit};
+#[cfg(feature = "audio")]
+pub use morse_audio::AudioSettings;
+pub use morse_lang::MorseLang;
+pub use ogham::Ogham;
+pub use pigpen::{Pigpen, PigpenShape, PigpenSymbol};
+#[cfg(feature = "svg")]
+pub use pigpen_svg::SvgSettings;
+pub use rot::{Rot13, Rot47};
+pub use semaphore::{FlagSemaphore, SemaphoreStyle};
+pub use web::{HtmlEntities, UrlEncoding};
+
+pub trait Codec {
+    fn encode(&self, input: &str) -> String;
+    fn decode(&self, input: &str) -> String;
+
+    // Like `decode`, but fails on input `decode` would skip or patch up.
+    // Codecs that can't tell fall back to `decode`.
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        Ok(self.decode(input))
+    }
+
+    // Like `encode` and `decode`, reporting what gets skipped to
+    // `diagnostics`. Codecs that never skip anything needn't override them.
+    fn encode_with_diagnostics(&self, input: &str, _diagnostics: &Diagnostics) -> String {
+        self.encode(input)
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, _diagnostics: &Diagnostics) -> String {
+        self.decode(input)
+    }
+
+    // `decode_with_diagnostics` with the tokens it skipped gathered up, so
+    // callers can tell which positions failed without a `Diagnostics`.
+    fn decode_outcome(&self, input: &str) -> DecodeOutcome {
+        let diagnostics = Diagnostics::new();
+        let text = self.decode_with_diagnostics(input, &diagnostics);
+        let skipped = diagnostics
+            .take()
+            .into_iter()
+            .filter_map(|warning| match warning {
+                Warning::SkippedToken { position, token } => Some((position, token)),
+                _ => None,
+            })
+            .collect();
+        DecodeOutcome { text, skipped }
+    }
+}
+
+// What a lenient decode produced, and the tokens it dropped along the way
+// as (position, token), positions counting as in `CodecError`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeOutcome {
+    pub text: String,
+    pub skipped: Vec<(usize, String)>,
+}
+
+impl DecodeOutcome {
+    pub fn is_lossless(&self) -> bool {
+        self.skipped.is_empty()
+    }
+
+    // The strict reading: the text, or the first skipped token.
+    pub fn into_result(self) -> Result<String, CodecError> {
+        match self.skipped.into_iter().next() {
+            Some((position, token)) => Err(CodecError::InvalidToken { position, token }),
+            None => Ok(self.text),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodecError {
+    // `position` counts tokens (Morse letters, binary groups, ...) from 0.
+    InvalidToken { position: usize, token: String },
+    InvalidInput(String),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CodecError::InvalidToken { position, token } => write!(f, "invalid token `{}` at position {}", token, position),
+            CodecError::InvalidInput(message) => write!(f, "invalid input: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+pub const CODEC_NAMES: &[&str] = &[
+    "base64", "base64url", "base32", "base58", "ascii85", "z85", "hex", "binary", "decimal", "rot13", "rot47", "morse",
+    "bacon", "bacon26", "cp037", "cp500", "ogham", "qwerty-dvorak", "qwerty-azerty", "dvorak-azerty", "url", "url-form",
+    "html", "html-numeric", "braille", "semaphore", "semaphore-arrows", "pigpen", "leet",
+];
+
+// Any codec `Registry::shared` makes: the ones above, their aliases, and
+// settings after a colon (`morse:russian`).
+pub fn codec_by_name(name: &str) -> Option<Box<dyn Codec>> {
+    Registry::shared().create_codec(name).ok()
+}
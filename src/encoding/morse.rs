@@ -0,0 +1,714 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::analysis::{LanguageModel, QuadgramModel};
+use crate::diagnostics::{Diagnostics, Warning};
+use crate::encoding::morse_lang::MorseLang;
+use crate::encoding::{Codec, CodecError};
+use crate::result::DecodedResult;
+
+pub const INTERNATIONAL_MORSE: &[(char, &str)] = &[
+    ('A', ".-"), ('B', "-..."), ('C', "-.-."), ('D', "-.."), ('E', "."),
+    ('F', "..-."), ('G', "--."), ('H', "...."), ('I', ".."), ('J', ".---"),
+    ('K', "-.-"), ('L', ".-.."), ('M', "--"), ('N', "-."), ('O', "---"),
+    ('P', ".--."), ('Q', "--.-"), ('R', ".-."), ('S', "..."), ('T', "-"),
+    ('U', "..-"), ('V', "...-"), ('W', ".--"), ('X', "-..-"), ('Y', "-.--"),
+    ('Z', "--.."),
+    ('0', "-----"), ('1', ".----"), ('2', "..---"), ('3', "...--"), ('4', "....-"),
+    ('5', "....."), ('6', "-...."), ('7', "--..."), ('8', "---.."), ('9', "----."),
+    ('.', ".-.-.-"), (',', "--..--"), ('?', "..--.."), ('\'', ".----."), ('!', "-.-.--"),
+    ('/', "-..-."), ('(', "-.--."), (')', "-.--.-"), ('&', ".-..."), (':', "---..."),
+    (';', "-.-.-."), ('=', "-...-"), ('+', ".-.-."), ('-', "-....-"), ('_', "..--.-"),
+    ('"', ".-..-."), ('$', "...-..-"), ('@', ".--.-."),
+];
+
+// Procedural signals, sent as one run-together character. Some share a
+// code with punctuation (AR is `+`, BT is `=`, KN is `(`, AS is `&`).
+pub const PROSIGNS: &[(&str, &str)] = &[
+    ("AR", ".-.-."), ("AS", ".-..."), ("BT", "-...-"), ("CT", "-.-.-"), ("HH", "........"),
+    ("KN", "-.--."), ("SK", "...-.-"), ("SN", "...-."), ("SOS", "...---..."),
+];
+
+// Glyphs that show up in place of '.' and '-' when Morse is copied from
+// web pages, word processors or books.
+pub const DEFAULT_GLYPHS: &[(char, char)] = &[
+    ('·', '.'), ('•', '.'), ('∙', '.'), ('⋅', '.'),
+    ('_', '-'), ('–', '-'), ('—', '-'), ('−', '-'),
+];
+
+// Read only in lenient mode, on top of the glyphs: symbols too ambiguous
+// to accept everywhere, and characters people use to break words.
+pub const LENIENT_GLYPHS: &[(char, char)] = &[('*', '.')];
+pub const LENIENT_WORD_BREAKS: &[char] = &['|'];
+
+// Runs of this many spaces or more are a word gap in lenient mode.
+const LENIENT_WORD_GAP: usize = 3;
+
+pub const WORD_SEPARATOR: &str = "/";
+
+// Partial readings `decode_unseparated` keeps at each point in the code.
+const SEGMENTATION_BEAM: usize = 256;
+const LETTER_BONUS: f64 = 2.5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MorseError {
+    UnknownLanguage(String),
+}
+
+impl fmt::Display for MorseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MorseError::UnknownLanguage(name) => {
+                let names: Vec<&str> = MorseLang::ALL.iter().map(MorseLang::name).collect();
+                write!(f, "unknown Morse language `{}`, expected one of: {}", name, names.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for MorseError {}
+
+// Something lenient decoding read as something else, and how often.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Substitution {
+    pub from: String,
+    pub to: String,
+    pub count: usize,
+}
+
+impl From<Substitution> for Warning {
+    fn from(substitution: Substitution) -> Self {
+        Warning::Substituted { from: substitution.from, to: substitution.to, count: substitution.count }
+    }
+}
+
+// Key-down or key-up for `duration`, in the unit the sequence was asked for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MorseSignal {
+    pub on: bool,
+    pub duration: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimingUnit {
+    Dits,
+    // Milliseconds at the given words per minute (PARIS, 1200 / wpm per dit).
+    Milliseconds(f64),
+}
+
+impl TimingUnit {
+    fn dit(&self) -> f64 {
+        match self {
+            TimingUnit::Dits => 1.0,
+            TimingUnit::Milliseconds(wpm) => 1200.0 / wpm.max(1.0),
+        }
+    }
+}
+
+pub struct Morse {
+    map: HashMap<char, String>,
+    rev_map: HashMap<String, char>,
+    prosigns: HashMap<String, String>,
+    rev_prosigns: HashMap<String, String>,
+    prosign_notation: bool,
+    lenient: bool,
+    lang: MorseLang,
+    glyphs: HashMap<char, char>,
+    dot: char,
+    dash: char,
+    letter_separator: String,
+    word_separator: String,
+}
+
+// Builds a `Morse` that reads and writes another tool's format, e.g.
+// `_` for dash or `|` between words.
+pub struct MorseBuilder {
+    glyphs: Vec<(char, char)>,
+    mappings: Vec<(char, String)>,
+    prosign_notation: bool,
+    lenient: bool,
+    lang: MorseLang,
+    dot: char,
+    dash: char,
+    letter_separator: String,
+    word_separator: String,
+}
+
+impl MorseBuilder {
+    pub fn new() -> Self {
+        Self {
+            glyphs: DEFAULT_GLYPHS.to_vec(),
+            mappings: Vec::new(),
+            prosign_notation: false,
+            lenient: false,
+            lang: MorseLang::International,
+            dot: '.',
+            dash: '-',
+            letter_separator: " ".to_string(),
+            word_separator: format!(" {} ", WORD_SEPARATOR),
+        }
+    }
+
+    // Replaces the glyphs accepted as '.' and '-' when decoding.
+    pub fn glyphs(mut self, glyphs: &[(char, char)]) -> Self {
+        self.glyphs = glyphs.to_vec();
+        self
+    }
+
+    // Adds `c`, or changes its code, on top of the language's table. The
+    // code is written in '.' and '-'; codes with anything else are ignored.
+    // A character that had the code before still encodes to it, but
+    // decodes as `c` from then on.
+    pub fn mapping(mut self, c: char, code: &str) -> Self {
+        if !code.is_empty() && code.chars().all(|symbol| symbol == '.' || symbol == '-') {
+            self.mappings.push((c, code.to_string()));
+        }
+        self
+    }
+
+    pub fn prosign_notation(mut self, prosign_notation: bool) -> Self {
+        self.prosign_notation = prosign_notation;
+        self
+    }
+
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    pub fn lang(mut self, lang: MorseLang) -> Self {
+        self.lang = lang;
+        self
+    }
+
+    pub fn dot(mut self, dot: char) -> Self {
+        self.dot = dot;
+        self
+    }
+
+    pub fn dash(mut self, dash: char) -> Self {
+        self.dash = dash;
+        self
+    }
+
+    // Empty separators are ignored.
+    pub fn letter_separator(mut self, separator: &str) -> Self {
+        if !separator.is_empty() {
+            self.letter_separator = separator.to_string();
+        }
+        self
+    }
+
+    pub fn word_separator(mut self, separator: &str) -> Self {
+        if !separator.is_empty() {
+            self.word_separator = separator.to_string();
+        }
+        self
+    }
+
+    pub fn build(self) -> Morse {
+        let mut map = HashMap::new();
+        let mut rev_map = HashMap::new();
+
+        for (letter, code) in self.lang.international().chain(self.lang.table()) {
+            map.insert(*letter, code.to_string());
+            rev_map.insert(code.to_string(), *letter);
+        }
+        for (c, code) in self.mappings {
+            if let Some(old) = map.insert(c, code.clone()) {
+                if rev_map.get(&old) == Some(&c) {
+                    rev_map.remove(&old);
+                }
+            }
+            rev_map.insert(code, c);
+        }
+
+        let prosigns = PROSIGNS.iter().map(|(name, code)| (name.to_string(), code.to_string())).collect();
+        let rev_prosigns = PROSIGNS.iter().map(|(name, code)| (code.to_string(), name.to_string())).collect();
+
+        // The chosen symbols win over any glyph, and separator characters
+        // must survive normalization untouched.
+        let mut glyphs: HashMap<char, char> = self.glyphs.into_iter().collect();
+        for c in self.letter_separator.chars().chain(self.word_separator.chars()) {
+            glyphs.remove(&c);
+        }
+        glyphs.insert(self.dot, '.');
+        glyphs.insert(self.dash, '-');
+
+        Morse {
+            map,
+            rev_map,
+            prosigns,
+            rev_prosigns,
+            prosign_notation: self.prosign_notation,
+            lenient: self.lenient,
+            lang: self.lang,
+            glyphs,
+            dot: self.dot,
+            dash: self.dash,
+            letter_separator: self.letter_separator,
+            word_separator: self.word_separator,
+        }
+    }
+}
+
+impl Default for MorseBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Morse {
+    pub fn new() -> Self {
+        MorseBuilder::new().build()
+    }
+
+    pub fn builder() -> MorseBuilder {
+        MorseBuilder::new()
+    }
+
+    // Unknown names fall back to International; `try_from_lang` reports
+    // them instead.
+    pub fn from_lang(name: &str) -> Self {
+        Self::try_from_lang(name).unwrap_or_default()
+    }
+
+    pub fn try_from_lang(name: &str) -> Result<Self, MorseError> {
+        Ok(MorseBuilder::new().lang(name.parse()?).build())
+    }
+
+    pub fn lang(&self) -> MorseLang {
+        self.lang
+    }
+
+    // The code `c` encodes to, in '.' and '-'.
+    pub fn code_for(&self, c: char) -> Option<&str> {
+        self.map.get(&c).map(String::as_str)
+    }
+
+    // The character `code`, in '.' and '-', decodes to, leaving prosigns
+    // aside.
+    pub fn char_for(&self, code: &str) -> Option<char> {
+        self.rev_map.get(code).copied()
+    }
+
+    // Every character this instance encodes, with its code, sorted by
+    // character.
+    pub fn iter_mappings(&self) -> impl Iterator<Item = (char, &str)> + '_ {
+        let mut mappings: Vec<(char, &str)> = self.map.iter().map(|(c, code)| (*c, code.as_str())).collect();
+        mappings.sort_unstable();
+        mappings.into_iter()
+    }
+
+    pub fn with_glyphs(glyphs: &[(char, char)]) -> Self {
+        MorseBuilder::new().glyphs(glyphs).build()
+    }
+
+    // Decode prosigns as `<AR>`, `<SK>`, ... instead of the punctuation that
+    // shares their code, or their bare letters.
+    pub fn with_prosign_notation(mut self, prosign_notation: bool) -> Self {
+        self.prosign_notation = prosign_notation;
+        self
+    }
+
+    // Decoding also reads `*` as a dot, `|` and runs of three or more
+    // spaces as word breaks, and reports what it read differently as
+    // `Warning::Substituted`. Neither is ever a separator of this format.
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    // Decodes leniently whatever `with_lenient` says, and lists what was
+    // read as something else.
+    pub fn decode_lenient(&self, input: &str) -> (String, Vec<Substitution>) {
+        let (text, substitutions) = self.lenient_input(input);
+        (self.decode_standard(&self.to_standard(&text)), substitutions)
+    }
+
+    // Reads a stream of dots and dashes sent without letter gaps, in this
+    // format and with or without word separators, as the `top` likeliest
+    // texts by English quadgrams. Only letters are tried, and readings of
+    // under four letters, which have no quadgram, rank last. The key is the
+    // code split into letters.
+    pub fn decode_unseparated(&self, code: &str, top: usize) -> Vec<DecodedResult> {
+        self.decode_unseparated_with_model(code, top, QuadgramModel::english())
+    }
+
+    pub fn decode_unseparated_with_model(
+        &self,
+        code: &str,
+        top: usize,
+        model: &dyn LanguageModel,
+    ) -> Vec<DecodedResult> {
+        // Word breaks become ' '; everything else that isn't a dot or dash
+        // is dropped.
+        let mut symbols: Vec<char> = Vec::new();
+        for word in self.to_standard(code).split(WORD_SEPARATOR) {
+            let word: Vec<char> = word.chars().filter(|c| *c == '.' || *c == '-').collect();
+            if !word.is_empty() {
+                if !symbols.is_empty() {
+                    symbols.push(' ');
+                }
+                symbols.extend(word);
+            }
+        }
+
+        let letters: HashMap<&str, char> =
+            self.rev_map.iter().filter(|(_, c)| c.is_alphabetic()).map(|(code, c)| (code.as_str(), *c)).collect();
+        let longest = letters.keys().map(|code| code.len()).max().unwrap_or(0);
+
+        // The quadgram sum, plus a bonus per letter so that readings with
+        // more, shorter letters aren't penalized just for having more
+        // quadgrams. Readings too short to have a quadgram get `None`.
+        let rate = |text: &str| {
+            let count = text.chars().filter(char::is_ascii_alphabetic).count();
+            (count >= 4).then(|| model.score(text) + LETTER_BONUS * count as f64)
+        };
+
+        // (rating, text, letters as codes) for the code up to each point.
+        type Reading = (Option<f64>, String, Vec<String>);
+        let mut beams: Vec<Vec<Reading>> = vec![Vec::new(); symbols.len() + 1];
+        beams[0].push((None, String::new(), Vec::new()));
+        for i in 0..symbols.len() {
+            // Short readings are all kept, and sort last.
+            let mut beam = std::mem::take(&mut beams[i]);
+            beam.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+            beam.dedup_by(|a, b| a.1 == b.1);
+            let short = beam.iter().filter(|(rating, _, _)| rating.is_none()).count();
+            beam.truncate(SEGMENTATION_BEAM + short);
+
+            for (rating, text, mut codes) in beam {
+                if symbols[i] == ' ' {
+                    codes.push(WORD_SEPARATOR.to_string());
+                    beams[i + 1].push((rating, format!("{} ", text), codes));
+                    continue;
+                }
+                for len in 1..=longest.min(symbols.len() - i) {
+                    let piece: String = symbols[i..i + len].iter().collect();
+                    if piece.contains(' ') {
+                        break;
+                    }
+                    if let Some(letter) = letters.get(piece.as_str()) {
+                        let text = format!("{}{}", text, letter);
+                        let mut codes = codes.clone();
+                        codes.push(piece);
+                        beams[i + len].push((rate(&text), text, codes));
+                    }
+                }
+            }
+        }
+
+        let mut results: Vec<DecodedResult> = beams
+            .pop()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(_, text, _)| !text.is_empty())
+            .map(|(rating, text, codes)| {
+                let score = rating.unwrap_or(f64::NEG_INFINITY);
+                DecodedResult::new(codes.join(" "), self.lang.finish(text), score).with_scorer("quadgram")
+            })
+            .collect();
+        crate::result::rank(&mut results);
+        results.dedup_by(|a, b| a.text == b.text);
+        results.truncate(top);
+        results
+    }
+
+    // Rewrites the lenient extras, and any glyph that isn't this format's
+    // own dot or dash, to what `to_standard` reads.
+    fn lenient_input(&self, input: &str) -> (String, Vec<Substitution>) {
+        let mut substitutions: Vec<Substitution> = Vec::new();
+        let mut note = |from: &str, to: &str| match substitutions.iter_mut().find(|s| s.from == from && s.to == to) {
+            Some(substitution) => substitution.count += 1,
+            None => substitutions.push(Substitution { from: from.to_string(), to: to.to_string(), count: 1 }),
+        };
+
+        let is_separator = |c: char| self.letter_separator.contains(c) || self.word_separator.contains(c);
+        let word_break = self.word_separator.trim();
+        let word_break = if word_break.is_empty() { self.word_separator.as_str() } else { word_break };
+        let space_breaks = self.letter_separator.trim().is_empty() && !self.word_separator.trim().is_empty();
+
+        let mut text = String::with_capacity(input.len());
+        let mut chars = input.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == ' ' && space_breaks {
+                let mut run = 1;
+                while chars.next_if_eq(&' ').is_some() {
+                    run += 1;
+                }
+                if run >= LENIENT_WORD_GAP {
+                    note(&" ".repeat(LENIENT_WORD_GAP), word_break);
+                    text.push_str(&format!(" {} ", word_break));
+                } else {
+                    text.push(' ');
+                }
+            } else if LENIENT_WORD_BREAKS.contains(&c) && !is_separator(c) {
+                note(&c.to_string(), word_break);
+                text.push_str(&format!(" {} ", word_break));
+            } else if let Some(symbol) = self.glyphs.get(&c).or_else(|| lenient_glyph(c).filter(|_| !is_separator(c))) {
+                if c != self.dot && c != self.dash && c != *symbol {
+                    note(&c.to_string(), &symbol.to_string());
+                }
+                let symbol = if *symbol == '-' { self.dash } else { self.dot };
+                text.push(symbol);
+            } else {
+                text.push(c);
+            }
+        }
+
+        (text, substitutions)
+    }
+
+    // Input in this format as `decode_tokens` reads it, leniently if
+    // asked to.
+    fn standard_input(&self, input: &str, diagnostics: Option<&Diagnostics>) -> String {
+        if !self.lenient {
+            return self.to_standard(input);
+        }
+        let (text, substitutions) = self.lenient_input(input);
+        if let Some(diagnostics) = diagnostics {
+            for substitution in substitutions {
+                diagnostics.warn(substitution.into());
+            }
+        }
+        self.to_standard(&text)
+    }
+
+    // Codes in '.'/'-' form, one list per word.
+    fn encode_codes(&self, text: &str, diagnostics: Option<&Diagnostics>) -> Vec<Vec<String>> {
+        text.split_whitespace()
+            .map(|word| self.encode_word(word, diagnostics))
+            .filter(|codes| !codes.is_empty())
+            .collect()
+    }
+
+    // `<AR>`-style prosigns in the text are sent as one character.
+    fn encode_word(&self, word: &str, diagnostics: Option<&Diagnostics>) -> Vec<String> {
+        let mut codes = Vec::new();
+        let mut rest = word;
+
+        while let Some(c) = rest.chars().next() {
+            if c == '<' {
+                if let Some(end) = rest.find('>') {
+                    if let Some(code) = self.prosigns.get(&rest[1..end].to_ascii_uppercase()) {
+                        codes.push(code.clone());
+                        rest = &rest[end + 1..];
+                        continue;
+                    }
+                }
+            }
+
+            let mut skipped = false;
+            for letter in self.lang.prepare(c).chars() {
+                match self.map.get(&letter) {
+                    Some(code) => codes.push(code.clone()),
+                    None => skipped = true,
+                }
+            }
+            if let (true, Some(diagnostics)) = (skipped, diagnostics) {
+                diagnostics.warn(Warning::SkippedCharacter(c));
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+
+        codes
+    }
+
+    fn decode_code(&self, code: &str) -> Option<String> {
+        let prosign = self.rev_prosigns.get(code);
+
+        match (prosign, self.rev_map.get(code)) {
+            (Some(name), _) if self.prosign_notation => Some(format!("<{}>", name)),
+            (_, Some(c)) => Some(c.to_string()),
+            (Some(name), None) => Some(name.clone()),
+            (None, None) => None,
+        }
+    }
+
+    // Standard timing: dit 1, dah 3, gap 1 inside a letter, 3 between
+    // letters and 7 between words.
+    pub fn encode_timing(&self, text: &str, unit: TimingUnit) -> Vec<MorseSignal> {
+        let dit = unit.dit();
+        let mut signals = Vec::new();
+        let mut push = |on: bool, dits: f64| signals.push(MorseSignal { on, duration: dits * dit });
+
+        for (w, word) in self.encode_codes(text, None).iter().enumerate() {
+            if w > 0 {
+                push(false, 7.0);
+            }
+            for (l, letter) in word.iter().enumerate() {
+                if l > 0 {
+                    push(false, 3.0);
+                }
+                for (s, symbol) in letter.chars().enumerate() {
+                    if s > 0 {
+                        push(false, 1.0);
+                    }
+                    push(true, if symbol == '-' { 3.0 } else { 1.0 });
+                }
+            }
+        }
+
+        signals
+    }
+
+    // Inverse of `encode_timing`. Durations are split at the midpoints
+    // between the nominal lengths (2 and 5 dits), which copes with timing
+    // off by well over 20%. Adjacent signals in the same state are merged.
+    pub fn decode_timing(&self, signals: &[MorseSignal], unit: TimingUnit) -> String {
+        let dit = unit.dit();
+        let mut merged: Vec<MorseSignal> = Vec::new();
+        for signal in signals {
+            match merged.last_mut() {
+                Some(last) if last.on == signal.on => last.duration += signal.duration,
+                _ => merged.push(*signal),
+            }
+        }
+
+        let mut code = String::new();
+        for signal in merged {
+            let dits = signal.duration / dit;
+            match (signal.on, dits) {
+                (true, d) if d < 2.0 => code.push('.'),
+                (true, _) => code.push('-'),
+                (false, d) if d < 2.0 => {}
+                (false, d) if d < 5.0 => code.push(' '),
+                (false, _) => code.push_str(&format!(" {} ", WORD_SEPARATOR)),
+            }
+        }
+
+        self.decode_standard(&code)
+    }
+
+    // `symbol` must be '.' or '-'; anything else is ignored.
+    pub fn add_glyph(&mut self, glyph: char, symbol: char) {
+        if symbol == '.' || symbol == '-' {
+            self.glyphs.insert(glyph, symbol);
+        }
+    }
+
+    pub fn clear_glyphs(&mut self) {
+        self.glyphs.clear();
+    }
+
+    pub fn normalize(&self, input: &str) -> String {
+        input
+            .chars()
+            .map(|c| *self.glyphs.get(&c).unwrap_or(&c))
+            .collect()
+    }
+
+    // Rewrites input in this instance's format to '.'/'-' with " " between
+    // letters and " / " between words. Separators match with or without
+    // the whitespace around them.
+    fn to_standard(&self, input: &str) -> String {
+        let separator = |s: &str| if s.trim().is_empty() { s.to_string() } else { s.trim().to_string() };
+        let word_separator = separator(&self.word_separator);
+        let letter_separator = separator(&self.letter_separator);
+
+        // Words first, through a placeholder, in case one separator is part
+        // of the other.
+        let mut text = self.normalize(input);
+        if word_separator != WORD_SEPARATOR {
+            text = text.replace(&word_separator, "\0");
+        }
+        if !letter_separator.trim().is_empty() {
+            text = text.replace(&letter_separator, " ");
+        }
+        text.replace("\0", &format!(" {} ", WORD_SEPARATOR))
+    }
+
+    fn decode_standard(&self, code: &str) -> String {
+        self.decode_tokens(code, false, None).unwrap_or_default()
+    }
+
+    // Unknown codes are skipped, and reported if there is somewhere to
+    // report them, or fail the decode when `strict`.
+    fn decode_tokens(&self, code: &str, strict: bool, diagnostics: Option<&Diagnostics>) -> Result<String, CodecError> {
+        let mut words = Vec::new();
+        let mut position = 0;
+
+        for word in code.split(WORD_SEPARATOR) {
+            let mut decoded = String::new();
+            for token in word.split_whitespace() {
+                match self.decode_code(token) {
+                    Some(text) => decoded.push_str(&text),
+                    None if strict => {
+                        return Err(CodecError::InvalidToken { position, token: token.to_string() });
+                    }
+                    None => {
+                        if let Some(diagnostics) = diagnostics {
+                            diagnostics.warn(Warning::SkippedToken { position, token: token.to_string() });
+                        }
+                    }
+                }
+                position += 1;
+            }
+            if !decoded.is_empty() {
+                words.push(decoded);
+            }
+        }
+
+        Ok(self.lang.finish(words.join(" ")))
+    }
+}
+
+fn lenient_glyph(c: char) -> Option<&'static char> {
+    LENIENT_GLYPHS.iter().find(|(glyph, _)| *glyph == c).map(|(_, symbol)| symbol)
+}
+
+impl Default for Morse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Morse {
+    fn format_codes(&self, words: Vec<Vec<String>>) -> String {
+        words
+            .iter()
+            .map(|word| {
+                word.iter()
+                    .map(|code| code.chars().map(|c| if c == '-' { self.dash } else { self.dot }).collect())
+                    .collect::<Vec<String>>()
+                    .join(&self.letter_separator)
+            })
+            .collect::<Vec<String>>()
+            .join(&self.word_separator)
+    }
+}
+
+impl Codec for Morse {
+    fn encode(&self, input: &str) -> String {
+        self.format_codes(self.encode_codes(input, None))
+    }
+
+    fn decode(&self, input: &str) -> String {
+        self.decode_standard(&self.standard_input(input, None))
+    }
+
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        self.decode_tokens(&self.standard_input(input, None), true, None)
+    }
+
+    fn encode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        self.format_codes(self.encode_codes(input, Some(diagnostics)))
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        self.decode_tokens(&self.standard_input(input, Some(diagnostics)), false, Some(diagnostics)).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_international_morse() {
+        let morse = Morse::new();
+        assert_eq!(morse.encode("SOS 5"), "... --- ... / .....");
+        assert_eq!(morse.decode("... --- ... / ....."), "SOS 5");
+    }
+}
@@ -0,0 +1,167 @@
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::encoding::morse::{Morse, TimingUnit};
+
+pub const DEFAULT_WPM: f64 = 20.0;
+pub const DEFAULT_FREQUENCY: f64 = 600.0;
+pub const DEFAULT_SAMPLE_RATE: u32 = 44_100;
+
+// Tones fade in and out over this long so keying doesn't click.
+const RAMP_SECONDS: f64 = 0.005;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioSettings {
+    wpm: f64,
+    // Effective speed when sending Farnsworth-style: characters keep `wpm`
+    // but the gaps between them are stretched to this overall speed.
+    farnsworth_wpm: Option<f64>,
+    frequency: f64,
+    sample_rate: u32,
+    volume: f64,
+}
+
+impl AudioSettings {
+    pub fn new() -> Self {
+        Self {
+            wpm: DEFAULT_WPM,
+            farnsworth_wpm: None,
+            frequency: DEFAULT_FREQUENCY,
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            volume: 0.8,
+        }
+    }
+
+    pub fn with_wpm(mut self, wpm: f64) -> Self {
+        self.wpm = wpm.max(1.0);
+        self
+    }
+
+    // Ignored unless slower than the character speed.
+    pub fn with_farnsworth(mut self, wpm: f64) -> Self {
+        self.farnsworth_wpm = Some(wpm.max(1.0));
+        self
+    }
+
+    pub fn with_frequency(mut self, frequency: f64) -> Self {
+        self.frequency = frequency.max(1.0);
+        self
+    }
+
+    pub fn with_sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = sample_rate.max(1);
+        self
+    }
+
+    // 0.0 to 1.0 of full scale.
+    pub fn with_volume(mut self, volume: f64) -> Self {
+        self.volume = volume.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    // PARIS timing: one dit lasts 1.2 / wpm seconds.
+    fn dit_seconds(&self) -> f64 {
+        1.2 / self.wpm
+    }
+
+    // Length of one unit of inter-character and inter-word gap. With
+    // Farnsworth spacing the 19 gap units in PARIS absorb the extra time
+    // (ARRL formula).
+    fn gap_unit_seconds(&self) -> f64 {
+        match self.farnsworth_wpm {
+            Some(effective) if effective < self.wpm => (60.0 / effective - 37.2 / self.wpm) / 19.0,
+            _ => self.dit_seconds(),
+        }
+    }
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Tone<'a> {
+    settings: &'a AudioSettings,
+    samples: Vec<i16>,
+}
+
+impl Tone<'_> {
+    fn seconds_to_samples(&self, seconds: f64) -> usize {
+        (seconds * self.settings.sample_rate as f64).round() as usize
+    }
+
+    fn on(&mut self, seconds: f64) {
+        let rate = self.settings.sample_rate as f64;
+        let len = self.seconds_to_samples(seconds);
+        let ramp = self.seconds_to_samples(RAMP_SECONDS).min(len / 2).max(1);
+        let amplitude = self.settings.volume * i16::MAX as f64;
+
+        for i in 0..len {
+            let envelope = (i.min(len - 1 - i) as f64 / ramp as f64).min(1.0);
+            let wave = (2.0 * PI * self.settings.frequency * i as f64 / rate).sin();
+            self.samples.push((wave * envelope * amplitude) as i16);
+        }
+    }
+
+    fn off(&mut self, seconds: f64) {
+        let len = self.seconds_to_samples(seconds);
+        self.samples.extend(std::iter::repeat_n(0, len));
+    }
+}
+
+impl Morse {
+    // Encodes `text` and renders it as mono 16-bit PCM at the settings'
+    // sample rate.
+    pub fn to_audio(&self, text: &str, settings: &AudioSettings) -> Vec<i16> {
+        let dit = settings.dit_seconds();
+        let gap = settings.gap_unit_seconds();
+        let mut tone = Tone { settings, samples: Vec::new() };
+
+        // Gaps longer than a dit are the ones Farnsworth spacing stretches.
+        for signal in self.encode_timing(text, TimingUnit::Dits) {
+            match (signal.on, signal.duration) {
+                (true, dits) => tone.on(dits * dit),
+                (false, dits) if dits > 1.0 => tone.off(dits * gap),
+                (false, dits) => tone.off(dits * dit),
+            }
+        }
+
+        tone.samples
+    }
+
+    pub fn export_wav<P: AsRef<Path>>(&self, text: &str, settings: &AudioSettings, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write_wav(&mut writer, &self.to_audio(text, settings), settings.sample_rate())?;
+        writer.flush()
+    }
+}
+
+// Writes mono 16-bit PCM samples as a RIFF/WAVE file.
+pub fn write_wav<W: Write>(writer: &mut W, samples: &[i16], sample_rate: u32) -> io::Result<()> {
+    let data_len = (samples.len() * 2) as u32;
+
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&(36 + data_len).to_le_bytes())?;
+    writer.write_all(b"WAVEfmt ")?;
+    writer.write_all(&16u32.to_le_bytes())?;
+    writer.write_all(&1u16.to_le_bytes())?; // PCM
+    writer.write_all(&1u16.to_le_bytes())?; // mono
+    writer.write_all(&sample_rate.to_le_bytes())?;
+    writer.write_all(&(sample_rate * 2).to_le_bytes())?;
+    writer.write_all(&2u16.to_le_bytes())?;
+    writer.write_all(&16u16.to_le_bytes())?;
+    writer.write_all(b"data")?;
+    writer.write_all(&data_len.to_le_bytes())?;
+
+    for sample in samples {
+        writer.write_all(&sample.to_le_bytes())?;
+    }
+    Ok(())
+}
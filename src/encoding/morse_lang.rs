@@ -0,0 +1,251 @@
+use std::str::FromStr;
+
+use crate::classical::alphabet::strip_diacritics;
+use crate::encoding::morse::{MorseError, INTERNATIONAL_MORSE};
+use crate::error::Error;
+use crate::json::Json;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MorseLang {
+    #[default]
+    International,
+    German,
+    Russian,
+    Greek,
+    // Wabun code, for katakana. Hiragana is sent as katakana.
+    Japanese,
+    // Sent as Telex: diacritics become trailing letters (`ế` is `ees`), so
+    // decoding gives Telex text back.
+    Vietnamese,
+}
+
+const GERMAN_MORSE: &[(char, &str)] = &[('Ä', ".-.-"), ('Ö', "---."), ('Ü', "..--"), ('ß', "...--..")];
+
+const RUSSIAN_MORSE: &[(char, &str)] = &[
+    ('А', ".-"), ('Б', "-..."), ('В', ".--"), ('Г', "--."), ('Д', "-.."), ('Е', "."),
+    ('Ж', "...-"), ('З', "--.."), ('И', ".."), ('Й', ".---"), ('К', "-.-"), ('Л', ".-.."),
+    ('М', "--"), ('Н', "-."), ('О', "---"), ('П', ".--."), ('Р', ".-."), ('С', "..."),
+    ('Т', "-"), ('У', "..-"), ('Ф', "..-."), ('Х', "...."), ('Ц', "-.-."), ('Ч', "---."),
+    ('Ш', "----"), ('Щ', "--.-"), ('Ъ', "--.--"), ('Ы', "-.--"), ('Ь', "-..-"), ('Э', "..-.."),
+    ('Ю', "..--"), ('Я', ".-.-"),
+];
+
+const GREEK_MORSE: &[(char, &str)] = &[
+    ('Α', ".-"), ('Β', "-..."), ('Γ', "--."), ('Δ', "-.."), ('Ε', "."), ('Ζ', "--.."),
+    ('Η', "...."), ('Θ', "-.-."), ('Ι', ".."), ('Κ', "-.-"), ('Λ', ".-.."), ('Μ', "--"),
+    ('Ν', "-."), ('Ξ', "-..-"), ('Ο', "---"), ('Π', ".--."), ('Ρ', ".-."), ('Σ', "..."),
+    ('Τ', "-"), ('Υ', "-.--"), ('Φ', "..-."), ('Χ', "----"), ('Ψ', "--.-"), ('Ω', ".--"),
+];
+
+const WABUN_MORSE: &[(char, &str)] = &[
+    ('イ', ".-"), ('ロ', ".-.-"), ('ハ', "-..."), ('ニ', "-.-."), ('ホ', "-.."), ('ヘ', "."),
+    ('ト', "..-.."), ('チ', "..-."), ('リ', "--."), ('ヌ', "...."), ('ル', "-.--."), ('ヲ', ".---"),
+    ('ワ', "-.-"), ('カ', ".-.."), ('ヨ', "--"), ('タ', "-."), ('レ', "---"), ('ソ', "---."),
+    ('ツ', ".--."), ('ネ', "--.-"), ('ナ', ".-."), ('ラ', "..."), ('ム', "-"), ('ウ', "..-"),
+    ('ヰ', ".-..-"), ('ノ', "..--"), ('オ', ".-..."), ('ク', "...-"), ('ヤ', ".--"), ('マ', "-..-"),
+    ('ケ', "-.--"), ('フ', "--.."), ('コ', "----"), ('エ', "-.---"), ('テ', ".-.--"), ('ア', "--.--"),
+    ('サ', "-.-.-"), ('キ', "-.-.."), ('ユ', "-..--"), ('メ', "-...-"), ('ミ', "..-.-"), ('シ', "--.-."),
+    ('ヱ', ".--.."), ('ヒ', "--..-"), ('モ', "-..-."), ('セ', ".---."), ('ス', "---.-"), ('ン', ".-.-."),
+    (DAKUTEN, ".."), (HANDAKUTEN, "..--."), ('ー', ".--.-"), ('、', ".-.-.-"), ('」', ".-.-.."),
+];
+
+const DAKUTEN: char = '゛';
+const HANDAKUTEN: char = '゜';
+
+// Katakana that take a dakuten as the next code point (ガ is カ + 1), and
+// the ハ row, which also takes a handakuten two code points on (パ is ハ + 2).
+const VOICEABLE: &str = "カキクケコサシスセソタチツテトウ";
+const SEMI_VOICEABLE: &str = "ハヒフヘホ";
+const SMALL_KANA: &str = "ァィゥェォッャュョヮ";
+
+// Each row is a Vietnamese vowel with no tone, then huyền, sắc, hỏi, ngã
+// and nặng, followed by how Telex types the bare vowel.
+const VIETNAMESE_VOWELS: &[(&str, &str)] = &[
+    ("aàáảãạ", "a"), ("ăằắẳẵặ", "aw"), ("âầấẩẫậ", "aa"), ("eèéẻẽẹ", "e"),
+    ("êềếểễệ", "ee"), ("iìíỉĩị", "i"), ("oòóỏõọ", "o"), ("ôồốổỗộ", "oo"),
+    ("ơờớởỡợ", "ow"), ("uùúủũụ", "u"), ("ưừứửữự", "uw"), ("yỳýỷỹỵ", "y"),
+];
+const TELEX_TONES: [&str; 6] = ["", "f", "s", "r", "x", "j"];
+
+impl MorseLang {
+    pub const ALL: &'static [MorseLang] = &[
+        MorseLang::International,
+        MorseLang::German,
+        MorseLang::Russian,
+        MorseLang::Greek,
+        MorseLang::Japanese,
+        MorseLang::Vietnamese,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MorseLang::International => "international",
+            MorseLang::German => "german",
+            MorseLang::Russian => "russian",
+            MorseLang::Greek => "greek",
+            MorseLang::Japanese => "japanese",
+            MorseLang::Vietnamese => "vietnamese",
+        }
+    }
+
+    // Codes for this language on top of the international ones. When a
+    // code is shared, these letters win on decoding.
+    pub(crate) fn table(&self) -> &'static [(char, &'static str)] {
+        match self {
+            MorseLang::International | MorseLang::Vietnamese => &[],
+            MorseLang::German => GERMAN_MORSE,
+            MorseLang::Russian => RUSSIAN_MORSE,
+            MorseLang::Greek => GREEK_MORSE,
+            MorseLang::Japanese => WABUN_MORSE,
+        }
+    }
+
+    // Wabun reuses the Latin letter codes, so Latin letters can't be
+    // told apart from kana and are left out.
+    pub(crate) fn keeps_latin(&self) -> bool {
+        *self != MorseLang::Japanese
+    }
+
+    pub(crate) fn international(&self) -> impl Iterator<Item = &'static (char, &'static str)> {
+        let keeps_latin = self.keeps_latin();
+        INTERNATIONAL_MORSE.iter().filter(move |(c, _)| keeps_latin || !c.is_ascii_alphabetic())
+    }
+
+    // Rewrites one input character into the characters the table has
+    // codes for.
+    pub(crate) fn prepare(&self, c: char) -> String {
+        match self {
+            MorseLang::Russian => match c {
+                'ё' | 'Ё' => "Е".to_string(),
+                _ => c.to_uppercase().collect(),
+            },
+            MorseLang::Greek => strip_diacritics(c).to_uppercase().collect(),
+            MorseLang::Japanese => decompose_kana(c),
+            MorseLang::Vietnamese => telex(c),
+            // Upper-casing would turn it into "SS".
+            MorseLang::German if c == 'ß' => c.to_string(),
+            MorseLang::International | MorseLang::German => c.to_uppercase().collect(),
+        }
+    }
+
+    // Undoes what `prepare` split up, where that can be done unambiguously.
+    pub(crate) fn finish(&self, text: String) -> String {
+        match self {
+            MorseLang::Japanese => compose_kana(&text),
+            _ => text,
+        }
+    }
+}
+
+impl FromStr for MorseLang {
+    type Err = MorseError;
+
+    // Full names, ISO 639-1 codes and a few common aliases.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "international" | "itu" | "en" | "english" | "latin" => Ok(MorseLang::International),
+            "german" | "de" | "deutsch" => Ok(MorseLang::German),
+            "russian" | "ru" | "cyrillic" => Ok(MorseLang::Russian),
+            "greek" | "el" => Ok(MorseLang::Greek),
+            "japanese" | "ja" | "wabun" => Ok(MorseLang::Japanese),
+            "vietnamese" | "vi" | "telex" => Ok(MorseLang::Vietnamese),
+            _ => Err(MorseError::UnknownLanguage(s.to_string())),
+        }
+    }
+}
+
+impl From<MorseLang> for Json {
+    fn from(lang: MorseLang) -> Self {
+        lang.name().into()
+    }
+}
+
+impl TryFrom<&Json> for MorseLang {
+    type Error = Error;
+
+    fn try_from(json: &Json) -> crate::Result<Self> {
+        let name = json.as_str().ok_or_else(|| Error::InvalidJson("expected a language name".to_string()))?;
+        name.parse().map_err(|e: MorseError| Error::InvalidJson(e.to_string()))
+    }
+}
+
+fn offset(c: char, by: i32) -> Option<char> {
+    char::from_u32((c as i32 + by) as u32)
+}
+
+fn decompose_kana(c: char) -> String {
+    // Hiragana sit 0x60 below their katakana.
+    let c = match c {
+        'ぁ'..='ゖ' => offset(c, 0x60).unwrap_or(c),
+        _ => c,
+    };
+
+    if SMALL_KANA.contains(c) {
+        return offset(c, 1).unwrap_or(c).to_string();
+    }
+    if c == 'ヴ' {
+        return format!("ウ{}", DAKUTEN);
+    }
+    for (mark, by) in [(DAKUTEN, 1), (HANDAKUTEN, 2)] {
+        if let Some(base) = offset(c, -by) {
+            let takes_mark = match mark {
+                DAKUTEN => VOICEABLE.contains(base) || SEMI_VOICEABLE.contains(base),
+                _ => SEMI_VOICEABLE.contains(base),
+            };
+            if takes_mark && base != 'ウ' {
+                return format!("{}{}", base, mark);
+            }
+        }
+    }
+
+    c.to_string()
+}
+
+fn compose_kana(text: &str) -> String {
+    let mut out: Vec<char> = Vec::new();
+
+    for c in text.chars() {
+        let composed = match (out.last(), c) {
+            (Some('ウ'), DAKUTEN) => Some('ヴ'),
+            (Some(&base), DAKUTEN) if VOICEABLE.contains(base) || SEMI_VOICEABLE.contains(base) => offset(base, 1),
+            (Some(&base), HANDAKUTEN) if SEMI_VOICEABLE.contains(base) => offset(base, 2),
+            _ => None,
+        };
+
+        match composed {
+            Some(composed) => *out.last_mut().unwrap() = composed,
+            None => out.push(c),
+        }
+    }
+
+    out.into_iter().collect()
+}
+
+fn telex(c: char) -> String {
+    let lower = c.to_lowercase().next().unwrap_or(c);
+
+    if lower == 'đ' {
+        return "DD".to_string();
+    }
+    for (forms, base) in VIETNAMESE_VOWELS {
+        if let Some(tone) = forms.chars().position(|f| f == lower) {
+            return format!("{}{}", base, TELEX_TONES[tone]).to_uppercase();
+        }
+    }
+
+    c.to_uppercase().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::morse::Morse;
+    use crate::encoding::Codec;
+
+    #[test]
+    fn russian_has_its_own_letters() {
+        let morse = Morse::builder().lang(MorseLang::Russian).build();
+        assert_eq!(morse.encode("ПРИВЕТ"), ".--. .-. .. .-- . -");
+        assert_eq!(morse.decode(".--. .-. .. .-- . -"), "ПРИВЕТ");
+    }
+}
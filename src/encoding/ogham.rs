@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+
+use crate::diagnostics::{Diagnostics, Warning};
+use crate::encoding::Codec;
+
+pub const SPACE_MARK: char = '\u{1680}';
+pub const FEATHER_MARK: char = '\u{169B}';
+pub const REVERSED_FEATHER_MARK: char = '\u{169C}';
+
+// Ogham letters and their usual Latin transliteration, in Unicode order.
+pub const OGHAM_LETTERS: &[(char, &str)] = &[
+    ('ᚁ', "B"), ('ᚂ', "L"), ('ᚃ', "F"), ('ᚄ', "S"), ('ᚅ', "N"),
+    ('ᚆ', "H"), ('ᚇ', "D"), ('ᚈ', "T"), ('ᚉ', "C"), ('ᚊ', "Q"),
+    ('ᚋ', "M"), ('ᚌ', "G"), ('ᚍ', "NG"), ('ᚎ', "Z"), ('ᚏ', "R"),
+    ('ᚐ', "A"), ('ᚑ', "O"), ('ᚒ', "U"), ('ᚓ', "E"), ('ᚔ', "I"),
+    ('ᚕ', "EA"), ('ᚖ', "OI"), ('ᚗ', "UI"), ('ᚘ', "IA"), ('ᚙ', "AE"),
+    ('ᚚ', "P"),
+];
+
+// Latin letters with no Ogham letter of their own are written with the
+// closest one, so these don't survive a round trip.
+const SUBSTITUTES: &[(char, &str)] = &[
+    ('J', "I"), ('K', "C"), ('V', "F"), ('W', "F"), ('X', "CS"), ('Y', "I"),
+];
+
+// Lossy for J, K, V, W, X and Y, which come back as the letters in
+// `SUBSTITUTES`; `encode_with_diagnostics` reports each one.
+pub struct Ogham {
+    feathers: bool,
+}
+
+impl Ogham {
+    pub fn new() -> Self {
+        Self { feathers: true }
+    }
+
+    // Whether encoded text is wrapped in the ᚛ ... ᚜ feather marks.
+    pub fn with_feathers(mut self, feathers: bool) -> Self {
+        self.feathers = feathers;
+        self
+    }
+
+    fn letter_for(latin: &str) -> Option<char> {
+        OGHAM_LETTERS.iter().find(|(_, l)| *l == latin).map(|(o, _)| *o)
+    }
+
+    fn encode_reporting(&self, input: &str, diagnostics: Option<&Diagnostics>) -> String {
+        let upper: Vec<char> = input.to_uppercase().chars().collect();
+        let mut encoded = String::new();
+        let mut substituted: BTreeMap<char, (&str, usize)> = BTreeMap::new();
+        let mut i = 0;
+
+        if self.feathers {
+            encoded.push(FEATHER_MARK);
+        }
+
+        while i < upper.len() {
+            let c = upper[i];
+
+            if c == 'N' && upper.get(i + 1) == Some(&'G') {
+                encoded.push('ᚍ');
+                i += 2;
+                continue;
+            }
+
+            if c.is_whitespace() {
+                encoded.push(SPACE_MARK);
+            } else if let Some(letter) = Ogham::letter_for(&c.to_string()) {
+                encoded.push(letter);
+            } else if let Some((_, latin)) = SUBSTITUTES.iter().find(|(s, _)| *s == c) {
+                encoded.extend(latin.chars().filter_map(|l| Ogham::letter_for(&l.to_string())));
+                substituted.entry(c).or_insert((latin, 0)).1 += 1;
+            } else {
+                encoded.push(c);
+            }
+            i += 1;
+        }
+
+        if self.feathers {
+            encoded.push(REVERSED_FEATHER_MARK);
+        }
+
+        if let Some(diagnostics) = diagnostics {
+            for (c, (latin, count)) in substituted {
+                diagnostics.warn(Warning::Substituted { from: c.to_string(), to: latin.to_string(), count });
+            }
+        }
+
+        encoded
+    }
+}
+
+impl Default for Ogham {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Codec for Ogham {
+    fn encode(&self, input: &str) -> String {
+        self.encode_reporting(input, None)
+    }
+
+    fn encode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        self.encode_reporting(input, Some(diagnostics))
+    }
+
+    fn decode(&self, input: &str) -> String {
+        input
+            .chars()
+            .filter(|c| *c != FEATHER_MARK && *c != REVERSED_FEATHER_MARK)
+            .map(|c| match OGHAM_LETTERS.iter().find(|(o, _)| *o == c) {
+                Some((_, latin)) => latin.to_string(),
+                None if c == SPACE_MARK => " ".to_string(),
+                None => c.to_string(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_between_feather_marks() {
+        assert_eq!(Ogham::new().encode("BEITH"), "᚛ᚁᚓᚔᚈᚆ᚜");
+        assert_eq!(Ogham::new().decode("᚛ᚁᚓᚔᚈᚆ᚜"), "BEITH");
+        assert_eq!(Ogham::new().with_feathers(false).encode("NG"), "ᚍ");
+    }
+
+    #[test]
+    fn reports_letters_written_with_another() {
+        let diagnostics = Diagnostics::new();
+        let encoded = Ogham::new().encode_with_diagnostics("HELLO WORLD", &diagnostics);
+        assert_eq!(Ogham::new().decode(&encoded), "HELLO FORLD");
+        assert_eq!(
+            diagnostics.take(),
+            [Warning::Substituted { from: "W".to_string(), to: "F".to_string(), count: 1 }]
+        );
+    }
+}
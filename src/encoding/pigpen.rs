@@ -0,0 +1,189 @@
+use crate::diagnostics::{Diagnostics, Warning};
+use crate::encoding::{Codec, CodecError};
+
+const WORD_SEPARATOR: &str = "/";
+
+// What each grid cell looks like, row by row: the lines around it that
+// the noughts-and-crosses grid draws.
+const GRID_GLYPHS: [char; 9] = ['⌟', '⊔', '⌞', '⊐', '□', '⊏', '⌝', '⊓', '⌜'];
+
+// The quarters of the X: top, left, right and bottom.
+const CROSS_GLYPHS: [char; 4] = ['∨', '>', '<', '∧'];
+
+const DOT: char = '•';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PigpenShape {
+    // A cell of the grid, 0 to 8 row by row.
+    Grid(usize),
+    // A quarter of the X, 0 to 3: top, left, right, bottom.
+    Cross(usize),
+}
+
+// One pigpen letter: A-I are the grid, J-R the grid with a dot, S-V the
+// X and W-Z the X with a dot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PigpenSymbol {
+    pub shape: PigpenShape,
+    pub dot: bool,
+}
+
+impl PigpenSymbol {
+    pub fn for_letter(letter: char) -> Option<Self> {
+        if !letter.is_ascii_alphabetic() {
+            return None;
+        }
+        let i = (letter.to_ascii_uppercase() as u8 - b'A') as usize;
+        let (shape, dot) = match i {
+            0..=8 => (PigpenShape::Grid(i), false),
+            9..=17 => (PigpenShape::Grid(i - 9), true),
+            18..=21 => (PigpenShape::Cross(i - 18), false),
+            _ => (PigpenShape::Cross(i - 22), true),
+        };
+        Some(Self { shape, dot })
+    }
+
+    pub fn letter(&self) -> char {
+        let i = match (self.shape, self.dot) {
+            (PigpenShape::Grid(cell), false) => cell,
+            (PigpenShape::Grid(cell), true) => cell + 9,
+            (PigpenShape::Cross(quarter), false) => quarter + 18,
+            (PigpenShape::Cross(quarter), true) => quarter + 22,
+        };
+        (b'A' + i as u8) as char
+    }
+
+    // The symbol as text: the outline, followed by `•` if it has a dot.
+    pub fn glyph(&self) -> String {
+        let outline = match self.shape {
+            PigpenShape::Grid(cell) => GRID_GLYPHS[cell],
+            PigpenShape::Cross(quarter) => CROSS_GLYPHS[quarter],
+        };
+        if self.dot {
+            format!("{}{}", outline, DOT)
+        } else {
+            outline.to_string()
+        }
+    }
+
+    // Reads `glyph` back; `.` will do for the dot.
+    pub fn parse(token: &str) -> Option<Self> {
+        let mut chars = token.chars();
+        let outline = chars.next()?;
+        let dot = match chars.next() {
+            None => false,
+            Some(DOT | '.') if chars.next().is_none() => true,
+            Some(_) => return None,
+        };
+        let shape = match GRID_GLYPHS.iter().position(|g| *g == outline) {
+            Some(cell) => PigpenShape::Grid(cell),
+            None => PigpenShape::Cross(CROSS_GLYPHS.iter().position(|g| *g == outline)?),
+        };
+        Some(Self { shape, dot })
+    }
+}
+
+// The pigpen (masonic) cipher written with look-alike characters, one
+// token per letter, the letters separated by spaces and the words by
+// " / ". Pigpen has no case, digits or punctuation, so those are lost.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Pigpen;
+
+impl Pigpen {
+    pub fn new() -> Self {
+        Self
+    }
+
+    // Characters with no symbol are dropped and passed to `on_error` with
+    // their position.
+    fn encode_reporting(&self, input: &str, mut on_error: impl FnMut(usize, char)) -> String {
+        let mut words = Vec::new();
+        let mut tokens = Vec::new();
+
+        for (position, c) in input.chars().enumerate() {
+            if c.is_whitespace() {
+                if !tokens.is_empty() {
+                    words.push(tokens.join(" "));
+                    tokens.clear();
+                }
+            } else {
+                match PigpenSymbol::for_letter(c) {
+                    Some(symbol) => tokens.push(symbol.glyph()),
+                    None => on_error(position, c),
+                }
+            }
+        }
+        if !tokens.is_empty() {
+            words.push(tokens.join(" "));
+        }
+
+        words.join(&format!(" {} ", WORD_SEPARATOR))
+    }
+
+    // Unknown tokens are skipped and passed to `on_error` with their
+    // position among the tokens.
+    fn decode_reporting(&self, input: &str, mut on_error: impl FnMut(usize, &str)) -> String {
+        let mut words = Vec::new();
+        let mut position = 0;
+
+        for word in input.split(WORD_SEPARATOR) {
+            let mut decoded = String::new();
+            for token in word.split_whitespace() {
+                match PigpenSymbol::parse(token) {
+                    Some(symbol) => decoded.push(symbol.letter()),
+                    None => on_error(position, token),
+                }
+                position += 1;
+            }
+            if !decoded.is_empty() {
+                words.push(decoded);
+            }
+        }
+
+        words.join(" ")
+    }
+}
+
+impl Codec for Pigpen {
+    fn encode(&self, input: &str) -> String {
+        self.encode_reporting(input, |_, _| {})
+    }
+
+    fn decode(&self, input: &str) -> String {
+        self.decode_reporting(input, |_, _| {})
+    }
+
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        let mut error = None;
+        let decoded = self.decode_reporting(input, |position, token| {
+            error.get_or_insert(CodecError::InvalidToken { position, token: token.to_string() });
+        });
+        match error {
+            Some(error) => Err(error),
+            None => Ok(decoded),
+        }
+    }
+
+    fn encode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        self.encode_reporting(input, |position, c| {
+            diagnostics.warn(Warning::SkippedToken { position, token: c.to_string() });
+        })
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        self.decode_reporting(input, |position, token| {
+            diagnostics.warn(Warning::SkippedToken { position, token: token.to_string() });
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_a_symbol_per_letter() {
+        assert_eq!(Pigpen.encode("AB"), "⌟ ⊔");
+        assert_eq!(Pigpen.decode("⌟ ⊔"), "AB");
+    }
+}
@@ -0,0 +1,184 @@
+use std::fmt::Write;
+
+use crate::encoding::pigpen::{Pigpen, PigpenShape, PigpenSymbol};
+
+pub const DEFAULT_CELL_SIZE: f64 = 48.0;
+pub const DEFAULT_COLUMNS: usize = 12;
+
+// Space around each symbol inside its cell, as a share of the cell.
+const PADDING: f64 = 0.15;
+// Height of the answer line under each row, as a share of the cell.
+const ANSWER_HEIGHT: f64 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SvgSettings {
+    cell_size: f64,
+    // Symbols per row before wrapping; a new line in the text also wraps.
+    columns: usize,
+    stroke_width: f64,
+    // Whether each symbol has its letter printed under it, for an answer
+    // sheet, or a blank line to write it on.
+    answers: bool,
+}
+
+impl SvgSettings {
+    pub fn new() -> Self {
+        Self { cell_size: DEFAULT_CELL_SIZE, columns: DEFAULT_COLUMNS, stroke_width: 3.0, answers: false }
+    }
+
+    pub fn with_cell_size(mut self, cell_size: f64) -> Self {
+        self.cell_size = cell_size.max(8.0);
+        self
+    }
+
+    pub fn with_columns(mut self, columns: usize) -> Self {
+        self.columns = columns.max(1);
+        self
+    }
+
+    pub fn with_stroke_width(mut self, stroke_width: f64) -> Self {
+        self.stroke_width = stroke_width.max(0.5);
+        self
+    }
+
+    pub fn with_answers(mut self, answers: bool) -> Self {
+        self.answers = answers;
+        self
+    }
+}
+
+impl Default for SvgSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pigpen {
+    // A printable worksheet: the text in pigpen, with a line under each
+    // symbol for the answer. Spaces leave a gap; anything else without a
+    // symbol is left out.
+    pub fn to_svg(&self, text: &str, settings: &SvgSettings) -> String {
+        let size = settings.cell_size;
+        let row_height = size * (1.0 + ANSWER_HEIGHT);
+
+        // (column, row, letter) for every symbol.
+        let mut cells = Vec::new();
+        let (mut column, mut row) = (0, 0);
+        for c in text.chars() {
+            if c == '\n' {
+                (column, row) = (0, row + 1);
+                continue;
+            }
+            if !c.is_whitespace() && !c.is_ascii_alphabetic() {
+                continue;
+            }
+            if column == settings.columns {
+                (column, row) = (0, row + 1);
+            }
+            // No gap at the start of a row.
+            if c.is_whitespace() && column == 0 {
+                continue;
+            }
+            if !c.is_whitespace() {
+                cells.push((column, row, c));
+            }
+            column += 1;
+        }
+
+        let width = round(settings.columns as f64 * size);
+        let height = round((row + 1) as f64 * row_height);
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+            w = width,
+            h = height
+        );
+        let _ = writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>");
+        let _ = writeln!(
+            svg,
+            "<g fill=\"none\" stroke=\"black\" stroke-width=\"{}\" stroke-linecap=\"round\" stroke-linejoin=\"round\">",
+            settings.stroke_width
+        );
+
+        for (column, row, letter) in &cells {
+            let x = *column as f64 * size;
+            let y = *row as f64 * row_height;
+            let symbol = PigpenSymbol::for_letter(*letter).expect("only letters are laid out");
+            draw_symbol(&mut svg, &symbol, x, y, size, settings.stroke_width);
+
+            let base = round(y + size * (1.0 + ANSWER_HEIGHT * 0.7));
+            if settings.answers {
+                let _ = writeln!(
+                    svg,
+                    "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" fill=\"black\" \
+                     stroke=\"none\" text-anchor=\"middle\">{}</text>",
+                    round(x + size / 2.0),
+                    base,
+                    round(size * ANSWER_HEIGHT * 0.6),
+                    letter.to_ascii_uppercase()
+                );
+            } else {
+                let (start, end) = (round(x + size * PADDING), round(x + size * (1.0 - PADDING)));
+                let _ = writeln!(svg, "<path d=\"M{} {} H{}\" stroke-width=\"1\"/>", start, base, end);
+            }
+        }
+
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+}
+
+// Coordinates to two decimals, so the file isn't full of float noise.
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn draw_symbol(svg: &mut String, symbol: &PigpenSymbol, x: f64, y: f64, size: f64, stroke_width: f64) {
+    let (left, top) = (round(x + size * PADDING), round(y + size * PADDING));
+    let (right, bottom) = (round(x + size * (1.0 - PADDING)), round(y + size * (1.0 - PADDING)));
+    let (middle_x, middle_y) = (round(x + size / 2.0), round(y + size / 2.0));
+
+    let (path, dot) = match symbol.shape {
+        // The grid's lines are on the sides facing the other cells.
+        PigpenShape::Grid(cell) => {
+            let (row, column) = (cell / 3, cell % 3);
+            let mut path = String::new();
+            let sides = [
+                (row > 0, (left, top), (right, top)),
+                (column < 2, (right, top), (right, bottom)),
+                (row < 2, (left, bottom), (right, bottom)),
+                (column > 0, (left, top), (left, bottom)),
+            ];
+            for (drawn, (x1, y1), (x2, y2)) in sides {
+                if drawn {
+                    let _ = write!(path, "M{} {} L{} {} ", x1, y1, x2, y2);
+                }
+            }
+            (path, (middle_x, middle_y))
+        }
+        // Each quarter of the X is a chevron opening away from the centre,
+        // with its dot in the opening.
+        PigpenShape::Cross(quarter) => {
+            let reach = round((right - left) * 0.3);
+            let ((x1, y1), (x2, y2), (x3, y3), dot) = match quarter {
+                0 => ((left, top), (middle_x, bottom), (right, top), (middle_x, top + reach)),
+                1 => ((left, top), (right, middle_y), (left, bottom), (left + reach, middle_y)),
+                2 => ((right, top), (left, middle_y), (right, bottom), (right - reach, middle_y)),
+                _ => ((left, bottom), (middle_x, top), (right, bottom), (middle_x, bottom - reach)),
+            };
+            (format!("M{} {} L{} {} L{} {}", x1, y1, x2, y2, x3, y3), dot)
+        }
+    };
+
+    let _ = writeln!(svg, "<path d=\"{}\"/>", path.trim_end());
+    if symbol.dot {
+        let _ = writeln!(
+            svg,
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"black\" stroke=\"none\"/>",
+            round(dot.0),
+            round(dot.1),
+            stroke_width * 1.5
+        );
+    }
+}
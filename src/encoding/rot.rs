@@ -0,0 +1,62 @@
+use crate::encoding::Codec;
+
+// Caesar shift by 13: letters only, and its own inverse.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rot13;
+
+// Rotates the 94 printable ASCII characters from `!` to `~` by 47, so
+// digits and punctuation are scrambled too. Its own inverse.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rot47;
+
+pub fn rot13(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'A'..='Z' => ((c as u8 - b'A' + 13) % 26 + b'A') as char,
+            'a'..='z' => ((c as u8 - b'a' + 13) % 26 + b'a') as char,
+            _ => c,
+        })
+        .collect()
+}
+
+pub fn rot47(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '!'..='~' => ((c as u8 - b'!' + 47) % 94 + b'!') as char,
+            _ => c,
+        })
+        .collect()
+}
+
+impl Codec for Rot13 {
+    fn encode(&self, input: &str) -> String {
+        rot13(input)
+    }
+
+    fn decode(&self, input: &str) -> String {
+        rot13(input)
+    }
+}
+
+impl Codec for Rot47 {
+    fn encode(&self, input: &str) -> String {
+        rot47(input)
+    }
+
+    fn decode(&self, input: &str) -> String {
+        rot47(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_letters_or_printable_ascii() {
+        assert_eq!(Rot13.encode("Hello"), "Uryyb");
+        assert_eq!(Rot13.decode("Uryyb"), "Hello");
+        assert_eq!(Rot47.encode("Hello"), "w6==@");
+        assert_eq!(Rot47.decode("w6==@"), "Hello");
+    }
+}
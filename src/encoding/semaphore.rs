@@ -0,0 +1,206 @@
+use crate::diagnostics::{Diagnostics, Warning};
+use crate::encoding::{Codec, CodecError};
+
+const WORD_SEPARATOR: &str = "/";
+
+// The eight arm positions as the reader sees the signaller, clockwise from
+// straight down, with the arrow that draws each.
+pub const POSITIONS: [(&str, char); 8] =
+    [("S", '↓'), ("SW", '↙'), ("W", '←'), ("NW", '↖'), ("N", '↑'), ("NE", '↗'), ("E", '→'), ("SE", '↘')];
+
+// Both arms for each letter, as indices into `POSITIONS`, lower first.
+const LETTERS: [(usize, usize); 26] = [
+    (0, 1), (0, 2), (0, 3), (0, 4), (0, 5), (0, 6), (0, 7), (1, 2), (1, 3), (4, 6), (1, 4), (1, 5), (1, 6),
+    (1, 7), (2, 3), (2, 4), (2, 5), (2, 6), (2, 7), (3, 4), (3, 5), (4, 7), (5, 6), (5, 7), (3, 6), (6, 7),
+];
+
+// After the numeral sign A to I are 1 to 9 and K is 0, until J, which
+// doubles as the sign for letters.
+const NUMERAL_SIGN: (usize, usize) = (4, 5);
+const LETTER_SIGN: usize = 9;
+const ZERO: usize = 10;
+
+// Both arms down: the rest position, read as a space.
+const REST: (usize, usize) = (0, 0);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SemaphoreStyle {
+    // Compass positions joined by a dash, e.g. `S-SW`.
+    #[default]
+    Names,
+    // A pair of arrows, e.g. `↓↙`.
+    Arrows,
+}
+
+// Flag semaphore, one token per letter, the letters separated by spaces and
+// the words by " / ", as in Morse. Decoding reads either style, and the
+// two arms in either order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FlagSemaphore {
+    style: SemaphoreStyle,
+}
+
+impl FlagSemaphore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_style(mut self, style: SemaphoreStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn style(&self) -> SemaphoreStyle {
+        self.style
+    }
+
+    fn token(&self, (first, second): (usize, usize)) -> String {
+        let ((first_name, first_arrow), (second_name, second_arrow)) = (POSITIONS[first], POSITIONS[second]);
+        match self.style {
+            SemaphoreStyle::Names => format!("{}-{}", first_name, second_name),
+            SemaphoreStyle::Arrows => format!("{}{}", first_arrow, second_arrow),
+        }
+    }
+
+    fn parse_token(token: &str) -> Option<(usize, usize)> {
+        let position = |name: &str| POSITIONS.iter().position(|(n, _)| n.eq_ignore_ascii_case(name));
+        let arrow = |c: char| POSITIONS.iter().position(|(_, a)| *a == c);
+
+        let arms = match token.split_once('-') {
+            Some((first, second)) => (position(first)?, position(second)?),
+            None => {
+                // Emoji arrows may carry a variation selector.
+                let mut arrows = token.chars().filter(|c| *c != '\u{fe0f}');
+                let arms = (arrow(arrows.next()?)?, arrow(arrows.next()?)?);
+                if arrows.next().is_some() {
+                    return None;
+                }
+                arms
+            }
+        };
+        Some((arms.0.min(arms.1), arms.0.max(arms.1)))
+    }
+
+    fn letter(arms: (usize, usize)) -> Option<usize> {
+        LETTERS.iter().position(|letter| *letter == arms)
+    }
+
+    // Characters semaphore has no signal for are dropped and passed to
+    // `on_error` with their position.
+    fn encode_reporting(&self, input: &str, mut on_error: impl FnMut(usize, char)) -> String {
+        let mut words = Vec::new();
+        let mut tokens = Vec::new();
+        let mut number = false;
+
+        for (position, c) in input.chars().enumerate() {
+            if c.is_whitespace() {
+                if !tokens.is_empty() {
+                    words.push(tokens.join(" "));
+                    tokens.clear();
+                }
+                number = false;
+            } else if let Some(digit) = c.to_digit(10) {
+                if !number {
+                    tokens.push(self.token(NUMERAL_SIGN));
+                    number = true;
+                }
+                let letter = if digit == 0 { ZERO } else { digit as usize - 1 };
+                tokens.push(self.token(LETTERS[letter]));
+            } else if c.is_ascii_alphabetic() {
+                if number {
+                    tokens.push(self.token(LETTERS[LETTER_SIGN]));
+                    number = false;
+                }
+                tokens.push(self.token(LETTERS[(c.to_ascii_uppercase() as u8 - b'A') as usize]));
+            } else {
+                on_error(position, c);
+            }
+        }
+        if !tokens.is_empty() {
+            words.push(tokens.join(" "));
+        }
+
+        words.join(&format!(" {} ", WORD_SEPARATOR))
+    }
+
+    // Unknown tokens are skipped and passed to `on_error` with their
+    // position among the tokens.
+    fn decode_reporting(&self, input: &str, mut on_error: impl FnMut(usize, &str)) -> String {
+        let mut words = Vec::new();
+        let mut position = 0;
+
+        for word in input.split(WORD_SEPARATOR) {
+            let mut decoded = String::new();
+            let mut number = false;
+
+            for token in word.split_whitespace() {
+                match Self::parse_token(token) {
+                    Some(NUMERAL_SIGN) => number = true,
+                    Some(REST) => decoded.push(' '),
+                    Some(arms) => match Self::letter(arms) {
+                        Some(LETTER_SIGN) if number => number = false,
+                        Some(ZERO) if number => decoded.push('0'),
+                        Some(letter) if number && letter < LETTER_SIGN => {
+                            decoded.push(char::from_digit(letter as u32 + 1, 10).unwrap())
+                        }
+                        Some(letter) => decoded.push((b'A' + letter as u8) as char),
+                        None => on_error(position, token),
+                    },
+                    None => on_error(position, token),
+                }
+                position += 1;
+            }
+
+            if !decoded.is_empty() {
+                words.push(decoded);
+            }
+        }
+
+        words.join(" ")
+    }
+}
+
+impl Codec for FlagSemaphore {
+    fn encode(&self, input: &str) -> String {
+        self.encode_reporting(input, |_, _| {})
+    }
+
+    fn decode(&self, input: &str) -> String {
+        self.decode_reporting(input, |_, _| {})
+    }
+
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        let mut error = None;
+        let decoded = self.decode_reporting(input, |position, token| {
+            error.get_or_insert(CodecError::InvalidToken { position, token: token.to_string() });
+        });
+        match error {
+            Some(error) => Err(error),
+            None => Ok(decoded),
+        }
+    }
+
+    fn encode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        self.encode_reporting(input, |position, c| {
+            diagnostics.warn(Warning::SkippedToken { position, token: c.to_string() });
+        })
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        self.decode_reporting(input, |position, token| {
+            diagnostics.warn(Warning::SkippedToken { position, token: token.to_string() });
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_flag_positions() {
+        assert_eq!(FlagSemaphore::new().encode("AB"), "S-SW S-W");
+        assert_eq!(FlagSemaphore::new().decode("S-SW S-W"), "AB");
+        assert_eq!(FlagSemaphore::new().with_style(SemaphoreStyle::Arrows).encode("AB"), "↓↙ ↓←");
+    }
+}
@@ -0,0 +1,299 @@
+use crate::diagnostics::{Diagnostics, Warning};
+use crate::encoding::ascii::report_invalid_utf8;
+use crate::encoding::{Codec, CodecError};
+
+// Characters RFC 3986 never escapes.
+fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~')
+}
+
+fn hex_byte(digits: &[u8]) -> Option<u8> {
+    u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()
+}
+
+// Percent-encoding (RFC 3986): every byte of the UTF-8 text outside
+// A-Z, a-z, 0-9 and `-._~` becomes `%XX`. The form variant writes spaces
+// as `+` and reads `+` back as a space.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UrlEncoding {
+    form: bool,
+}
+
+impl UrlEncoding {
+    pub fn new() -> Self {
+        Self { form: false }
+    }
+
+    // `application/x-www-form-urlencoded`.
+    pub fn form() -> Self {
+        Self { form: true }
+    }
+
+    pub fn is_form(&self) -> bool {
+        self.form
+    }
+
+    // Stray `%`s that don't start a valid escape are kept as they are and
+    // passed to `on_error` with their position among the characters. The
+    // escapes may spell invalid UTF-8, so this stops at the bytes.
+    fn decode_reporting(&self, input: &str, mut on_error: impl FnMut(usize, &str)) -> Vec<u8> {
+        let bytes = input.as_bytes();
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'%' => match bytes.get(i + 1..i + 3).and_then(hex_byte) {
+                    Some(b) => {
+                        out.push(b);
+                        i += 3;
+                        continue;
+                    }
+                    None => {
+                        let end = (i + 3).min(bytes.len());
+                        on_error(input[..i].chars().count(), &String::from_utf8_lossy(&bytes[i..end]));
+                        out.push(b'%');
+                    }
+                },
+                b'+' if self.form => out.push(b' '),
+                b => out.push(b),
+            }
+            i += 1;
+        }
+
+        out
+    }
+}
+
+impl Codec for UrlEncoding {
+    fn encode(&self, input: &str) -> String {
+        input
+            .bytes()
+            .map(|b| match b {
+                b' ' if self.form => "+".to_string(),
+                b if is_unreserved(b) => (b as char).to_string(),
+                b => format!("%{:02X}", b),
+            })
+            .collect()
+    }
+
+    // Invalid UTF-8 becomes U+FFFD.
+    fn decode(&self, input: &str) -> String {
+        String::from_utf8_lossy(&self.decode_reporting(input, |_, _| {})).into_owned()
+    }
+
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        let mut error = None;
+        let decoded = self.decode_reporting(input, |position, token| {
+            error.get_or_insert(CodecError::InvalidToken { position, token: token.to_string() });
+        });
+        match error {
+            Some(error) => Err(error),
+            None => String::from_utf8(decoded)
+                .map_err(|_| CodecError::InvalidInput("decoded bytes are not valid UTF-8".to_string())),
+        }
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        let decoded = self.decode_reporting(input, |position, token| {
+            diagnostics.warn(Warning::SkippedToken { position, token: token.to_string() });
+        });
+        report_invalid_utf8(decoded, diagnostics)
+    }
+}
+
+// Named entities understood when decoding; the first five are the ones
+// encoding produces.
+const NAMED_ENTITIES: &[(&str, char)] = &[
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", '\u{a0}'),
+    ("copy", '©'),
+    ("reg", '®'),
+    ("trade", '™'),
+    ("deg", '°'),
+    ("hellip", '…'),
+    ("ndash", '–'),
+    ("mdash", '—'),
+    ("lsquo", '‘'),
+    ("rsquo", '’'),
+    ("ldquo", '“'),
+    ("rdquo", '”'),
+    ("laquo", '«'),
+    ("raquo", '»'),
+    ("middot", '·'),
+    ("times", '×'),
+    ("divide", '÷'),
+    ("euro", '€'),
+    ("pound", '£'),
+    ("sect", '§'),
+    ("para", '¶'),
+    ("agrave", 'à'),
+    ("aacute", 'á'),
+    ("acirc", 'â'),
+    ("auml", 'ä'),
+    ("ccedil", 'ç'),
+    ("egrave", 'è'),
+    ("eacute", 'é'),
+    ("ecirc", 'ê'),
+    ("euml", 'ë'),
+    ("iacute", 'í'),
+    ("icirc", 'î'),
+    ("iuml", 'ï'),
+    ("ntilde", 'ñ'),
+    ("oacute", 'ó'),
+    ("ocirc", 'ô'),
+    ("ouml", 'ö'),
+    ("uacute", 'ú'),
+    ("ugrave", 'ù'),
+    ("ucirc", 'û'),
+    ("uuml", 'ü'),
+    ("szlig", 'ß'),
+];
+
+// Longest entity name, `&hellip;` and friends; a `&` further than this
+// from the next `;` isn't an entity.
+const MAX_ENTITY_LEN: usize = 10;
+
+// HTML character references. Encoding escapes `& < > " '` by name and,
+// unless told otherwise, everything outside ASCII as `&#x..;`; the
+// numeric variant escapes every character, as obfuscated puzzle pages do.
+// Decoding understands the common named entities and both numeric forms.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HtmlEntities {
+    numeric: bool,
+}
+
+impl HtmlEntities {
+    pub fn new() -> Self {
+        Self { numeric: false }
+    }
+
+    // Every character as `&#NN;`.
+    pub fn numeric() -> Self {
+        Self { numeric: true }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        self.numeric
+    }
+
+    fn entity(name: &str) -> Option<char> {
+        if let Some(number) = name.strip_prefix('#') {
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            return char::from_u32(code);
+        }
+        NAMED_ENTITIES.iter().find(|(entity, _)| *entity == name).map(|(_, c)| *c)
+    }
+
+    // References that don't resolve are kept as they are and passed to
+    // `on_error` with their position among the characters.
+    fn decode_reporting(&self, input: &str, mut on_error: impl FnMut(usize, &str)) -> String {
+        let mut out = String::with_capacity(input.len());
+        let mut rest = input;
+        let mut position = 0;
+
+        while let Some(start) = rest.find('&') {
+            out.push_str(&rest[..start]);
+            position += rest[..start].chars().count();
+            rest = &rest[start..];
+
+            let end = rest[1..].find(';').map(|end| end + 1).filter(|end| *end <= MAX_ENTITY_LEN + 1);
+            let Some(end) = end else {
+                out.push('&');
+                rest = &rest[1..];
+                position += 1;
+                continue;
+            };
+
+            let reference = &rest[..=end];
+            match Self::entity(&reference[1..end]) {
+                Some(c) => out.push(c),
+                None => {
+                    on_error(position, reference);
+                    out.push_str(reference);
+                }
+            }
+            position += reference.chars().count();
+            rest = &rest[end + 1..];
+        }
+
+        out.push_str(rest);
+        out
+    }
+}
+
+impl Codec for HtmlEntities {
+    fn encode(&self, input: &str) -> String {
+        input
+            .chars()
+            .map(|c| match c {
+                _ if self.numeric => format!("&#{};", c as u32),
+                '&' | '<' | '>' | '"' | '\'' => {
+                    let (name, _) = NAMED_ENTITIES.iter().find(|(_, named)| *named == c).expect("escaped by name");
+                    format!("&{};", name)
+                }
+                c if !c.is_ascii() => format!("&#x{:x};", c as u32),
+                c => c.to_string(),
+            })
+            .collect()
+    }
+
+    fn decode(&self, input: &str) -> String {
+        self.decode_reporting(input, |_, _| {})
+    }
+
+    fn try_decode(&self, input: &str) -> Result<String, CodecError> {
+        let mut error = None;
+        let decoded = self.decode_reporting(input, |position, token| {
+            error.get_or_insert(CodecError::InvalidToken { position, token: token.to_string() });
+        });
+        match error {
+            Some(error) => Err(error),
+            None => Ok(decoded),
+        }
+    }
+
+    fn decode_with_diagnostics(&self, input: &str, diagnostics: &Diagnostics) -> String {
+        self.decode_reporting(input, |position, token| {
+            diagnostics.warn(Warning::SkippedToken { position, token: token.to_string() });
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_encodes_utf8() {
+        assert_eq!(UrlEncoding::new().encode("a b&c/é"), "a%20b%26c%2F%C3%A9");
+        assert_eq!(UrlEncoding::new().decode("a%20b%26c%2F%C3%A9"), "a b&c/é");
+        assert_eq!(UrlEncoding::form().encode("a b+c"), "a+b%2Bc");
+    }
+
+    #[test]
+    fn reports_escapes_that_are_not_utf8() {
+        let url = UrlEncoding::new();
+        assert_eq!(url.decode("a%FFb"), "a\u{FFFD}b");
+        assert!(matches!(url.try_decode("%FF"), Err(CodecError::InvalidInput(_))));
+        assert_eq!(url.try_decode("%C3%A9").unwrap(), "é");
+
+        let diagnostics = Diagnostics::new();
+        assert_eq!(url.decode_with_diagnostics("%C3", &diagnostics), "\u{FFFD}");
+        assert!(matches!(diagnostics.warnings()[..], [Warning::InvalidInput(_)]));
+    }
+
+    #[test]
+    fn escapes_html() {
+        assert_eq!(HtmlEntities::new().encode("<a & \"b\">"), "&lt;a &amp; &quot;b&quot;&gt;");
+        assert_eq!(HtmlEntities::numeric().encode("<é>"), "&#60;&#233;&#62;");
+        assert_eq!(HtmlEntities::new().decode("&lt;&eacute;&#62;"), "<é>");
+    }
+}
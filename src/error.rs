@@ -0,0 +1,63 @@
+use std::fmt;
+use std::io;
+
+use crate::json::JsonError;
+
+// Errors shared across the crate. Modules with richer failure modes keep
+// their own error types (`CodecError`, `MorseError`, ...).
+#[derive(Debug)]
+pub enum Error {
+    InvalidKey(String),
+    UnsupportedCharacter(char),
+    InvalidAlphabet(String),
+    AnalysisFailed(String),
+    // The operation doesn't apply to this cipher or input.
+    Unsupported(String),
+    // A pipeline recipe step that doesn't name a known codec or cipher.
+    InvalidRecipe(String),
+    // JSON that parses but doesn't have the shape of the value asked for.
+    InvalidJson(String),
+    Io(io::Error),
+    // Raised through the Python bindings, or by the Python process behind
+    // `PythonWordfreqScorer`.
+    Python(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidKey(message) => write!(f, "invalid key: {}", message),
+            Error::UnsupportedCharacter(c) => write!(f, "unsupported character {:?}", c),
+            Error::InvalidAlphabet(message) => write!(f, "invalid alphabet: {}", message),
+            Error::AnalysisFailed(message) => write!(f, "analysis failed: {}", message),
+            Error::Unsupported(message) => write!(f, "unsupported: {}", message),
+            Error::InvalidRecipe(message) => write!(f, "invalid recipe: {}", message),
+            Error::InvalidJson(message) => write!(f, "invalid JSON: {}", message),
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Python(message) => write!(f, "Python error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<JsonError> for Error {
+    fn from(e: JsonError) -> Self {
+        Error::InvalidJson(e.to_string())
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
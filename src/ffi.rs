@@ -0,0 +1,124 @@
+// C interface, for embedding the library in C/C++ programs. The matching
+// declarations are in include/cryptan.h.
+//
+// Every function takes NUL-terminated UTF-8 and returns a newly allocated
+// string the caller gives back with `cryptan_string_free`, or NULL on
+// failure, in which case `cryptan_last_error` says why.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+use crate::encoding::{codec_by_name, CODEC_NAMES};
+use crate::pipeline::Step;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_error(message: String) {
+    let message = CString::new(message).unwrap_or_else(|_| c"error message contained a NUL byte".into());
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+unsafe fn read_str<'a>(name: &str, s: *const c_char) -> Result<&'a str, String> {
+    if s.is_null() {
+        return Err(format!("`{}` is NULL", name));
+    }
+    CStr::from_ptr(s).to_str().map_err(|_| format!("`{}` is not valid UTF-8", name))
+}
+
+fn into_raw(result: Result<String, String>) -> *mut c_char {
+    let result = result.and_then(|text| CString::new(text).map_err(|_| "the result contains a NUL byte".to_string()));
+    match result {
+        Ok(text) => {
+            LAST_ERROR.with(|last| *last.borrow_mut() = None);
+            text.into_raw()
+        }
+        Err(message) => {
+            set_error(message);
+            ptr::null_mut()
+        }
+    }
+}
+
+// `cipher` and `key` are a recipe step split in two, so ("caesar", "3") or
+// ("vigenere", "LEMON"); `key` may be NULL for ciphers without one.
+unsafe fn crypt(cipher: *const c_char, key: *const c_char, text: *const c_char, encrypt: bool) -> *mut c_char {
+    let result = (|| {
+        let cipher = read_str("cipher", cipher)?;
+        let text = read_str("text", text)?;
+        let recipe = match key.is_null() {
+            true => cipher.to_string(),
+            false => format!("{}:{}", cipher, read_str("key", key)?),
+        };
+        let step = Step::from_recipe(&recipe).map_err(|e| e.to_string())?;
+        Ok(if encrypt { step.forward(text) } else { step.backward(text) })
+    })();
+    into_raw(result)
+}
+
+unsafe fn code(codec: *const c_char, text: *const c_char, encode: bool) -> *mut c_char {
+    let result = (|| {
+        let name = read_str("codec", codec)?;
+        let text = read_str("text", text)?;
+        let codec = codec_by_name(name)
+            .ok_or_else(|| format!("unknown codec `{}`, expected one of: {}", name, CODEC_NAMES.join(", ")))?;
+        match encode {
+            true => Ok(codec.encode(text)),
+            false => codec.try_decode(text).map_err(|e| e.to_string()),
+        }
+    })();
+    into_raw(result)
+}
+
+/// # Safety
+///
+/// `cipher` and `text` must be valid NUL-terminated strings; `key` must be
+/// one too, or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn cryptan_encrypt(cipher: *const c_char, key: *const c_char, text: *const c_char) -> *mut c_char {
+    crypt(cipher, key, text, true)
+}
+
+/// # Safety
+///
+/// As for `cryptan_encrypt`.
+#[no_mangle]
+pub unsafe extern "C" fn cryptan_decrypt(cipher: *const c_char, key: *const c_char, text: *const c_char) -> *mut c_char {
+    crypt(cipher, key, text, false)
+}
+
+/// # Safety
+///
+/// `codec` and `text` must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn cryptan_encode(codec: *const c_char, text: *const c_char) -> *mut c_char {
+    code(codec, text, true)
+}
+
+/// # Safety
+///
+/// As for `cryptan_encode`. Input the codec can't read is an error.
+#[no_mangle]
+pub unsafe extern "C" fn cryptan_decode(codec: *const c_char, text: *const c_char) -> *mut c_char {
+    code(codec, text, false)
+}
+
+/// # Safety
+///
+/// `s` must be NULL or a string returned by this library that hasn't been
+/// freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn cryptan_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+// Why the last call on this thread returned NULL, or NULL if it didn't.
+// Owned by the library and valid until the next call on this thread.
+#[no_mangle]
+pub extern "C" fn cryptan_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
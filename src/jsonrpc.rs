@@ -0,0 +1,115 @@
+use std::io::{self, BufRead, Write};
+
+use crate::api;
+use crate::json::Json;
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+
+// Reads one JSON-RPC 2.0 request, or batch of them, per line and writes
+// one response per line, until the input is exhausted. Blank lines and
+// notifications get no response.
+pub fn serve<R: BufRead, W: Write>(reader: R, mut writer: W) -> io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(response) = handle_line(&line) {
+            writeln!(writer, "{}", response)?;
+            writer.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+// The response to a request or a batch, or None when there is nothing to
+// send: a notification, or a batch of nothing else.
+pub fn handle_line(line: &str) -> Option<Json> {
+    match Json::parse(line) {
+        Err(e) => Some(error(Json::Null, PARSE_ERROR, &e.to_string())),
+        Ok(Json::Array(requests)) if requests.is_empty() => Some(error(Json::Null, INVALID_REQUEST, "empty batch")),
+        Ok(Json::Array(requests)) => {
+            let responses: Vec<Json> = requests.iter().filter_map(handle_request).collect();
+            (!responses.is_empty()).then_some(Json::Array(responses))
+        }
+        Ok(request) => handle_request(&request),
+    }
+}
+
+// A request without an `id` is a notification: it runs, but gets no
+// response, not even an error.
+fn handle_request(request: &Json) -> Option<Json> {
+    let id = request.get("id").cloned();
+    let method = match request.get("method").and_then(Json::as_str) {
+        Some(method) => method,
+        None => return Some(error(id.unwrap_or(Json::Null), INVALID_REQUEST, "missing string field `method`")),
+    };
+
+    let response = if api::METHODS.contains(&method) {
+        let params = request.get("params").cloned().unwrap_or(Json::Object(Vec::new()));
+        api::dispatch(method, &params)
+    } else {
+        Err(format!("unknown method `{}`", method))
+    };
+
+    let id = id?;
+    Some(match response {
+        Ok(result) => Json::object(vec![("jsonrpc", "2.0".into()), ("id", id), ("result", result)]),
+        Err(message) if api::METHODS.contains(&method) => error(id, INVALID_PARAMS, &message),
+        Err(message) => error(id, METHOD_NOT_FOUND, &message),
+    })
+}
+
+fn error(id: Json, code: i64, message: &str) -> Json {
+    Json::object(vec![
+        ("jsonrpc", "2.0".into()),
+        ("id", id),
+        ("error", Json::object(vec![("code", code.into()), ("message", message.into())])),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serve_lines(input: &str) -> String {
+        let mut output = Vec::new();
+        serve(input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn answers_requests_by_id() {
+        let request = r#"{"jsonrpc": "2.0", "id": 7, "method": "encrypt", "params": {"key": 1, "text": "a"}}"#;
+        assert_eq!(handle_line(request).unwrap().to_string(), r#"{"jsonrpc":"2.0","id":7,"result":{"result":"b"}}"#);
+        let response = handle_line(r#"{"jsonrpc": "2.0", "id": 8, "method": "nope"}"#).unwrap();
+        assert_eq!(response.get("error").and_then(|e| e.get("code")).and_then(Json::as_i64), Some(METHOD_NOT_FOUND));
+    }
+
+    #[test]
+    fn notifications_get_no_response() {
+        assert_eq!(handle_line(r#"{"jsonrpc": "2.0", "method": "encrypt", "params": {"key": 1, "text": "a"}}"#), None);
+        assert_eq!(handle_line(r#"{"jsonrpc": "2.0", "method": "nope"}"#), None);
+        assert_eq!(serve_lines("{\"jsonrpc\": \"2.0\", \"method\": \"analyze\", \"params\": {\"text\": \"a\"}}\n"), "");
+    }
+
+    #[test]
+    fn batches_leave_out_notifications() {
+        let batch = r#"[
+            {"jsonrpc": "2.0", "id": 1, "method": "encode", "params": {"codec": "hex", "text": "hi"}},
+            {"jsonrpc": "2.0", "method": "encode", "params": {"codec": "hex", "text": "hi"}},
+            {"jsonrpc": "2.0", "id": 2, "method": "decode", "params": {"codec": "hex", "text": "6869"}}
+        ]"#;
+        let response = handle_line(&batch.replace('\n', "")).unwrap();
+        let ids: Vec<i64> = response.as_array().unwrap().iter().filter_map(|r| r.get("id")?.as_i64()).collect();
+        assert_eq!(ids, [1, 2]);
+
+        assert_eq!(handle_line(r#"[{"jsonrpc": "2.0", "method": "encode", "params": {}}]"#), None);
+        assert!(handle_line("[]").unwrap().get("error").is_some());
+    }
+}
@@ -0,0 +1,81 @@
+use crate::classical::affine::VALID_A;
+use crate::classical::alphabet::LATIN;
+use crate::classical::TurningGrille;
+use crate::error::{Error, Result};
+use crate::random::Rng;
+
+pub const KEYGEN_CIPHERS: &[&str] =
+    &["caesar", "affine", "substitution", "vigenere", "columnar", "playfair", "grille"];
+
+// Random keys for the classical ciphers. Give a seed to get the same keys
+// every time, e.g. so a whole class works on the same exercise.
+pub struct KeyGenerator {
+    rng: Rng,
+}
+
+impl KeyGenerator {
+    pub fn new() -> Self {
+        Self { rng: Rng::from_os() }
+    }
+
+    pub fn seeded(seed: u64) -> Self {
+        Self { rng: Rng::seeded(seed) }
+    }
+
+    // 1 to 25; a shift of 0 wouldn't change anything.
+    pub fn caesar_shift(&mut self) -> i64 {
+        1 + self.rng.below(25) as i64
+    }
+
+    // `a` coprime with 26, and not the identity key (1, 0).
+    pub fn affine_key(&mut self) -> (i32, i32) {
+        loop {
+            let a = VALID_A[self.rng.below(VALID_A.len() as u64) as usize];
+            let b = self.rng.below(26) as i32;
+            if (a, b) != (1, 0) {
+                return (a, b);
+            }
+        }
+    }
+
+    // A shuffled A-Z, the cipher alphabet of `SubstitutionCipher`.
+    pub fn substitution_alphabet(&mut self) -> String {
+        let mut letters: Vec<char> = LATIN.chars().collect();
+        self.rng.shuffle(&mut letters);
+        letters.into_iter().collect()
+    }
+
+    // Random letters; a random keyword is stronger than a dictionary word.
+    pub fn vigenere_keyword(&mut self, length: usize) -> Result<String> {
+        if length == 0 {
+            return Err(Error::InvalidKey("the keyword length must be at least 1".to_string()));
+        }
+        Ok((0..length).map(|_| (b'A' + self.rng.below(26) as u8) as char).collect())
+    }
+
+    // A column order for `ColumnarTransposition::try_from_order`.
+    pub fn transposition_order(&mut self, columns: usize) -> Result<Vec<usize>> {
+        if columns < 2 {
+            return Err(Error::InvalidKey("a transposition needs at least 2 columns".to_string()));
+        }
+        let mut order: Vec<usize> = (0..columns).collect();
+        self.rng.shuffle(&mut order);
+        Ok(order)
+    }
+
+    // 25 letters without J, read row by row into the key square.
+    pub fn playfair_keyword(&mut self) -> String {
+        self.substitution_alphabet().chars().filter(|c| *c != 'J').collect()
+    }
+
+    // A `size` by `size` turning grille; `size` must be even.
+    pub fn turning_grille(&mut self, size: usize) -> Result<TurningGrille> {
+        TurningGrille::random(size, &mut self.rng)
+    }
+}
+
+impl Default for KeyGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
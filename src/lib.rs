@@ -0,0 +1,27 @@
+pub mod analysis;
+pub mod api;
+pub mod budget;
+pub mod ceasar_cipher;
+pub mod classical;
+pub mod diagnostics;
+pub mod encoding;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod json;
+pub mod jsonrpc;
+pub mod keygen;
+pub mod magic;
+pub mod modern_toy;
+pub mod pipeline;
+#[cfg(feature = "python-ext")]
+mod python;
+pub mod random;
+pub mod registry;
+pub mod result;
+#[cfg(feature = "serve")]
+pub mod server;
+pub mod testing;
+
+pub use error::{Error, Result};
+pub use result::DecodedResult;
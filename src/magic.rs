@@ -0,0 +1,367 @@
+use std::collections::HashSet;
+
+use crate::analysis::meaningful_ratio;
+use crate::budget::Budget;
+use crate::diagnostics::{Diagnostics, Warning};
+use crate::encoding::base_n::{
+    ASCII85_ALPHABET, BASE32_ALPHABET, BASE58_ALPHABET, BASE64_ALPHABET, BASE64_URL_ALPHABET, Z85_ALPHABET,
+};
+use crate::encoding::braille;
+use crate::encoding::{
+    Ascii85, Base32, Base58, Base64, BinaryAscii, Braille, CodePage, Codec, DecimalAscii, Ebcdic, FlagSemaphore, Hex,
+    HtmlEntities, LeetSpeak, Morse, Ogham, Pigpen, PigpenSymbol, Rot13, Rot47, UrlEncoding, Z85,
+};
+
+pub const DEFAULT_MAX_DEPTH: usize = 5;
+
+// Layers that undo themselves: decoding with one twice in a row gets back
+// where it started, so the second step is never tried.
+const SELF_INVERSE: &[&str] = &["rot13", "rot47"];
+
+pub struct Layer {
+    pub name: &'static str,
+    pub codec: Box<dyn Codec>,
+    pub detect: fn(&str) -> bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct DecodeNode {
+    pub layer: String,
+    pub text: String,
+    pub score: f64,
+    // What the layer's codec skipped or patched up to get `text`.
+    pub warnings: Vec<Warning>,
+    pub children: Vec<DecodeNode>,
+}
+
+pub struct Magic {
+    layers: Vec<Layer>,
+    max_depth: usize,
+}
+
+impl Magic {
+    pub fn new() -> Self {
+        let mut magic = Self { layers: Vec::new(), max_depth: DEFAULT_MAX_DEPTH };
+
+        magic.add_layer("base64", Box::new(Base64::new()), looks_like_base64);
+        magic.add_layer("base64url", Box::new(Base64::url_safe()), looks_like_base64url);
+        magic.add_layer("base32", Box::new(Base32::new()), looks_like_base32);
+        magic.add_layer("base58", Box::new(Base58), looks_like_base58);
+        magic.add_layer("ascii85", Box::new(Ascii85::new()), looks_like_ascii85);
+        magic.add_layer("z85", Box::new(Z85), looks_like_z85);
+        magic.add_layer("hex", Box::new(Hex::new()), looks_like_hex);
+        magic.add_layer("binary", Box::new(BinaryAscii), looks_like_binary);
+        magic.add_layer("decimal", Box::new(DecimalAscii), looks_like_decimal);
+        magic.add_layer("rot13", Box::new(Rot13), has_letters);
+        magic.add_layer("rot47", Box::new(Rot47), looks_like_rot47);
+        magic.add_layer("morse", Box::new(Morse::new()), looks_like_morse);
+        magic.add_layer("ebcdic", Box::new(Ebcdic::new(CodePage::Cp037)), looks_like_hex);
+        magic.add_layer("ogham", Box::new(Ogham::new()), looks_like_ogham);
+        magic.add_layer("url", Box::new(UrlEncoding::new()), looks_like_url_encoded);
+        magic.add_layer("html", Box::new(HtmlEntities::new()), looks_like_html_entities);
+        magic.add_layer("braille", Box::new(Braille), looks_like_braille);
+        magic.add_layer("semaphore", Box::new(FlagSemaphore::new()), looks_like_semaphore);
+        magic.add_layer("pigpen", Box::new(Pigpen), looks_like_pigpen);
+        magic.add_layer("leet", Box::new(LeetSpeak::new()), looks_like_leet);
+
+        magic
+    }
+
+    pub fn add_layer(&mut self, name: &'static str, codec: Box<dyn Codec>, detect: fn(&str) -> bool) {
+        self.layers.push(Layer { name, codec, detect });
+    }
+
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    pub fn decode(&self, input: &str) -> DecodeNode {
+        self.decode_with_budget(input, &Budget::unlimited())
+    }
+
+    // Each decoded layer costs one unit of the budget; once it runs out the
+    // tree explored so far is returned. The search goes breadth first, so
+    // each text is reached in the fewest layers first; reached again, it
+    // is left out.
+    pub fn decode_with_budget(&self, input: &str, budget: &Budget) -> DecodeNode {
+        let mut seen = HashSet::from([input.to_string()]);
+        // Every node found, with its parent's index.
+        let mut found = vec![(0, DecodeNode::leaf("input", input.to_string(), score(input), Vec::new()))];
+        let mut frontier = vec![0];
+
+        'search: for _ in 0..self.max_depth {
+            let mut next = Vec::new();
+            for parent in frontier {
+                let text = found[parent].1.text.clone();
+                for layer in &self.layers {
+                    if found[parent].1.layer == layer.name && SELF_INVERSE.contains(&layer.name) {
+                        continue;
+                    }
+                    if !(layer.detect)(&text) {
+                        continue;
+                    }
+                    if !budget.spend() {
+                        break 'search;
+                    }
+
+                    let diagnostics = Diagnostics::new();
+                    let decoded = layer.codec.decode_with_diagnostics(&text, &diagnostics);
+                    if decoded.trim().is_empty() || !seen.insert(decoded.clone()) {
+                        continue;
+                    }
+
+                    let score = score(&decoded);
+                    budget.record_score(score);
+                    next.push(found.len());
+                    found.push((parent, DecodeNode::leaf(layer.name, decoded, score, diagnostics.take())));
+                }
+            }
+            frontier = next;
+        }
+        budget.report();
+
+        // Children come after their parents, so from the back each node is
+        // complete by the time it joins its parent.
+        while found.len() > 1 {
+            let (parent, mut node) = found.pop().expect("more than one left");
+            node.children.reverse();
+            node.children.sort_by(|a, b| b.best_score().total_cmp(&a.best_score()));
+            found[parent].1.children.push(node);
+        }
+        let (_, mut root) = found.pop().expect("the input is always there");
+        root.children.reverse();
+        root.children.sort_by(|a, b| b.best_score().total_cmp(&a.best_score()));
+        root
+    }
+}
+
+impl Default for Magic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DecodeNode {
+    fn leaf(layer: &str, text: String, score: f64, warnings: Vec<Warning>) -> Self {
+        Self { layer: layer.to_string(), text, score, warnings, children: Vec::new() }
+    }
+
+    pub fn best_score(&self) -> f64 {
+        self.children
+            .iter()
+            .map(|child| child.best_score())
+            .fold(self.score, f64::max)
+    }
+
+    // Layer names from the root to the highest scoring node, and that node's text.
+    pub fn best_path(&self) -> (Vec<String>, String) {
+        let best_child = self
+            .children
+            .iter()
+            .filter(|child| child.best_score() > self.score)
+            .max_by(|a, b| a.best_score().total_cmp(&b.best_score()));
+
+        match best_child {
+            Some(child) => {
+                let (mut path, text) = child.best_path();
+                path.insert(0, child.layer.clone());
+                (path, text)
+            }
+            None => (Vec::new(), self.text.clone()),
+        }
+    }
+}
+
+// Half for being made of letters and spaces (control characters count
+// against it), half for how many of the words are real English words, so
+// letter soup like Base64 doesn't pass for plaintext.
+pub fn score(text: &str) -> f64 {
+    let total = text.chars().count();
+    if total == 0 {
+        return 0.0;
+    }
+
+    let readable = text.chars().filter(|c| c.is_alphabetic() || *c == ' ').count();
+    let control = text.chars().filter(|c| c.is_control() && !c.is_whitespace()).count();
+    let readable = (readable as f64 - 2.0 * control as f64).max(0.0) / total as f64;
+
+    (readable + meaningful_ratio(text)) / 2.0
+}
+
+pub fn looks_like_morse(text: &str) -> bool {
+    let morse = Morse::new();
+    let normalized = morse.normalize(text);
+
+    normalized.contains(['.', '-'])
+        && normalized.chars().all(|c| c == '.' || c == '-' || c == '/' || c.is_whitespace())
+}
+
+pub fn looks_like_hex(text: &str) -> bool {
+    let digits = text.chars().filter(|c| !c.is_whitespace()).count();
+
+    digits > 0 && digits % 2 == 0 && text.chars().all(|c| c.is_ascii_hexdigit() || c.is_whitespace())
+}
+
+pub fn looks_like_ogham(text: &str) -> bool {
+    text.chars().any(|c| ('\u{1680}'..='\u{169F}').contains(&c))
+}
+
+pub fn looks_like_braille(text: &str) -> bool {
+    text.chars().any(|c| c != '\u{2800}' && braille::is_braille(c))
+}
+
+// Every token a pair of arm positions, by name or by arrow.
+pub fn looks_like_semaphore(text: &str) -> bool {
+    let mut tokens = text.split_whitespace().filter(|t| *t != "/").peekable();
+    tokens.peek().is_some() && tokens.all(|token| FlagSemaphore::new().try_decode(token).is_ok())
+}
+
+pub fn looks_like_pigpen(text: &str) -> bool {
+    let mut tokens = text.split_whitespace().filter(|t| *t != "/").peekable();
+    tokens.peek().is_some() && tokens.all(|token| PigpenSymbol::parse(token).is_some())
+}
+
+// A word with letters and digits both, like `h3ll0`.
+pub fn looks_like_leet(text: &str) -> bool {
+    text.split_whitespace().any(|word| {
+        word.chars().any(|c| c.is_ascii_alphabetic()) && word.chars().any(|c| matches!(c, '0'..='9' | '@' | '$'))
+    })
+}
+
+// Symbols from the alphabet, then optional `=` padding, and no spaces
+// inside (line breaks are fine).
+fn looks_like_base(text: &str, alphabet: &str, min_len: usize) -> bool {
+    let symbols: String = text.chars().filter(|c| *c != '\n' && *c != '\r').collect();
+    let body = symbols.trim().trim_end_matches('=');
+
+    body.len() >= min_len && body.chars().all(|c| alphabet.contains(c))
+}
+
+pub fn looks_like_base64(text: &str) -> bool {
+    looks_like_base(text, BASE64_ALPHABET, 4)
+}
+
+pub fn looks_like_base64url(text: &str) -> bool {
+    looks_like_base(text, BASE64_URL_ALPHABET, 4) && text.contains(['-', '_'])
+}
+
+pub fn looks_like_base32(text: &str) -> bool {
+    looks_like_base(text, BASE32_ALPHABET, 8)
+}
+
+// At least one `%XX` escape.
+pub fn looks_like_url_encoded(text: &str) -> bool {
+    text.as_bytes().windows(3).any(|w| w[0] == b'%' && w[1].is_ascii_hexdigit() && w[2].is_ascii_hexdigit())
+}
+
+// At least one `&name;` or `&#NN;` reference.
+pub fn looks_like_html_entities(text: &str) -> bool {
+    text.split('&').skip(1).any(|rest| {
+        rest.split_once(';').is_some_and(|(name, _)| {
+            let number = name.strip_prefix('#').map(|n| n.strip_prefix(['x', 'X']).unwrap_or(n));
+            match number {
+                Some(digits) => !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit()),
+                None => !name.is_empty() && name.len() <= 8 && name.chars().all(|c| c.is_ascii_alphabetic()),
+            }
+        })
+    })
+}
+
+// Base58 has no padding and takes in most words, so only long unbroken
+// runs count.
+pub fn looks_like_base58(text: &str) -> bool {
+    let text = text.trim();
+    text.len() >= 8 && text.chars().all(|c| BASE58_ALPHABET.contains(c))
+}
+
+// Inside `<~ ~>`, or a long run of its symbols with some punctuation
+// among them.
+pub fn looks_like_ascii85(text: &str) -> bool {
+    let text = text.trim();
+    if text.starts_with("<~") && text.ends_with("~>") {
+        return true;
+    }
+    // `z` abbreviates a group of zero bytes.
+    looks_like_base85(text, &format!("{}z", ASCII85_ALPHABET))
+}
+
+pub fn looks_like_z85(text: &str) -> bool {
+    looks_like_base85(text.trim(), Z85_ALPHABET)
+}
+
+fn looks_like_base85(text: &str, alphabet: &str) -> bool {
+    text.len() >= 10
+        && text.chars().all(|c| alphabet.contains(c))
+        && text.chars().any(|c| c.is_ascii_punctuation())
+        && text.chars().any(|c| c.is_ascii_alphanumeric())
+}
+
+pub fn has_letters(text: &str) -> bool {
+    text.chars().any(|c| c.is_ascii_alphabetic())
+}
+
+// ROT47 output is plain ASCII, usually with punctuation where the spaces
+// between words didn't move.
+pub fn looks_like_rot47(text: &str) -> bool {
+    text.is_ascii() && text.chars().any(|c| c.is_ascii_punctuation())
+}
+
+pub fn looks_like_binary(text: &str) -> bool {
+    let bits = text.chars().filter(|c| *c == '0' || *c == '1').count();
+
+    bits >= 8 && text.chars().all(|c| c == '0' || c == '1' || c.is_whitespace())
+}
+
+pub fn looks_like_decimal(text: &str) -> bool {
+    let mut values = text.split(|c: char| c.is_whitespace() || c == ',').filter(|v| !v.is_empty()).peekable();
+
+    values.peek().is_some() && values.all(|v| v.parse::<u8>().is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(node: &DecodeNode, path: &mut Vec<String>, all: &mut Vec<Vec<String>>) {
+        for child in &node.children {
+            path.push(child.layer.clone());
+            all.push(path.clone());
+            paths(child, path, all);
+            path.pop();
+        }
+    }
+
+    #[test]
+    fn finds_layered_encodings() {
+        let encoded = Base64::new().encode(&Hex::new().encode("attack at dawn"));
+        let (path, text) = Magic::new().decode(&encoded).best_path();
+        assert_eq!((path, text.as_str()), (vec!["base64".to_string(), "hex".to_string()], "attack at dawn"));
+    }
+
+    #[test]
+    fn never_undoes_a_step_or_repeats_a_text() {
+        let tree = Magic::new().decode("72 101 108 108 111 32 119 111 114 108 100");
+        let mut all = Vec::new();
+        paths(&tree, &mut Vec::new(), &mut all);
+        assert!(!all.is_empty());
+        for path in &all {
+            let undone = path.windows(2).any(|pair| pair[0] == pair[1] && SELF_INVERSE.contains(&pair[0].as_str()));
+            assert!(!undone, "{}", path.join(" -> "));
+        }
+
+        let mut texts = Vec::new();
+        let mut stack = vec![&tree];
+        while let Some(node) = stack.pop() {
+            texts.push(node.text.as_str());
+            stack.extend(&node.children);
+        }
+        let count = texts.len();
+        texts.sort();
+        texts.dedup();
+        assert_eq!(texts.len(), count);
+    }
+}
@@ -1,23 +1,1731 @@
-pub mod ceasar_cipher;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, IsTerminal, Read, Write};
+use std::path::Path;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::Duration;
 
-use crate::ceasar_cipher::{CeasarCipher, Decode, Encode, BruteForce};
+use cryptan::analysis::xor::{DEFAULT_KEY_SIZE_CANDIDATES, DEFAULT_MAX_KEY_SIZE};
+use cryptan::analysis::{
+    break_repeating_xor, crib_attack, hex_dump, meaningful_ratio, scorer_by_name, AnalysisReport, CipherIdentifier,
+    Inspection, Language, MultiLanguageScorer, NgramScorer, Scorer, UserWords, Wordlist, WordlistScorer,
+};
+use cryptan::budget::{Budget, Progress, ProgressSink};
+use cryptan::classical::alphabet::ALPHABET_NAMES;
+use cryptan::classical::{
+    byte_shift, caesar, columnar, route, Alphabet, AtbashCipher, AutokeyCipher, BeaufortCipher, BifidCipher, BookCipher,
+    BookStyle, BruteForceOptions, ByteShiftCipher, CaesarCipher, CharPolicy, ClassicalCipher, ColumnarTransposition,
+    GronsfeldCipher, PlayfairCipher, PortaCipher, Route, RouteCipher, TrifidCipher, TurningGrille, VigenereCipher,
+    VigenereSolver,
+};
+use cryptan::diagnostics::Diagnostics;
+use cryptan::encoding::base_n::{hex_decode, hex_encode};
+use cryptan::encoding::{LeetSpeak, Morse};
+use cryptan::json::Json;
+use cryptan::keygen::{KeyGenerator, KEYGEN_CIPHERS};
+use cryptan::modern_toy::{xor, OutputEncoding, XorCipher};
+use cryptan::pipeline::{self, Pipeline};
+use cryptan::registry::{Registry, Transform};
+use cryptan::DecodedResult;
+
+const USAGE: &str = "usage: cryptan <command> [options]
+
+commands:
+    caesar encrypt [--unicode | --alphabet <set>] [--rot5] [--policy preserve|strip|error] <key> <text>
+    caesar decrypt [--unicode | --alphabet <set>] [--rot5] [--policy preserve|strip|error] <key> <text>
+    caesar rot18 <text>             ROT13 for letters and ROT5 for digits; `--rot5` adds the latter to any key
+    caesar brute [--top <n>] [--best] [--min-length <n>] [--scorer <name>] [--alphabet <set>]
+                 [--wordlist <file> | --language <codes>] [--threshold <score>] [--stop-above <score>]
+                 <text>
+                                    (`--best` prints only the best plaintext; scorers are
+                                    english, wordlist, ngram, multilingual and python (word
+                                    frequencies from the `wordfreq` package, run with python3);
+                                    languages are en, es, fr, de, vi, several separated by commas
+                                    or `auto` for all of them, and each result names the one it
+                                    reads best in;
+                                    `--threshold` hides lower scores; `--stop-above` stops trying
+                                    keys once one scores above it;
+                                    `--alphabet` is latin, digits, hex, alphanumeric, cyrillic,
+                                    greek, vietnamese or the symbols themselves, e.g. `01234567`)
+    caesar vigenere encrypt <keyword> <text>
+    caesar vigenere decrypt <keyword> <text>
+    caesar vigenere crack [--max-length <n>] [--top <n>] <text>
+    beaufort encrypt <keyword> <text>
+    beaufort decrypt <keyword> <text>
+    autokey encrypt <keyword> <text>
+    autokey decrypt <keyword> <text>
+    porta encrypt|decrypt <keyword> <text>
+    gronsfeld encrypt|decrypt <digits> <text>
+    atbash encode <text>
+    atbash decode <text>
+    columnar encrypt --keyword <keyword> [--pad-char <c>] <text>
+    columnar decrypt --keyword <keyword> [--pad-char <c>] <text>
+    columnar brute [--min <columns>] [--max <columns>] [--top <n>] <text>
+    route encrypt|decrypt --columns <n> [--rows <n>] [--route <route>] <text>
+                                    write the text into rows, read it along the route: columns (the
+                                    Caesar box), boustrophedon, column-boustrophedon, spiral,
+                                    counter-spiral or diagonal
+    route brute [--top <n>] <text>  try every grid shape and route
+    grille encrypt|decrypt --grille <mask> <text>
+                                    turning grille; the mask is rows of X (hole) and . (card)
+                                    separated by /, e.g. X.../X..X/..X./.... (see `keygen grille`)
+    playfair encrypt --keyword <keyword> <text>
+    playfair decrypt --keyword <keyword> <text>
+    bifid encrypt|decrypt --keyword <keyword> [--period <n>] <text>
+    trifid encrypt|decrypt --keyword <keyword> [--period <n>] <text>
+    book encode|decode --key-file <file> [--style index|page] <text>
+                                    (references are word numbers, or page.line.word with `page`)
+    xor encrypt --key <key> [--encoding hex|base64] <text>
+    xor decrypt --key <key> [--encoding hex|base64] <text>
+    xor brute [--top <n>] <hex>
+    xor crack [--max-key-size <n>] [--key-sizes <n>] [--top <n>] <hex>
+                                    break a repeating key by Hamming distance and single-byte XOR per column
+    byteshift encrypt|decrypt [--hex] <key> <text>
+    byteshift brute [--top <n>] [--hex] <text>
+                                    add the key (0-255) to every byte, mod 256; `--in` files are read
+                                    byte for byte, UTF-8 or not, and `--hex` takes and prints hex
+    morse export --out <file.wav> [--wpm <n>] [--farnsworth <n>] [--tone <hz>] <text>
+    morse play [--wpm <n>] [--farnsworth <n>] [--tone <hz>] <text>
+                                    (requires the `audio` feature; `play` writes WAV to stdout)
+    pigpen svg [--answers] [--columns <n>] [--cell-size <px>] <text>
+                                    printable pigpen worksheet as SVG, with blank answer lines or
+                                    the letters under each symbol (requires the `svg` feature)
+    encode --codec <name> [--lang <language>] <text>
+                                    (`--codec` also takes a cipher and its key, e.g. caesar:3)
+    decode --codec <name> [--lang <language>] [--lenient] [--strict] <text>
+                                    (`--lang` picks the Morse alphabet, e.g. russian, greek, japanese;
+                                    `--lenient` also reads `*` as a dot and `|` or long gaps as word
+                                    breaks, and reports each symbol it read differently)
+    encode --recipe <recipe|file> <text>
+    decode --recipe <recipe|file> <text>
+                                    (a recipe chains steps, e.g. \"caesar:3 | base64\")
+    watch --recipe <recipe|file> [--decode] [--interval <ms>] <file>
+                                    run a recipe on a file, and again each time the file or the
+                                    recipe file changes, until interrupted
+    recipe run [--decode] <file> <text>
+                                    run a recipe file (.json, .yaml or plain) forwards, or undo it
+    solve [--depth <n>] [--time-limit <ms>] [--save <file>] <text>
+                                    undo codecs and ciphers until the text reads as English
+    tui <text>                      work out a substitution cipher by hand: letter frequencies,
+                                    your guesses and the plaintext they give, redrawn after each
+                                    command read from stdin (requires the `tui` feature)
+    identify <text>                 guess which cipher or encoding produced the text
+    analyze [--leet] <text>         letter frequencies, IC, entropy, character set and guesses
+                                    (`--leet` reads leet speak back to letters first)
+    inspect <text>                  hex dump, likely encoding and byte statistics (use `--in`
+                                    for binary files)
+    keygen <cipher> [--seed <n>] [--length <n>]
+                                    random key for caesar, affine, substitution, vigenere,
+                                    columnar, playfair or grille (`--length` is its size), in the
+                                    form recipes take
+    wordlist add|remove [--language <code>] <word>...
+    wordlist import [--language <code>] <file>
+    wordlist export [--language <code>]
+    wordlist stats [--language <code>]
+                                    your own words for a language's dictionary, so names and jargon
+                                    score as words; kept in $XDG_DATA_HOME/cryptan/wordlists (or
+                                    $CRYPTAN_DATA_DIR/wordlists); `import` takes the first word of
+                                    each line of a file
+    crib [--top <n>] <crib> <text>  find Caesar and Vigenère keys that put a known word in the text
+    serve [--addr <host:port>]      (requires the `serve` feature)
+    --jsonrpc                       read JSON-RPC requests from stdin, one per line
+    completions bash|zsh|fish       print a shell completion script, e.g. for
+                                    ~/.local/share/bash-completion/completions/cryptan
+    --generate-man                  print a man page (roff), e.g. for `man -l -`
+
+options for every command:
+    --in <file>                     read the text from a file
+    --out <file>                    write the result to a file
+    --json                          print results as JSON, and errors to stderr as
+                                    {\"error\": <kind>, \"message\": ..., \"exit_code\": <n>}
+    --no-progress                   no progress bar for slow searches (shown only on a terminal)
+    --no-color                      never colour the output; also when NO_COLOR is set, and
+                                    whenever it isn't going straight to a terminal
+    --verbose                       with ranked results, the scorer, a 0-100 confidence and the
+                                    words that weren't recognized
+    --stats                         after a search, print keys tried, texts scored and keys/s
+    --define <name>=<recipe>        name a recipe, e.g. \"class=caesar:3 | atbash\", to use as one
+                                    step or with `--codec`; may be given more than once
+    -                               in place of the text, read it from stdin
+
+exit status:
+    0                               success, also when the output is closed early, e.g. by `head`
+    1                               the output couldn't be written, or a streamed `--in` file
+                                    couldn't be read (kind `output`)
+    2                               usage error: unknown command, bad option or key, unreadable
+                                    file (kind `usage`)
+    3                               a search found no candidate above its threshold
+                                    (kind `no_candidate`)
+    4                               the input couldn't be decoded, e.g. `decode --strict` or
+                                    invalid hex (kind `decode`)";
+
+type Sink = Mutex<Box<dyn Write + Send>>;
+
+// Where results go: stdout, or the file named by `--out`.
+static OUTPUT: OnceLock<Sink> = OnceLock::new();
+
+// Set by `--out`. `OUTPUT` alone can't tell, as writing to stdout fills it
+// in too.
+static OUT_FILE: AtomicBool = AtomicBool::new(false);
+
+fn output_is_terminal() -> bool {
+    !OUT_FILE.load(Ordering::Relaxed) && io::stdout().is_terminal()
+}
+
+fn output() -> MutexGuard<'static, Box<dyn Write + Send>> {
+    OUTPUT.get_or_init(|| Mutex::new(Box::new(io::stdout()))).lock().unwrap()
+}
+
+// A failed read or write while producing output. A closed pipe, as in
+// `cryptan … | head`, is no error: `main` stops quietly.
+fn output_failure(e: io::Error) -> Failure {
+    if e.kind() == io::ErrorKind::BrokenPipe {
+        Failure::Closed
+    } else {
+        Failure::Output(format!("I/O error: {}", e))
+    }
+}
+
+macro_rules! out {
+    ($($arg:tt)*) => {
+        writeln!(output(), $($arg)*).map_err(output_failure)?
+    };
+}
+
+// The file named by `--in`; read when the text is needed, or streamed.
+static INPUT: OnceLock<String> = OnceLock::new();
+
+// Set by `--json`: results come out as one JSON value each.
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+fn json_output() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+// Set by `--no-progress`.
+static NO_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+// Set by `--no-color`.
+static NO_COLOR: AtomicBool = AtomicBool::new(false);
+
+// Colours only when writing straight to a terminal, and not when asked
+// not to by `--no-color` or a non-empty `NO_COLOR` (https://no-color.org).
+fn colour() -> bool {
+    !NO_COLOR.load(Ordering::Relaxed)
+        && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+        && output_is_terminal()
+}
+
+// Set by `--verbose`: ranked results come with their `Explanation`.
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+// Set by `--stats`: searches print their `Metrics` to stderr.
+static STATS: AtomicBool = AtomicBool::new(false);
+
+// Set once the progress bar has drawn something that needs clearing.
+static PROGRESS_DRAWN: AtomicBool = AtomicBool::new(false);
+
+const PROGRESS_WIDTH: usize = 30;
+
+// Candidates between redraws.
+const PROGRESS_EVERY: u64 = 10;
+
+// A one-line progress bar on stderr for the slow searches.
+struct ProgressBar;
+
+impl ProgressSink for ProgressBar {
+    fn report(&self, progress: &Progress) {
+        let best = progress.best_score.map_or("-".to_string(), |score| format!("{:.2}", score));
+        let line = match progress.total {
+            Some(total) if total > 0 => {
+                let done = (progress.tried.min(total) as usize * PROGRESS_WIDTH) / total as usize;
+                let eta = progress.eta().map_or("-".to_string(), |eta| format!("{:.0}s", eta.as_secs_f64()));
+                let bar = format!("{}{}", "#".repeat(done), "-".repeat(PROGRESS_WIDTH - done));
+                format!("[{}] {}/{}  best {}  eta {}", bar, progress.tried, total, best, eta)
+            }
+            _ => format!("{} tried  best {}  {:.1}s", progress.tried, best, progress.elapsed.as_secs_f64()),
+        };
+        eprint!("\r{}\x1b[K", line);
+        PROGRESS_DRAWN.store(true, Ordering::Relaxed);
+    }
+}
+
+// A budget that draws the progress bar, unless stderr isn't a terminal or
+// `--no-progress` was given.
+fn progress_budget() -> Budget {
+    if NO_PROGRESS.load(Ordering::Relaxed) || !io::stderr().is_terminal() {
+        return Budget::unlimited();
+    }
+    Budget::unlimited().with_progress(PROGRESS_EVERY, ProgressBar)
+}
+
+// Clears the progress bar's line once the search is done, and prints what
+// the search did if `--stats` asked for it.
+fn finish_progress(budget: &Budget) {
+    if PROGRESS_DRAWN.swap(false, Ordering::Relaxed) {
+        eprint!("\r\x1b[K");
+    }
+    if STATS.load(Ordering::Relaxed) {
+        eprintln!("stats: {}", budget.metrics());
+    }
+}
+
+// The text a cipher or codec produced, bare or as JSON naming the cipher.
+fn emit_text(cipher: &str, text: &str) -> Result<(), Failure> {
+    if json_output() {
+        out!("{}", Json::object(vec![("cipher", cipher.into()), ("output", text.into())]));
+    } else {
+        out!("{}", text);
+    }
+    Ok(())
+}
+
+// Ranked candidates from a brute force or solver. None at all is a
+// `Failure::NoCandidate`, after printing the empty list for `--json`.
+fn emit_results(cipher: &str, results: &[DecodedResult]) -> Result<(), Failure> {
+    if results.is_empty() {
+        if json_output() {
+            out!("[]");
+        }
+        return Err(Failure::NoCandidate(format!("no {} key gave a candidate above the threshold", cipher)));
+    }
+    if !json_output() {
+        let colour = colour();
+        let verbose = VERBOSE.load(Ordering::Relaxed);
+        for result in results {
+            if colour {
+                out!("{:#}", result);
+            } else {
+                out!("{}", result.plain_string());
+            }
+            if verbose {
+                out!("{}", result.explain());
+            }
+            out!();
+        }
+        return Ok(());
+    }
+
+    let results = results
+        .iter()
+        .map(|result| {
+            let mut fields = vec![
+                ("cipher", cipher.into()),
+                ("key", result.key.as_str().into()),
+                ("plaintext", result.text.as_str().into()),
+                ("score", result.score.into()),
+                ("meaningful_ratio", meaningful_ratio(&result.text).into()),
+            ];
+            if let Some(language) = result.language {
+                fields.push(("language", language.code().into()));
+            }
+            if VERBOSE.load(Ordering::Relaxed) {
+                fields.push(("explanation", Json::from(&result.explain())));
+            }
+            Json::object(fields)
+        })
+        .collect();
+    out!("{}", Json::Array(results));
+    Ok(())
+}
+
+// Why a command failed, which picks the exit status USAGE documents.
+// Errors from the argument helpers are plain strings and count as usage
+// errors.
+#[derive(Debug)]
+enum Failure {
+    // Exit 1: the output couldn't be written, or a streamed file read.
+    Output(String),
+    // Exit 2: an unknown command, a bad option or key, an unreadable file.
+    Usage(String),
+    // Exit 3: a search found nothing good enough to show.
+    NoCandidate(String),
+    // Exit 4: input a codec, recipe or decoder couldn't read.
+    Decode(String),
+    // Exit 0: whoever reads the output stopped early.
+    Closed,
+}
+
+impl Failure {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Failure::Output(_) => 1,
+            Failure::Usage(_) => 2,
+            Failure::NoCandidate(_) => 3,
+            Failure::Decode(_) => 4,
+            Failure::Closed => 0,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Failure::Output(_) => "output",
+            Failure::Usage(_) => "usage",
+            Failure::NoCandidate(_) => "no_candidate",
+            Failure::Decode(_) => "decode",
+            Failure::Closed => "closed",
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            Failure::Output(message)
+            | Failure::Usage(message)
+            | Failure::NoCandidate(message)
+            | Failure::Decode(message) => message,
+            Failure::Closed => "output closed",
+        }
+    }
+
+    // To stderr: `report_brief`, followed by the usage for usage errors.
+    fn report(&self) {
+        if matches!(self, Failure::Closed) {
+            return;
+        }
+        self.report_brief();
+        if matches!(self, Failure::Usage(_)) && !json_output() {
+            eprintln!("\n{}", USAGE);
+        }
+    }
+
+    // A JSON object with `--json`, otherwise a line of text.
+    fn report_brief(&self) {
+        if json_output() {
+            let json = Json::object(vec![
+                ("error", self.kind().into()),
+                ("message", self.message().into()),
+                ("exit_code", (self.exit_code() as i64).into()),
+            ]);
+            eprintln!("{}", json);
+        } else {
+            eprintln!("error: {}", self.message());
+        }
+    }
+}
+
+impl From<String> for Failure {
+    fn from(message: String) -> Self {
+        Failure::Usage(message)
+    }
+}
+
+impl From<&str> for Failure {
+    fn from(message: &str) -> Self {
+        Failure::Usage(message.to_string())
+    }
+}
 
 fn main() {
-    let plain = "My plain text is Abc & 123".to_string();
-    let key = 3;
-    let mut ceasar = CeasarCipher::new();
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    // Words added with `cryptan wordlist` count when scoring.
+    Wordlist::with_user_words();
+
+    let result = run(&mut args);
+    // Whatever was written before a failure still goes out.
+    let flushed = output().flush().map_err(output_failure);
+    if let Err(failure) = result.and(flushed) {
+        failure.report();
+        process::exit(failure.exit_code());
+    }
+}
+
+fn run(args: &mut Vec<String>) -> Result<(), Failure> {
+    if args.is_empty() {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let command = args.remove(0);
+    take_global_options(args)?;
+    match command.as_str() {
+        "caesar" => caesar(args),
+        "beaufort" => keyword_cipher("beaufort", args, |k| BeaufortCipher::try_new(k).map(boxed)),
+        "autokey" => keyword_cipher("autokey", args, |k| AutokeyCipher::try_new(k).map(boxed)),
+        "porta" => keyword_cipher("porta", args, |k| PortaCipher::try_new(k).map(boxed)),
+        "gronsfeld" => keyword_cipher("gronsfeld", args, |k| GronsfeldCipher::try_new(k).map(boxed)),
+        "atbash" => atbash(args),
+        "columnar" => columnar(args),
+        "route" => route(args),
+        "grille" => grille(args),
+        "playfair" => playfair(args),
+        "bifid" | "trifid" => fractionating(&command, args),
+        "book" => book(args),
+        "keygen" => keygen(args),
+        "wordlist" => wordlist(args),
+        "watch" => watch(args),
+        "tui" => tui(args),
+        "completions" => completions(args),
+        "--generate-man" => {
+            out!("{}", man_page().trim_end());
+            Ok(())
+        }
+        "xor" => xor(args),
+        "byteshift" => byteshift(args),
+        "morse" => morse(args),
+        "pigpen" => pigpen(args),
+        "encode" | "decode" => code(command == "encode", args),
+        "identify" => identify(args),
+        "analyze" => analyze(args),
+        "inspect" => inspect(args),
+        "crib" => crib(args),
+        "solve" => solve(args),
+        "recipe" => recipe(args),
+        "serve" => serve(args),
+        "--jsonrpc" => {
+            let stdin = io::stdin();
+            let served = cryptan::jsonrpc::serve(stdin.lock(), io::stdout());
+            served.map_err(output_failure)
+        }
+        "help" | "-h" | "--help" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(format!("unknown command `{}`", command).into()),
+    }
+}
+
+// `--in <file>`, `--out <file>` and `--json` work with every command, and
+// a `-` in place of the text reads it from stdin.
+fn take_global_options(args: &mut Vec<String>) -> Result<(), String> {
+    // First, so that errors in the rest come out as JSON too.
+    if take_flag(args, "--json") {
+        JSON_OUTPUT.store(true, Ordering::Relaxed);
+    }
+
+    if let Some(path) = take_option(args, "--out")? {
+        let file = File::create(&path).map_err(|e| format!("cannot create `{}`: {}", path, e))?;
+        let _ = OUTPUT.set(Mutex::new(Box::new(BufWriter::new(file))));
+        OUT_FILE.store(true, Ordering::Relaxed);
+    }
+
+    if take_flag(args, "--no-progress") {
+        NO_PROGRESS.store(true, Ordering::Relaxed);
+    }
+
+    if take_flag(args, "--no-color") || take_flag(args, "--no-colour") {
+        NO_COLOR.store(true, Ordering::Relaxed);
+    }
+
+    if take_flag(args, "--verbose") {
+        VERBOSE.store(true, Ordering::Relaxed);
+    }
+
+    if take_flag(args, "--stats") {
+        STATS.store(true, Ordering::Relaxed);
+    }
+
+    while let Some(definition) = take_option(args, "--define")? {
+        let (name, recipe) = definition.split_once('=').ok_or("expected `--define <name>=<recipe>`")?;
+        Pipeline::from_recipe(recipe).map_err(|e| e.to_string())?;
+        Registry::shared_mut().register_recipe(name, recipe).map_err(|e| e.to_string())?;
+    }
+
+    if let Some(path) = take_option(args, "--in")? {
+        let _ = INPUT.set(path);
+    } else if args.last().is_some_and(|arg| arg == "-") {
+        args.pop();
+        let mut data = Vec::new();
+        io::stdin().read_to_end(&mut data).map_err(|e| format!("cannot read stdin: {}", e))?;
+        args.push(input_text(data));
+    }
+
+    Ok(())
+}
+
+// Files and pipes usually end in a newline that isn't part of the text.
+fn input_text(data: Vec<u8>) -> String {
+    let text = String::from_utf8_lossy(&data);
+    let text = text.strip_suffix('\n').unwrap_or(&text);
+    text.strip_suffix('\r').unwrap_or(text).to_string()
+}
+
+// Removes `--name <value>` from the arguments and returns the value.
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>, String> {
+    match args.iter().position(|arg| arg == name) {
+        Some(i) if i + 1 < args.len() => {
+            let value = args.remove(i + 1);
+            args.remove(i);
+            Ok(Some(value))
+        }
+        Some(_) => Err(format!("`{}` needs a value", name)),
+        None => Ok(None),
+    }
+}
+
+fn take_flag(args: &mut Vec<String>, name: &str) -> bool {
+    match args.iter().position(|arg| arg == name) {
+        Some(i) => {
+            args.remove(i);
+            true
+        }
+        None => false,
+    }
+}
+
+fn take_text(args: &mut Vec<String>) -> Result<String, String> {
+    if let Some(path) = INPUT.get() {
+        let data = fs::read(path).map_err(|e| format!("cannot read `{}`: {}", path, e))?;
+        return Ok(input_text(data));
+    }
+    if args.is_empty() {
+        return Err("missing text".to_string());
+    }
+
+    Ok(std::mem::take(args).join(" "))
+}
+
+// With `--in` and plain output, runs the file through the cipher a chunk
+// at a time instead of reading it all. False means nothing was done and
+// the caller should take the text as usual.
+fn stream_input(cipher: &dyn ClassicalCipher, encrypt: bool) -> Result<bool, Failure> {
+    let Some(path) = INPUT.get() else {
+        return Ok(false);
+    };
+    if json_output() {
+        return Ok(false);
+    }
+
+    let mut file = File::open(path).map_err(|e| format!("cannot read `{}`: {}", path, e))?;
+    let mut output = output();
+    let result = if encrypt {
+        cipher.encrypt_reader(&mut file, &mut *output)
+    } else {
+        cipher.decrypt_reader(&mut file, &mut *output)
+    };
+    result.map_err(output_failure)?;
+    Ok(true)
+}
+
+fn take_key(args: &mut Vec<String>) -> Result<i64, String> {
+    if args.is_empty() {
+        return Err("missing key".to_string());
+    }
+
+    let key = args.remove(0);
+    key.parse::<i64>().map_err(|_| format!("invalid key `{}`", key))
+}
+
+// Below this many letters `caesar brute` doesn't trust its scores.
+const DEFAULT_MIN_LENGTH: usize = 6;
+
+// How often `watch` looks for changes.
+const WATCH_INTERVAL_MS: usize = 300;
+
+fn caesar(args: &mut Vec<String>) -> Result<(), Failure> {
+    if args.is_empty() {
+        return Err("missing caesar subcommand".to_string().into());
+    }
+
+    let subcommand = args.remove(0);
+    match subcommand.as_str() {
+        "encrypt" | "decrypt" => {
+            let unicode = take_flag(args, "--unicode");
+            let rot5 = take_flag(args, "--rot5");
+            let alphabet = take_alphabet(args)?;
+            if unicode && alphabet.is_some() {
+                return Err("`--unicode` and `--alphabet` cannot be used together".to_string().into());
+            }
+            let policy: CharPolicy = take_option(args, "--policy")?.unwrap_or("preserve".to_string()).parse()?;
+            let key = take_key(args)?;
+            let caesar = match alphabet {
+                Some(alphabet) => CaesarCipher::with_alphabet(key, alphabet),
+                None => CaesarCipher::new(key).with_unicode(unicode),
+            };
+            let caesar = caesar.with_rotated_digits(rot5).with_policy(policy);
+            // Streaming can't stop at the first unsupported letter.
+            if policy != CharPolicy::Error && stream_input(&caesar, subcommand == "encrypt")? {
+                return Ok(());
+            }
+            let text = take_text(args)?;
+            let result = if subcommand == "encrypt" { caesar.try_encrypt(&text) } else { caesar.try_decrypt(&text) };
+            emit_text("caesar", &result.map_err(|e| e.to_string())?)?;
+        }
+        "rot18" => emit_text("caesar", &CaesarCipher::rot18().encrypt(&take_text(args)?))?,
+        "brute" => {
+            let top = take_number(args, "--top", 26)?;
+            let best = take_flag(args, "--best");
+            let min_length = take_number(args, "--min-length", DEFAULT_MIN_LENGTH)?;
+            let scorer = take_scorer(args)?;
+            let alphabet = take_alphabet(args)?.unwrap_or_else(Alphabet::latin);
+            let mut options = BruteForceOptions::new().with_threshold(take_float(args, "--threshold")?.unwrap_or(0.0));
+            if let Some(score) = take_float(args, "--stop-above")? {
+                options = options.with_stop_at_first_above(score);
+            }
+            let text = take_text(args)?;
+
+            let budget = progress_budget();
+            let mut results = caesar::brute_force_with_budget(&text, &alphabet, scorer.as_ref(), &options, &budget);
+            finish_progress(&budget);
+            // Scores on a handful of letters are mostly noise, so short
+            // inputs list every key in order rather than a ranking.
+            if text.chars().filter(|c| alphabet.contains(*c)).count() < min_length {
+                eprintln!("warning: fewer than {} letters, too short to rank; showing keys in order", min_length);
+                results.sort_by_key(|result| result.key.parse::<u32>().unwrap_or(0));
+            }
+            results.truncate(top);
+
+            match results.first() {
+                Some(result) if best && !json_output() => out!("{}", result.text),
+                Some(_) if best => emit_results("caesar", &results[..1])?,
+                _ => emit_results("caesar", &results)?,
+            }
+        }
+        "vigenere" if args.first().is_some_and(|arg| arg == "crack") => {
+            args.remove(0);
+            let max_length = take_number(args, "--max-length", 20)?;
+            let top = take_number(args, "--top", 3)?;
+            let solver = VigenereSolver::new().with_max_key_length(max_length);
+            let budget = progress_budget();
+            let results = solver.solve_with_budget(&take_text(args)?, &budget);
+            finish_progress(&budget);
+            let results: Vec<DecodedResult> = results.into_iter().take(top).collect();
+            emit_results("vigenere", &results)?;
+        }
+        "vigenere" => return keyword_cipher("vigenere", args, |k| VigenereCipher::try_new(k).map(boxed)),
+        _ => return Err(format!("unknown caesar subcommand `{}`", subcommand).into()),
+    }
+
+    Ok(())
+}
+
+// `--alphabet`, by name or spelled out. Spelled-out symbols are
+// case-sensitive.
+fn take_alphabet(args: &mut Vec<String>) -> Result<Option<Alphabet>, String> {
+    let Some(value) = take_option(args, "--alphabet")? else {
+        return Ok(None);
+    };
+    if let Some(alphabet) = Alphabet::by_name(&value) {
+        return Ok(Some(alphabet));
+    }
+    Alphabet::new(&value).map(Some).map_err(|e| format!("{} (or use one of: {})", e, ALPHABET_NAMES.join(", ")))
+}
+
+// `--scorer`, or a word-list scorer when `--wordlist` or `--language`
+// picks the words.
+fn take_scorer(args: &mut Vec<String>) -> Result<Box<dyn Scorer>, String> {
+    let name = take_option(args, "--scorer")?;
+    let wordlist = take_option(args, "--wordlist")?;
+    let language = take_option(args, "--language")?;
+
+    if wordlist.is_some() || language.is_some() {
+        if name.as_deref().is_some_and(|name| name != "wordlist" && name != "multilingual") {
+            return Err("--wordlist and --language only apply to the wordlist and multilingual scorers".to_string());
+        }
+        return Ok(match (wordlist, language) {
+            (Some(path), _) => Box::new(
+                WordlistScorer::from_file(&path).map_err(|e| format!("cannot read word list {}: {}", path, e))?,
+            ),
+            (None, Some(language)) if language == "auto" => Box::new(MultiLanguageScorer::all()),
+            (None, Some(language)) => {
+                let languages: Vec<Language> = language.split(',').map(str::parse).collect::<Result<_, _>>()?;
+                match languages[..] {
+                    [language] => Box::new(WordlistScorer::for_language(language)),
+                    _ => Box::new(MultiLanguageScorer::new(&languages)),
+                }
+            }
+            (None, None) => unreachable!(),
+        });
+    }
+
+    let name = name.unwrap_or("english".to_string());
+    scorer_by_name(&name).map_err(|e| e.to_string())
+}
+
+fn take_word(args: &mut Vec<String>, what: &str) -> Result<String, String> {
+    if args.is_empty() {
+        return Err(format!("missing {}", what));
+    }
+
+    Ok(args.remove(0))
+}
+
+fn boxed<C: ClassicalCipher + 'static>(cipher: C) -> Box<dyn ClassicalCipher> {
+    Box::new(cipher)
+}
+
+// `<name> encrypt|decrypt <keyword> <text>` for the keyword-driven
+// polyalphabetic ciphers.
+fn keyword_cipher(
+    name: &str,
+    args: &mut Vec<String>,
+    make: fn(&str) -> cryptan::Result<Box<dyn ClassicalCipher>>,
+) -> Result<(), Failure> {
+    let subcommand = take_word(args, &format!("{} subcommand", name))?;
+    let keyword = take_word(args, "keyword")?;
+    let cipher = make(&keyword).map_err(|e| e.to_string())?;
+    let encrypt = match subcommand.as_str() {
+        "encrypt" => true,
+        "decrypt" => false,
+        _ => return Err(format!("unknown {} subcommand `{}`", name, subcommand).into()),
+    };
+    if stream_input(&*cipher, encrypt)? {
+        return Ok(());
+    }
+
+    let text = take_text(args)?;
+    if encrypt {
+        emit_text(name, &cipher.encrypt(&text))?;
+    } else {
+        emit_text(name, &cipher.decrypt(&text))?;
+    }
+
+    Ok(())
+}
+
+fn atbash(args: &mut Vec<String>) -> Result<(), Failure> {
+    let subcommand = take_word(args, "atbash subcommand")?;
+    if subcommand != "encode" && subcommand != "decode" {
+        return Err(format!("unknown atbash subcommand `{}`", subcommand).into());
+    }
+    // Atbash is its own inverse.
+    if stream_input(&AtbashCipher::new(), true)? {
+        return Ok(());
+    }
+
+    emit_text("atbash", &AtbashCipher::new().encrypt(&take_text(args)?))?;
+    Ok(())
+}
+
+fn take_number(args: &mut Vec<String>, name: &str, default: usize) -> Result<usize, String> {
+    match take_option(args, name)? {
+        Some(value) => value.parse().map_err(|_| format!("`{}` needs a number, got `{}`", name, value)),
+        None => Ok(default),
+    }
+}
+
+fn parse_float(name: &str, value: &str) -> Result<f64, String> {
+    value.parse().map_err(|_| format!("`{}` needs a number, got `{}`", name, value))
+}
+
+fn take_float(args: &mut Vec<String>, name: &str) -> Result<Option<f64>, String> {
+    take_option(args, name)?.map(|value| parse_float(name, &value)).transpose()
+}
+
+fn columnar(args: &mut Vec<String>) -> Result<(), Failure> {
+    let subcommand = take_word(args, "columnar subcommand")?;
+
+    if subcommand == "brute" {
+        let min = take_number(args, "--min", 2)?;
+        let max = take_number(args, "--max", columnar::EXHAUSTIVE_MAX_COLUMNS)?;
+        let top = take_number(args, "--top", 5)?;
+        let budget = progress_budget();
+        let results = columnar::brute_force_with_budget(&take_text(args)?, min, max, top, &budget);
+        finish_progress(&budget);
+        return emit_results("columnar", &results);
+    }
+
+    let keyword = take_option(args, "--keyword")?.ok_or("missing `--keyword <keyword>`")?;
+    let mut cipher = ColumnarTransposition::try_from_keyword(&keyword).map_err(|e| e.to_string())?;
+    if let Some(pad) = take_option(args, "--pad-char")? {
+        let mut chars = pad.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => cipher = cipher.with_padding(c),
+            _ => return Err(format!("`--pad-char` needs a single character, got `{}`", pad).into()),
+        }
+    }
+    let text = take_text(args)?;
+
+    match subcommand.as_str() {
+        "encrypt" => emit_text("columnar", &cipher.encrypt(&text))?,
+        "decrypt" => emit_text("columnar", &cipher.decrypt(&text))?,
+        _ => return Err(format!("unknown columnar subcommand `{}`", subcommand).into()),
+    }
+
+    Ok(())
+}
+
+fn route(args: &mut Vec<String>) -> Result<(), Failure> {
+    let subcommand = take_word(args, "route subcommand")?;
+
+    if subcommand == "brute" {
+        let options = BruteForceOptions::new().with_max_results(take_number(args, "--top", 5)?);
+        let budget = progress_budget();
+        let results = route::brute_force_with_budget(&take_text(args)?, &NgramScorer, &options, &budget);
+        finish_progress(&budget);
+        return emit_results("route", &results);
+    }
+
+    let columns = take_number(args, "--columns", 0)?;
+    let rows = take_number(args, "--rows", 0)?;
+    let route: Route = take_option(args, "--route")?.unwrap_or("columns".to_string()).parse()?;
+    let text = take_text(args)?;
+    // Without `--rows`, one grid holds the whole text.
+    let rows = if rows == 0 { text.chars().count().div_ceil(columns.max(1)).max(1) } else { rows };
+    let cipher = RouteCipher::try_new(rows, columns, route).map_err(|e| e.to_string())?;
+
+    match subcommand.as_str() {
+        "encrypt" => emit_text("route", &cipher.encrypt(&text))?,
+        "decrypt" => emit_text("route", &cipher.decrypt(&text))?,
+        _ => return Err(format!("unknown route subcommand `{}`", subcommand).into()),
+    }
+
+    Ok(())
+}
+
+fn grille(args: &mut Vec<String>) -> Result<(), Failure> {
+    let subcommand = take_word(args, "grille subcommand")?;
+    let mask = take_option(args, "--grille")?.ok_or("missing `--grille <mask>`")?;
+    let cipher = TurningGrille::try_from_mask(&mask).map_err(|e| e.to_string())?;
+    let text = take_text(args)?;
+
+    match subcommand.as_str() {
+        "encrypt" => emit_text("grille", &cipher.encrypt(&text))?,
+        "decrypt" => emit_text("grille", &cipher.decrypt(&text))?,
+        _ => return Err(format!("unknown grille subcommand `{}`", subcommand).into()),
+    }
+
+    Ok(())
+}
+
+fn playfair(args: &mut Vec<String>) -> Result<(), Failure> {
+    let subcommand = take_word(args, "playfair subcommand")?;
+    let keyword = take_option(args, "--keyword")?.ok_or("missing `--keyword <keyword>`")?;
+    let cipher = PlayfairCipher::new(&keyword);
+    let text = take_text(args)?;
+
+    match subcommand.as_str() {
+        "encrypt" => emit_text("playfair", &cipher.encrypt(&text))?,
+        "decrypt" => emit_text("playfair", &cipher.decrypt(&text))?,
+        _ => return Err(format!("unknown playfair subcommand `{}`", subcommand).into()),
+    }
+
+    Ok(())
+}
+
+// Bifid and Trifid.
+fn fractionating(name: &str, args: &mut Vec<String>) -> Result<(), Failure> {
+    let subcommand = take_word(args, &format!("{} subcommand", name))?;
+    let keyword = take_option(args, "--keyword")?.ok_or("missing `--keyword <keyword>`")?;
+    let period = take_number(args, "--period", 0)?;
+    let cipher: Box<dyn ClassicalCipher> = match name {
+        "bifid" => Box::new(BifidCipher::new(&keyword).with_period(period)),
+        _ => Box::new(TrifidCipher::new(&keyword).with_period(period)),
+    };
+    let text = take_text(args)?;
+
+    match subcommand.as_str() {
+        "encrypt" => emit_text(name, &cipher.encrypt(&text))?,
+        "decrypt" => emit_text(name, &cipher.decrypt(&text))?,
+        _ => return Err(format!("unknown {} subcommand `{}`", name, subcommand).into()),
+    }
+
+    Ok(())
+}
+
+fn book(args: &mut Vec<String>) -> Result<(), Failure> {
+    let subcommand = take_word(args, "book subcommand")?;
+    let key_file = take_option(args, "--key-file")?.ok_or("missing `--key-file <file>`")?;
+    let style: BookStyle = take_option(args, "--style")?.unwrap_or("index".to_string()).parse()?;
+    let cipher = BookCipher::from_file(&key_file).map_err(|e| format!("cannot read key text {}: {}", key_file, e))?;
+    let cipher = cipher.with_style(style);
+    let text = take_text(args)?;
+
+    match subcommand.as_str() {
+        "encode" => emit_text("book", &cipher.encrypt(&text))?,
+        "decode" => emit_text("book", &cipher.decrypt_strict(&text).map_err(|e| e.to_string())?)?,
+        _ => return Err(format!("unknown book subcommand `{}`", subcommand).into()),
+    }
+
+    Ok(())
+}
+
+fn keygen(args: &mut Vec<String>) -> Result<(), Failure> {
+    let cipher = take_word(args, "cipher")?;
+    let mut generator = match take_option(args, "--seed")? {
+        Some(seed) => KeyGenerator::seeded(seed.parse().map_err(|_| format!("`{}` is not a valid seed", seed))?),
+        None => KeyGenerator::new(),
+    };
+    let length = take_number(args, "--length", 6)?;
+
+    let key = match cipher.as_str() {
+        "caesar" => generator.caesar_shift().to_string(),
+        "affine" => {
+            let (a, b) = generator.affine_key();
+            format!("{},{}", a, b)
+        }
+        "substitution" => generator.substitution_alphabet(),
+        "vigenere" => generator.vigenere_keyword(length).map_err(|e| e.to_string())?,
+        "columnar" => {
+            let order = generator.transposition_order(length).map_err(|e| e.to_string())?;
+            order.iter().map(usize::to_string).collect::<Vec<_>>().join(",")
+        }
+        "playfair" => generator.playfair_keyword(),
+        "grille" => generator.turning_grille(length).map_err(|e| e.to_string())?.mask(),
+        _ => {
+            let known = KEYGEN_CIPHERS.join(", ");
+            return Err(format!("cannot make keys for `{}`, expected one of: {}", cipher, known).into());
+        }
+    };
+    emit_text(&cipher, &key)?;
+    Ok(())
+}
+
+fn wordlist(args: &mut Vec<String>) -> Result<(), Failure> {
+    let subcommand = take_word(args, "wordlist subcommand")?;
+    let language: Option<Language> = take_option(args, "--language")?.map(|code| code.parse()).transpose()?;
+    let open = |language: Language| UserWords::open(language).map_err(|e| e.to_string());
+
+    match subcommand.as_str() {
+        "add" | "remove" | "import" => {
+            let mut user = open(language.unwrap_or_default())?;
+            let text = match subcommand.as_str() {
+                "import" => {
+                    let file = take_word(args, "word list file")?;
+                    let text = fs::read_to_string(&file).map_err(|e| format!("cannot read `{}`: {}", file, e))?;
+                    let first_words = text.lines().filter(|line| !line.starts_with('#'));
+                    first_words.filter_map(|line| line.split_whitespace().next()).collect::<Vec<_>>().join(" ")
+                }
+                _ => take_text(args)?,
+            };
+            let changed = match subcommand.as_str() {
+                "remove" => user.remove(&text),
+                _ => user.add(&text),
+            };
+            user.save().map_err(|e| format!("cannot write `{}`: {}", user.path().display(), e))?;
+            let verb = if subcommand == "remove" { "removed" } else { "added" };
+            eprintln!("{} {} word(s); {} now has {}", verb, changed, user.path().display(), user.len());
+        }
+        "export" => {
+            let user = open(language.unwrap_or_default())?;
+            if json_output() {
+                out!("{}", Json::Array(user.words().map(Json::from).collect()));
+            } else {
+                for word in user.words() {
+                    out!("{}", word);
+                }
+            }
+        }
+        "stats" => {
+            let languages = language.map_or(Language::ALL.to_vec(), |language| vec![language]);
+            let mut stats = Vec::new();
+            for language in languages {
+                let user = open(language)?;
+                let dictionary = Wordlist::for_language(language).len();
+                if json_output() {
+                    stats.push(Json::object(vec![
+                        ("language", language.code().into()),
+                        ("dictionary_words", dictionary.into()),
+                        ("user_words", user.len().into()),
+                        ("path", user.path().display().to_string().into()),
+                    ]));
+                } else {
+                    let path = user.path().display();
+                    out!("{}: {} words, {} of them yours ({})", language.code(), dictionary, user.len(), path);
+                }
+            }
+            if json_output() {
+                out!("{}", Json::Array(stats));
+            }
+        }
+        _ => return Err(format!("unknown wordlist subcommand `{}`", subcommand).into()),
+    }
+    Ok(())
+}
+
+fn xor(args: &mut Vec<String>) -> Result<(), Failure> {
+    let subcommand = take_word(args, "xor subcommand")?;
+
+    if subcommand == "brute" {
+        let top = take_number(args, "--top", 5)?;
+        let data = hex_decode(&take_text(args)?).ok_or(Failure::Decode("input is not valid hex".to_string()))?;
+        let results: Vec<DecodedResult> = xor::single_byte_brute_force(&data)
+            .iter()
+            .take(top)
+            .map(|c| {
+                let text = String::from_utf8_lossy(&c.plaintext);
+                DecodedResult::new(format!("0x{:02x}", c.key), text, c.score).with_scorer("english-bytes")
+            })
+            .collect();
+        return emit_results("xor", &results);
+    }
+
+    if subcommand == "crack" {
+        let max_size = take_number(args, "--max-key-size", DEFAULT_MAX_KEY_SIZE)?;
+        let candidates = take_number(args, "--key-sizes", DEFAULT_KEY_SIZE_CANDIDATES)?;
+        let top = take_number(args, "--top", 5)?;
+        let data = hex_decode(&take_text(args)?).ok_or(Failure::Decode("input is not valid hex".to_string()))?;
+        let results: Vec<DecodedResult> = break_repeating_xor(&data, max_size, candidates)
+            .iter()
+            .take(top)
+            .map(|c| {
+                let hex: String = c.key.iter().map(|b| format!("{:02x}", b)).collect();
+                let key = match std::str::from_utf8(&c.key) {
+                    Ok(text) if c.key.iter().all(|b| b.is_ascii_graphic() || *b == b' ') => {
+                        format!("0x{} ({:?})", hex, text)
+                    }
+                    _ => format!("0x{}", hex),
+                };
+                DecodedResult::new(key, String::from_utf8_lossy(&c.plaintext), c.score).with_scorer("english-bytes")
+            })
+            .collect();
+        return emit_results("xor", &results);
+    }
+
+    let key = take_option(args, "--key")?.ok_or("missing `--key <key>`")?;
+    let encoding: OutputEncoding = take_option(args, "--encoding")?.unwrap_or("hex".to_string()).parse()?;
+    let cipher = XorCipher::try_new(key.as_bytes()).map_err(|e| e.to_string())?.with_encoding(encoding);
+    let text = take_text(args)?;
+
+    match subcommand.as_str() {
+        "encrypt" => emit_text("xor", &cipher.encrypt(&text))?,
+        "decrypt" => {
+            let invalid = Failure::Decode("ciphertext is not valid for the chosen encoding".to_string());
+            let plain = cipher.try_decrypt(&text).ok_or(invalid)?;
+            emit_text("xor", &String::from_utf8_lossy(&plain))?;
+        }
+        _ => return Err(format!("unknown xor subcommand `{}`", subcommand).into()),
+    }
+
+    Ok(())
+}
+
+fn byteshift(args: &mut Vec<String>) -> Result<(), Failure> {
+    let subcommand = take_word(args, "byteshift subcommand")?;
+    let hex = take_flag(args, "--hex");
+
+    if subcommand == "brute" {
+        let top = take_number(args, "--top", 5)?;
+        let data = take_bytes(args, hex)?;
+        let budget = progress_budget();
+        let results: Vec<DecodedResult> = byte_shift::brute_force_with_budget(&data, &budget)
+            .iter()
+            .take(top)
+            .map(|c| DecodedResult::new(c.key.to_string(), String::from_utf8_lossy(&c.bytes), c.score))
+            .map(|result| result.with_scorer("printable"))
+            .collect();
+        finish_progress(&budget);
+        return emit_results("byteshift", &results);
+    }
+
+    let encrypt = match subcommand.as_str() {
+        "encrypt" => true,
+        "decrypt" => false,
+        _ => return Err(format!("unknown byteshift subcommand `{}`", subcommand).into()),
+    };
+    let key = take_word(args, "key")?;
+    let key: u8 = key.parse().map_err(|_| format!("invalid key `{}`, expected 0-255", key))?;
+    let cipher = ByteShiftCipher::new(key);
+
+    if let Some(path) = INPUT.get().filter(|_| !hex && !json_output()) {
+        let mut file = File::open(path).map_err(|e| format!("cannot read `{}`: {}", path, e))?;
+        let mut sink = output();
+        let result = if encrypt {
+            cipher.encrypt_reader(&mut file, &mut *sink)
+        } else {
+            cipher.decrypt_reader(&mut file, &mut *sink)
+        };
+        result.map_err(output_failure)?;
+        return Ok(());
+    }
+
+    let data = take_bytes(args, hex)?;
+    let result = if encrypt { cipher.encrypt_bytes(&data) } else { cipher.decrypt_bytes(&data) };
+    if hex || json_output() {
+        emit_text("byteshift", &hex_encode(&result))?;
+    } else {
+        // Raw, as the result needn't be UTF-8.
+        let mut sink = output();
+        sink.write_all(&result).and_then(|_| sink.write_all(b"\n")).map_err(output_failure)?;
+    }
+    Ok(())
+}
+
+// The exact bytes of an `--in` file, or the text, or with `hex` the bytes
+// the text spells out in hex.
+fn take_bytes(args: &mut Vec<String>, hex: bool) -> Result<Vec<u8>, Failure> {
+    if let (Some(path), false) = (INPUT.get(), hex) {
+        return Ok(fs::read(path).map_err(|e| format!("cannot read `{}`: {}", path, e))?);
+    }
+    let text = take_text(args)?;
+    if !hex {
+        return Ok(text.into_bytes());
+    }
+    hex_decode(text.trim()).ok_or(Failure::Decode("input is not valid hex".to_string()))
+}
+
+fn identify(args: &mut Vec<String>) -> Result<(), Failure> {
+    let guesses = CipherIdentifier::new().identify(&take_text(args)?);
+    if json_output() {
+        out!("{}", Json::Array(guesses.iter().map(Json::from).collect()));
+        return Ok(());
+    }
+    if guesses.is_empty() {
+        out!("no idea");
+    }
+    for guess in guesses {
+        out!("{:.2}  {}", guess.confidence, guess.kind);
+    }
+
+    Ok(())
+}
+
+fn analyze(args: &mut Vec<String>) -> Result<(), Failure> {
+    let leet = take_flag(args, "--leet");
+    let mut text = take_text(args)?;
+    if leet {
+        text = LeetSpeak::new().normalize(&text, &WordlistScorer::new());
+    }
+    let report = AnalysisReport::new(&text);
+    if json_output() {
+        out!("{}", Json::from(&report));
+        return Ok(());
+    }
+    let text = if colour() { format!("{:#}", report) } else { report.to_string() };
+    out!("{}", text.trim_end());
+    Ok(())
+}
+
+// Unlike the other commands, looks at the exact bytes of an `--in` file.
+fn inspect(args: &mut Vec<String>) -> Result<(), Failure> {
+    let data = match INPUT.get() {
+        Some(path) => fs::read(path).map_err(|e| format!("cannot read `{}`: {}", path, e))?,
+        None => take_text(args)?.into_bytes(),
+    };
+    let inspection = Inspection::new(&data);
+
+    if json_output() {
+        out!("{}", Json::from(&inspection));
+    } else {
+        out!("{}\n\n{}", hex_dump(&data), inspection);
+    }
+    Ok(())
+}
+
+fn crib(args: &mut Vec<String>) -> Result<(), Failure> {
+    let top = take_number(args, "--top", 5)?;
+    let crib = take_word(args, "crib")?;
+    let mut results = crib_attack(&take_text(args)?, &crib);
+    results.truncate(top);
+    if results.is_empty() && !json_output() {
+        return Err(Failure::NoCandidate("the crib doesn't fit any Caesar or Vigenère key".to_string()));
+    }
+    emit_results("crib", &results)
+}
+
+fn recipe(args: &mut Vec<String>) -> Result<(), Failure> {
+    let subcommand = take_word(args, "recipe subcommand")?;
+    if subcommand != "run" {
+        return Err(format!("unknown recipe subcommand `{}`", subcommand).into());
+    }
+
+    let decode = take_flag(args, "--decode");
+    let file = take_word(args, "recipe file")?;
+    let pipeline = Pipeline::load(&file).map_err(|e| format!("cannot load `{}`: {}", file, e))?;
+    let text = take_text(args)?;
+
+    if decode {
+        emit_text(&pipeline.to_recipe(), &pipeline.backward(&text))?;
+    } else {
+        emit_text(&pipeline.to_recipe(), &pipeline.forward(&text))?;
+    }
+
+    Ok(())
+}
+
+fn solve(args: &mut Vec<String>) -> Result<(), Failure> {
+    let depth = take_number(args, "--depth", pipeline::DEFAULT_MAX_DEPTH)?;
+    let save = take_option(args, "--save")?;
+    let mut budget = progress_budget();
+    if let Some(limit) = take_option(args, "--time-limit")? {
+        let millis = limit.parse().map_err(|_| format!("`--time-limit` needs milliseconds, got `{}`", limit))?;
+        budget = budget.with_time_limit(Duration::from_millis(millis));
+    }
+    let text = take_text(args)?;
+
+    let solution = Pipeline::auto_solve_with_budget(&text, depth, &budget);
+    finish_progress(&budget);
+    if json_output() {
+        let json = match &solution {
+            Some(solution) => Json::object(vec![
+                ("chain", Json::Array(solution.chain().into_iter().map(Json::from).collect())),
+                ("recipe", solution.pipeline.to_recipe().into()),
+                ("plaintext", solution.text.as_str().into()),
+                ("score", solution.score.into()),
+                ("meaningful_ratio", meaningful_ratio(&solution.text).into()),
+            ]),
+            None => Json::Null,
+        };
+        out!("{}", json);
+    }
+
+    let Some(solution) = solution else {
+        return Err(Failure::NoCandidate("nothing found that reads better than the input".to_string()));
+    };
+    if !json_output() {
+        out!(
+            "CHAIN: {}\nRECIPE: {}\nSCORE: {:.2}\nDECODED TEXT: {}",
+            solution.chain().join(" -> "),
+            solution.pipeline.to_recipe(),
+            solution.score,
+            solution.text
+        );
+    }
+
+    // `recipe run --decode <file>` replays the solution.
+    if let Some(path) = save {
+        solution.pipeline.save(&path).map_err(|e| format!("cannot save `{}`: {}", path, e))?;
+    }
+
+    Ok(())
+}
+
+fn code(encode: bool, args: &mut Vec<String>) -> Result<(), Failure> {
+    // Taken first, so they never end up in the text.
+    let lang = take_option(args, "--lang")?;
+    let lenient = take_flag(args, "--lenient");
+    let strict = take_flag(args, "--strict");
+    let codec_only = || match (lang.is_some() || lenient, strict) {
+        (true, _) => Err("`--lang` and `--lenient` only apply to the morse codec"),
+        (_, true) => Err("`--strict` only applies to decoding with a codec"),
+        _ => Ok(()),
+    };
+
+    if let Some(recipe) = take_option(args, "--recipe")? {
+        codec_only()?;
+        return run_recipe(encode, &recipe, args);
+    }
+
+    // Any codec or cipher the registry knows, with its key: `--codec caesar:3`.
+    let name = take_option(args, "--codec")?.ok_or("missing `--codec <name>`")?;
+    let registry = Registry::shared();
+    let mut codec = match registry.create(&name) {
+        Ok(Transform::Codec(codec)) => codec,
+        Ok(Transform::Cipher(cipher)) => {
+            codec_only()?;
+            let text = take_text(args)?;
+            emit_text(&name, &if encode { cipher.encrypt(&text) } else { cipher.decrypt(&text) })?;
+            return Ok(());
+        }
+        Err(e) if registry.get(name.split(':').next().unwrap_or("")).is_some() => return Err(e.to_string().into()),
+        Err(_) => {
+            let known = registry.names().join(", ");
+            return Err(format!("unknown codec `{}`, expected one of: {}", name, known).into());
+        }
+    };
+    if lang.is_some() || lenient {
+        if !name.eq_ignore_ascii_case("morse") {
+            return Err("`--lang` and `--lenient` only apply to the morse codec".to_string().into());
+        }
+        let morse = Morse::try_from_lang(lang.as_deref().unwrap_or("international")).map_err(|e| e.to_string())?;
+        codec = Box::new(morse.with_lenient(lenient));
+    }
+    let text = take_text(args)?;
+
+    let diagnostics = Diagnostics::new().with_callback(|warning| eprintln!("warning: {}", warning));
+    if encode {
+        emit_text(&name, &codec.encode_with_diagnostics(&text, &diagnostics))?;
+    } else if strict {
+        emit_text(&name, &codec.try_decode(&text).map_err(|e| Failure::Decode(e.to_string()))?)?;
+    } else {
+        emit_text(&name, &codec.decode_with_diagnostics(&text, &diagnostics))?;
+    }
+
+    Ok(())
+}
+
+// `--recipe` takes the recipe itself or a recipe file (plain, JSON or YAML).
+fn run_recipe(encode: bool, recipe: &str, args: &mut Vec<String>) -> Result<(), Failure> {
+    let pipeline = load_recipe(recipe)?;
+    let text = take_text(args)?;
+
+    if encode {
+        emit_text(&pipeline.to_recipe(), &pipeline.forward(&text))?;
+    } else {
+        emit_text(&pipeline.to_recipe(), &pipeline.backward(&text))?;
+    }
+
+    Ok(())
+}
+
+fn load_recipe(recipe: &str) -> Result<Pipeline, String> {
+    if Path::new(recipe).is_file() {
+        Pipeline::load(recipe).map_err(|e| format!("cannot load `{}`: {}", recipe, e))
+    } else {
+        Pipeline::from_recipe(recipe).map_err(|e| e.to_string())
+    }
+}
+
+// Runs `recipe` on `file` now and again whenever either changes, until
+// interrupted. Polls, as std has no file change notifications. Errors are
+// reported and the watch goes on, since the next edit may fix them.
+fn watch(args: &mut Vec<String>) -> Result<(), Failure> {
+    let recipe = take_option(args, "--recipe")?.ok_or("missing `--recipe <recipe|file>`")?;
+    let decode = take_flag(args, "--decode");
+    let interval = Duration::from_millis(take_number(args, "--interval", WATCH_INTERVAL_MS)? as u64);
+    let file = match INPUT.get() {
+        Some(path) => path.clone(),
+        None => take_word(args, "file to watch")?,
+    };
+    fs::metadata(&file).map_err(|e| format!("cannot watch `{}`: {}", file, e))?;
+
+    let modified = |path: &str| fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+    let mut seen = None;
+    loop {
+        let now = Some((modified(&file), modified(&recipe)));
+        if now != seen {
+            // On a terminal each run replaces the last; elsewhere they're
+            // separated by a blank line.
+            if output_is_terminal() {
+                write!(output(), "\x1b[2J\x1b[H").map_err(output_failure)?;
+            } else if seen.is_some() {
+                out!();
+            }
+            seen = now;
+            let result = load_recipe(&recipe).map_err(Failure::from).and_then(|pipeline| {
+                let data = fs::read(&file).map_err(|e| format!("cannot read `{}`: {}", file, e))?;
+                let text = input_text(data);
+                let output = if decode { pipeline.backward(&text) } else { pipeline.forward(&text) };
+                emit_text(&pipeline.to_recipe(), &output)
+            });
+            // A bad recipe or file is reported and waited out; the output
+            // going away ends the watch.
+            match result {
+                Err(failure @ (Failure::Output(_) | Failure::Closed)) => return Err(failure),
+                Err(failure) => failure.report_brief(),
+                Ok(()) => {}
+            }
+            output().flush().map_err(output_failure)?;
+        }
+        thread::sleep(interval);
+    }
+}
+
+#[cfg(feature = "audio")]
+fn morse(args: &mut Vec<String>) -> Result<(), Failure> {
+    use cryptan::encoding::morse_audio::write_wav;
+    use cryptan::encoding::{AudioSettings, Morse};
+
+    let subcommand = take_word(args, "morse subcommand")?;
+    let mut settings = AudioSettings::new();
+    if let Some(wpm) = take_option(args, "--wpm")? {
+        settings = settings.with_wpm(parse_float("--wpm", &wpm)?);
+    }
+    if let Some(wpm) = take_option(args, "--farnsworth")? {
+        settings = settings.with_farnsworth(parse_float("--farnsworth", &wpm)?);
+    }
+    if let Some(tone) = take_option(args, "--tone")? {
+        settings = settings.with_frequency(parse_float("--tone", &tone)?);
+    }
+
+    // The global `--out` has already been taken, so `export` only checks
+    // that it was given; both write the WAV to the output.
+    match subcommand.as_str() {
+        "export" if !OUT_FILE.load(Ordering::Relaxed) => Err("missing `--out <file.wav>`".into()),
+        "export" | "play" => {
+            let samples = Morse::new().to_audio(&take_text(args)?, &settings);
+            let written = write_wav(&mut *output(), &samples, settings.sample_rate());
+            written.map_err(output_failure)
+        }
+        _ => Err(format!("unknown morse subcommand `{}`", subcommand).into()),
+    }
+}
+
+#[cfg(not(feature = "audio"))]
+fn morse(_args: &mut Vec<String>) -> Result<(), Failure> {
+    Err("this build has no audio output; rebuild with `--features audio`".to_string().into())
+}
+
+#[cfg(feature = "svg")]
+fn pigpen(args: &mut Vec<String>) -> Result<(), Failure> {
+    use cryptan::encoding::pigpen_svg::DEFAULT_COLUMNS;
+    use cryptan::encoding::{Pigpen, SvgSettings};
+
+    let subcommand = take_word(args, "pigpen subcommand")?;
+    if subcommand != "svg" {
+        return Err(format!("unknown pigpen subcommand `{}`", subcommand).into());
+    }
+
+    let mut settings = SvgSettings::new()
+        .with_answers(take_flag(args, "--answers"))
+        .with_columns(take_number(args, "--columns", DEFAULT_COLUMNS)?);
+    if let Some(size) = take_float(args, "--cell-size")? {
+        settings = settings.with_cell_size(size);
+    }
+
+    let svg = Pigpen::new().to_svg(&take_text(args)?, &settings);
+    write!(output(), "{}", svg).map_err(output_failure)
+}
+
+#[cfg(not(feature = "svg"))]
+fn pigpen(_args: &mut Vec<String>) -> Result<(), Failure> {
+    Err("this build has no SVG output; rebuild with `--features svg`".to_string().into())
+}
+
+#[cfg(feature = "tui")]
+const WORKBENCH_HELP: &str = "XY guesses cipher X is plain Y (several at once: XY QE), -X drops the guess for X,
+auto guesses by letter frequency, reset drops every guess, quit (or the end of stdin) stops";
+
+// A substitution workbench driven a line at a time from stdin: the
+// ciphertext, its letter frequencies, the guesses so far and the text as
+// they make it read, redrawn after every command.
+#[cfg(feature = "tui")]
+fn tui(args: &mut Vec<String>) -> Result<(), Failure> {
+    use cryptan::analysis::{letter_frequencies, Workbench};
+
+    let mut workbench = Workbench::new(take_text(args)?);
+    let frequencies = letter_frequencies(workbench.ciphertext());
+    let top = frequencies.iter().map(|(_, frequency)| *frequency).fold(0.0, f64::max);
+    let (bold, cyan, reset) = if colour() { ("\x1b[1m", "\x1b[36m", "\x1b[0m") } else { ("", "", "") };
+    let mut message = String::new();
+    let mut lines = io::stdin().lines();
+    let mut drawn = false;
+
+    loop {
+        let mut screen = String::new();
+        if output_is_terminal() {
+            screen += "\x1b[2J\x1b[H";
+        } else if drawn {
+            screen += "\n";
+        }
+        drawn = true;
+        screen += &format!("{}CIPHERTEXT{}\n  {}\n", bold, reset, workbench.ciphertext());
+        screen += &format!("{}LETTER FREQUENCIES{}\n", bold, reset);
+        let mut sorted: Vec<&(char, f64)> = frequencies.iter().filter(|(_, frequency)| *frequency > 0.0).collect();
+        sorted.sort_by(|a, b| b.1.total_cmp(&a.1));
+        for (letter, frequency) in sorted {
+            let bar = "#".repeat((frequency / top * 30.0).round() as usize);
+            let guess = workbench.plain_for(*letter).map_or(String::new(), |plain| format!(" -> {}", plain));
+            screen += &format!("  {} {:5.1}% {}{:<30}{}{}\n", letter, frequency * 100.0, cyan, bar, reset, guess);
+        }
+        let guesses: String = ('A'..='Z').map(|c| workbench.plain_for(c).unwrap_or('.')).collect();
+        screen += &format!("{}GUESSES{}\n  cipher ABCDEFGHIJKLMNOPQRSTUVWXYZ\n  plain  {}\n", bold, reset, guesses);
+        screen += &format!("  key    {}\n", workbench.key());
+        screen += &format!("{}PLAINTEXT{}\n  {}\n", bold, reset, workbench.preview('_'));
+        if !message.is_empty() {
+            screen += &format!("{}\n", std::mem::take(&mut message));
+        }
+        screen += "> ";
+        let mut sink = output();
+        write!(sink, "{}", screen).and_then(|_| sink.flush()).map_err(output_failure)?;
+        drop(sink);
+
+        let Some(line) = lines.next() else {
+            out!();
+            return Ok(());
+        };
+        let line = line.map_err(|e| format!("cannot read stdin: {}", e))?;
+        for command in line.split_whitespace() {
+            let letters: Vec<char> = command.chars().filter(|&c| c != '=').collect();
+            let result = match (command.to_ascii_lowercase().as_str(), letters.as_slice()) {
+                ("quit" | "q" | "exit", _) => return Ok(()),
+                ("auto", _) => {
+                    workbench.guess_by_frequency();
+                    Ok(())
+                }
+                ("reset", _) => {
+                    workbench.reset();
+                    Ok(())
+                }
+                ("help" | "?", _) => {
+                    message = WORKBENCH_HELP.to_string();
+                    Ok(())
+                }
+                (_, &['-', cipher]) => workbench.clear(cipher),
+                (_, &[cipher, plain]) => workbench.set(cipher, plain),
+                _ => {
+                    message = format!("unknown command `{}`; {}", command, WORKBENCH_HELP);
+                    Ok(())
+                }
+            };
+            if let Err(e) = result {
+                message = e.to_string();
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "tui"))]
+fn tui(_args: &mut Vec<String>) -> Result<(), Failure> {
+    Err("this build has no workbench; rebuild with `--features tui`".into())
+}
+
+fn serve(args: &mut Vec<String>) -> Result<(), Failure> {
+    let addr = take_option(args, "--addr")?;
+    start_server(addr)
+}
+
+#[cfg(feature = "serve")]
+fn start_server(addr: Option<String>) -> Result<(), Failure> {
+    let addr = addr.unwrap_or_else(|| cryptan::server::DEFAULT_ADDR.to_string());
+
+    eprintln!("listening on http://{}", addr);
+    let diagnostics = Diagnostics::new().with_callback(|warning| eprintln!("warning: {}", warning));
+    cryptan::server::run(&addr, Some(&diagnostics)).map_err(|e| format!("server error: {}", e).into())
+}
+
+#[cfg(not(feature = "serve"))]
+fn start_server(_addr: Option<String>) -> Result<(), Failure> {
+    Err("this build has no server; rebuild with `--features serve`".to_string().into())
+}
+
+// A command as USAGE lists it, which is where `completions` and
+// `--generate-man` read the CLI from so they can't drift apart from it.
+struct CommandDoc {
+    name: String,
+    subcommands: Vec<String>,
+    options: Vec<String>,
+}
+
+fn usage_section(heading: &str) -> &'static str {
+    let start = USAGE.find(heading).map_or(USAGE.len(), |i| i + heading.len());
+    let section = &USAGE[start..];
+    section.find("\n\n").map_or(section, |end| &section[..end]).trim_start_matches('\n')
+}
+
+// Every `--option` mentioned in a line.
+fn options_in(line: &str) -> impl Iterator<Item = String> + '_ {
+    line.split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .filter(|word| word.len() > 2 && word.starts_with("--"))
+        .map(str::to_string)
+}
+
+fn usage_commands() -> Vec<CommandDoc> {
+    fn add(list: &mut Vec<String>, item: &str) {
+        if !list.iter().any(|known| known == item) {
+            list.push(item.to_string());
+        }
+    }
+
+    let mut commands: Vec<CommandDoc> = Vec::new();
+    let mut current = None;
+    for line in usage_section("commands:\n").lines() {
+        // Command lines are indented four spaces; deeper ones continue the
+        // command above. A command listed more than once, like `encode`,
+        // gets one entry.
+        if line.starts_with("    ") && !line[4..].starts_with(' ') {
+            let mut words = line.split_whitespace();
+            let name = words.next().unwrap_or_default();
+            let index = commands.iter().position(|command| command.name == name).unwrap_or_else(|| {
+                commands.push(CommandDoc { name: name.to_string(), subcommands: Vec::new(), options: Vec::new() });
+                commands.len() - 1
+            });
+            if let Some(subcommands) = words.next().filter(|word| word.starts_with(|c: char| c.is_ascii_lowercase())) {
+                subcommands.split('|').for_each(|subcommand| add(&mut commands[index].subcommands, subcommand));
+            }
+            current = Some(index);
+        }
+        if let Some(command) = current.map(|index| &mut commands[index]) {
+            for option in options_in(line).filter(|option| *option != command.name) {
+                add(&mut command.options, &option);
+            }
+        }
+    }
+    commands
+}
+
+fn global_options() -> Vec<String> {
+    usage_section("options for every command:\n")
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|word| word.starts_with("--"))
+        .map(str::to_string)
+        .collect()
+}
+
+fn completions(args: &mut Vec<String>) -> Result<(), Failure> {
+    let shell = take_word(args, "shell (bash, zsh or fish)")?;
+    let commands = usage_commands();
+    let globals = global_options().join(" ");
+    let names: Vec<&str> = commands.iter().map(|command| command.name.as_str()).collect();
+    let words = |command: &CommandDoc| [command.subcommands.clone(), command.options.clone()].concat().join(" ");
+
+    let script = match shell.as_str() {
+        "bash" => {
+            let mut script = String::from("# bash completion for cryptan\n_cryptan() {\n");
+            script += "    local cur=${COMP_WORDS[COMP_CWORD]} words\n";
+            script += "    if [ \"$COMP_CWORD\" -eq 1 ]; then\n";
+            script += &format!("        words=\"{}\"\n    else\n", names.join(" "));
+            script += "        case \"${COMP_WORDS[1]}\" in\n";
+            for command in &commands {
+                script += &format!("            {}) words=\"{}\" ;;\n", command.name, words(command));
+            }
+            script += &format!("        esac\n        words=\"$words {}\"\n    fi\n", globals);
+            script += "    COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))\n}\n";
+            script + "complete -o default -F _cryptan cryptan\n"
+        }
+        "zsh" => {
+            let mut script = String::from("#compdef cryptan\n\n_cryptan() {\n    local -a candidates\n");
+            script += &format!("    if (( CURRENT == 2 )); then\n        candidates=({})\n    else\n", names.join(" "));
+            script += "        case ${words[2]} in\n";
+            for command in &commands {
+                script += &format!("            {}) candidates=({}) ;;\n", command.name, words(command));
+            }
+            script += &format!("        esac\n        candidates+=({})\n    fi\n", globals);
+            script + "    compadd -a candidates\n    _files\n}\n\n_cryptan \"$@\"\n"
+        }
+        "fish" => {
+            let mut script = String::from("# fish completion for cryptan\n");
+            script += &format!("complete -c cryptan -n __fish_use_subcommand -a \"{}\"\n", names.join(" "));
+            for command in &commands {
+                let seen = format!("complete -c cryptan -n \"__fish_seen_subcommand_from {}\"", command.name);
+                if !command.subcommands.is_empty() {
+                    script += &format!("{} -a \"{}\"\n", seen, command.subcommands.join(" "));
+                }
+                for option in &command.options {
+                    script += &format!("{} -l {}\n", seen, &option[2..]);
+                }
+            }
+            for option in global_options() {
+                script += &format!("complete -c cryptan -l {}\n", &option[2..]);
+            }
+            script
+        }
+        _ => return Err(format!("unknown shell `{}`, expected bash, zsh or fish", shell).into()),
+    };
+    write!(output(), "{}", script).map_err(output_failure)
+}
+
+// roff for man(1).
+fn man_page() -> String {
+    let escape = |text: &str| {
+        text.lines()
+            .map(|line| {
+                let line = line.replace('\\', "\\e").replace('-', "\\-");
+                if line.starts_with(['.', '\'']) {
+                    format!("\\&{}", line)
+                } else {
+                    line
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
 
-    //Set plain text
-    ceasar.set_plain(plain);
+    let mut page = format!(".TH CRYPTAN 1 \"\" \"cryptan {}\" \"User Commands\"\n", env!("CARGO_PKG_VERSION"));
+    page += ".SH NAME\ncryptan \\- classical ciphers, encodings and tools to break them\n";
+    page += ".SH SYNOPSIS\n.B cryptan\n\\fIcommand\\fR [\\fIoptions\\fR]\n";
+    page += &format!(".SH COMMANDS\n.nf\n{}\n.fi\n", escape(usage_section("commands:\n")));
+    page += &format!(".SH OPTIONS\n.nf\n{}\n.fi\n", escape(usage_section("options for every command:\n")));
+    page += &format!(".SH EXIT STATUS\n.nf\n{}\n.fi\n", escape(usage_section("exit status:\n")));
+    page += ".SH ENVIRONMENT\n.TP\n.B NO_COLOR\nWhen set and not empty, output is never coloured.\n";
+    page += ".TP\n.B CRYPTAN_DATA_DIR\nWhere user word lists are kept, instead of ";
+    page += "\\fI$XDG_DATA_HOME/cryptan\\fR or \\fI~/.local/share/cryptan\\fR.\n";
+    page
+}
 
-    //encode and save to object
-    print!("Encoded: {}\n", ceasar.encode(key));
-    ceasar.set_encoded_text(ceasar.encode(key));
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    //decode (result can be save to object too)
-    print!("Decode: {}\n", ceasar.decode(key));
+    #[test]
+    fn output_failures_pick_their_exit_status() {
+        let closed = output_failure(io::Error::from(io::ErrorKind::BrokenPipe));
+        assert!(matches!(closed, Failure::Closed));
+        assert_eq!(closed.exit_code(), 0);
+        let full = output_failure(io::Error::from(io::ErrorKind::WriteZero));
+        assert_eq!((full.exit_code(), full.kind()), (1, "output"));
+    }
 
-    //Brute force
-    print!("\nBrute force result:\n");
-    ceasar.brute_force();
-}
\ No newline at end of file
+    #[test]
+    fn codec_flags_are_refused_with_ciphers() {
+        for flag in ["--strict", "--lenient"] {
+            let mut args: Vec<String> = ["--codec", "caesar:3", flag, "khoor"].map(String::from).to_vec();
+            assert!(matches!(code(false, &mut args), Err(Failure::Usage(_))), "{}", flag);
+        }
+        let mut args: Vec<String> = ["--recipe", "caesar:3", "--lang", "russian", "hi"].map(String::from).to_vec();
+        assert!(matches!(code(true, &mut args), Err(Failure::Usage(_))));
+    }
+}
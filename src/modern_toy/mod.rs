@@ -0,0 +1,5 @@
+pub mod one_time_pad;
+pub mod xor;
+
+pub use one_time_pad::{OneTimePad, PadMode};
+pub use xor::{OutputEncoding, XorCipher};
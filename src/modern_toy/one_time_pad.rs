@@ -0,0 +1,218 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::encoding::base_n::{hex_decode, hex_encode};
+use crate::error::{Error, Result};
+use crate::random;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PadMode {
+    // Letters added to key letters mod 26, as done by hand. Other
+    // characters pass through and use no key.
+    #[default]
+    Letters,
+    // Bytes XORed with key bytes; ciphertext is hex.
+    Xor,
+}
+
+impl PadMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PadMode::Letters => "letters",
+            PadMode::Xor => "xor",
+        }
+    }
+}
+
+impl FromStr for PadMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "letters" | "mod26" => Ok(PadMode::Letters),
+            "xor" | "bytes" => Ok(PadMode::Xor),
+            _ => Err(format!("unknown pad mode `{}`, expected letters or xor", s)),
+        }
+    }
+}
+
+// A pad of key material that is used up as it encrypts: every letter or
+// byte takes the next unused piece of key, and encrypting more than is
+// left fails rather than wrapping around. Sender and receiver each hold a
+// copy of the pad and use it in step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OneTimePad {
+    mode: PadMode,
+    // Shifts 0-25 in `Letters` mode, bytes in `Xor` mode.
+    key: Vec<u8>,
+    used: usize,
+}
+
+impl OneTimePad {
+    // `key` is letters in `Letters` mode and hex in `Xor` mode.
+    pub fn from_key(mode: PadMode, key: &str) -> Result<Self> {
+        let key = match mode {
+            PadMode::Letters => key
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| match c.is_ascii_alphabetic() {
+                    true => Ok(c.to_ascii_uppercase() as u8 - b'A'),
+                    false => Err(Error::InvalidKey(format!("pad has {:?}; use letters A-Z only", c))),
+                })
+                .collect::<Result<Vec<u8>>>()?,
+            PadMode::Xor => hex_decode(key).ok_or_else(|| Error::InvalidKey("the pad is not valid hex".to_string()))?,
+        };
+        if key.is_empty() {
+            return Err(Error::InvalidKey("the pad is empty".to_string()));
+        }
+        Ok(Self { mode, key, used: 0 })
+    }
+
+    // A fresh pad of `len` letters or bytes from the system's random
+    // source. Fine for demonstrations; real pads need a trusted source and
+    // a secure way to share them.
+    pub fn generate(mode: PadMode, len: usize) -> io::Result<Self> {
+        let key = match mode {
+            PadMode::Letters => random::os_below(26, len)?,
+            PadMode::Xor => random::os_bytes(len)?,
+        };
+        Ok(Self { mode, key, used: 0 })
+    }
+
+    // Pad files hold the mode on the first line and the unused key on the
+    // second.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let mut lines = text.lines();
+        let mode: PadMode = lines.next().unwrap_or_default().trim().parse().map_err(Error::InvalidKey)?;
+        Self::from_key(mode, &lines.collect::<String>())
+    }
+
+    // Only the unused part is written, so a saved pad can't be used twice.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        fs::write(path, format!("{}\n{}\n", self.mode.name(), self.remaining_key()))?;
+        Ok(())
+    }
+
+    pub fn mode(&self) -> PadMode {
+        self.mode
+    }
+
+    // Letters or bytes of key not used yet.
+    pub fn remaining(&self) -> usize {
+        self.key.len() - self.used
+    }
+
+    // The unused key, written as `from_key` reads it.
+    pub fn remaining_key(&self) -> String {
+        let rest = &self.key[self.used..];
+        match self.mode {
+            PadMode::Letters => rest.iter().map(|k| (b'A' + k) as char).collect(),
+            PadMode::Xor => hex_encode(rest),
+        }
+    }
+
+    // Hands out the next `len` pieces of key, or fails without using any.
+    fn take(&mut self, len: usize) -> Result<&[u8]> {
+        if len > self.remaining() {
+            return Err(Error::InvalidKey(format!("the pad has {} left but {} are needed", self.remaining(), len)));
+        }
+        self.used += len;
+        Ok(&self.key[self.used - len..self.used])
+    }
+
+    pub fn encrypt(&mut self, plain: &str) -> Result<String> {
+        match self.mode {
+            PadMode::Letters => self.shift_letters(plain, 1),
+            PadMode::Xor => {
+                let key = self.take(plain.len())?;
+                Ok(hex_encode(&xor(plain.as_bytes(), key)))
+            }
+        }
+    }
+
+    // Invalid UTF-8 in a decrypted `Xor` message is replaced.
+    pub fn decrypt(&mut self, cipher: &str) -> Result<String> {
+        match self.mode {
+            PadMode::Letters => self.shift_letters(cipher, -1),
+            PadMode::Xor => {
+                let data = hex_decode(cipher)
+                    .ok_or_else(|| Error::Unsupported("XOR pad ciphertext must be hex".to_string()))?;
+                let key = self.take(data.len())?;
+                Ok(String::from_utf8_lossy(&xor(&data, key)).into_owned())
+            }
+        }
+    }
+
+    fn shift_letters(&mut self, text: &str, direction: i32) -> Result<String> {
+        let letters = text.chars().filter(char::is_ascii_alphabetic).count();
+        let mut key = self.take(letters)?.iter();
+
+        Ok(text
+            .chars()
+            .map(|c| {
+                let base = match c {
+                    'A'..='Z' => b'A',
+                    'a'..='z' => b'a',
+                    _ => return c,
+                };
+                let shift = *key.next().unwrap_or(&0) as i32 * direction;
+                (base + ((c as u8 - base) as i32 + shift).rem_euclid(26) as u8) as char
+            })
+            .collect())
+    }
+}
+
+fn xor(data: &[u8], key: &[u8]) -> Vec<u8> {
+    data.iter().zip(key).map(|(b, k)| b ^ k).collect()
+}
+
+// Why a pad must never be reused: XORing two ciphertexts made with the
+// same `Xor` pad cancels the key and leaves the two plaintexts XORed
+// together. Both are hex; the result is as long as the shorter one.
+pub fn two_time_pad(first: &str, second: &str) -> Option<Vec<u8>> {
+    Some(xor(&hex_decode(first)?, &hex_decode(second)?))
+}
+
+// Crib dragging on a reused pad: tries `crib` as part of one message at
+// every offset and returns what the other message must say there, where
+// that comes out as printable text.
+pub fn crib_drag(first: &str, second: &str, crib: &str) -> Vec<(usize, String)> {
+    let Some(combined) = two_time_pad(first, second) else {
+        return Vec::new();
+    };
+    let crib = crib.as_bytes();
+    if crib.is_empty() || crib.len() > combined.len() {
+        return Vec::new();
+    }
+
+    (0..=combined.len() - crib.len())
+        .filter_map(|offset| {
+            let revealed = xor(&combined[offset..offset + crib.len()], crib);
+            let printable = revealed.iter().all(|b| b.is_ascii_graphic() || *b == b' ');
+            printable.then(|| (offset, String::from_utf8_lossy(&revealed).into_owned()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_the_key_letters() {
+        let mut pad = OneTimePad::from_key(PadMode::Letters, "XMCKL").unwrap();
+        assert_eq!(pad.encrypt("HELLO").unwrap(), "EQNVZ");
+        let mut pad = OneTimePad::from_key(PadMode::Letters, "XMCKL").unwrap();
+        assert_eq!(pad.decrypt("EQNVZ").unwrap(), "HELLO");
+    }
+
+    #[test]
+    fn refuses_to_reuse_key() {
+        let mut pad = OneTimePad::from_key(PadMode::Xor, "0102").unwrap();
+        assert_eq!(pad.encrypt("ab").unwrap(), "6060");
+        assert!(pad.encrypt("c").is_err());
+    }
+}
@@ -0,0 +1,154 @@
+use std::str::FromStr;
+
+use crate::analysis::english_byte_score;
+use crate::budget::Budget;
+use crate::classical::ClassicalCipher;
+use crate::encoding::base_n::{base64_decode, base64_encode, hex_decode, hex_encode};
+use crate::error::{self, Error};
+
+// How ciphertext bytes are written out as text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+impl OutputEncoding {
+    pub fn encode(&self, data: &[u8]) -> String {
+        match self {
+            OutputEncoding::Hex => hex_encode(data),
+            OutputEncoding::Base64 => base64_encode(data),
+        }
+    }
+
+    pub fn decode(&self, text: &str) -> Option<Vec<u8>> {
+        match self {
+            OutputEncoding::Hex => hex_decode(text),
+            OutputEncoding::Base64 => base64_decode(text),
+        }
+    }
+}
+
+impl FromStr for OutputEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "hex" => Ok(OutputEncoding::Hex),
+            "base64" => Ok(OutputEncoding::Base64),
+            _ => Err(format!("unknown output encoding `{}`, expected hex or base64", s)),
+        }
+    }
+}
+
+// XORs every byte with a repeating key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XorCipher {
+    key: Vec<u8>,
+    encoding: OutputEncoding,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SingleByteXor {
+    pub key: u8,
+    pub plaintext: Vec<u8>,
+    pub score: f64,
+}
+
+impl XorCipher {
+    pub fn new(key: &[u8]) -> Option<Self> {
+        Self::try_new(key).ok()
+    }
+
+    pub fn try_new(key: &[u8]) -> error::Result<Self> {
+        if key.is_empty() {
+            return Err(Error::InvalidKey("the key is empty".to_string()));
+        }
+
+        Ok(Self { key: key.to_vec(), encoding: OutputEncoding::default() })
+    }
+
+    pub fn with_encoding(mut self, encoding: OutputEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    // XOR is its own inverse, so this both encrypts and decrypts.
+    pub fn apply(&self, data: &[u8]) -> Vec<u8> {
+        data.iter()
+            .zip(self.key.iter().cycle())
+            .map(|(b, k)| b ^ k)
+            .collect()
+    }
+
+    pub fn try_decrypt(&self, cipher: &str) -> Option<Vec<u8>> {
+        Some(self.apply(&self.encoding.decode(cipher)?))
+    }
+}
+
+impl ClassicalCipher for XorCipher {
+    fn encrypt(&self, plain: &str) -> String {
+        self.encoding.encode(&self.apply(plain.as_bytes()))
+    }
+
+    // Invalid hex/base64 decrypts to an empty string; invalid UTF-8 in the
+    // result is replaced.
+    fn decrypt(&self, cipher: &str) -> String {
+        let plain = self.try_decrypt(cipher).unwrap_or_default();
+        String::from_utf8_lossy(&plain).into_owned()
+    }
+}
+
+// Tries all 256 single-byte keys, best English letter-frequency score first.
+pub fn single_byte_brute_force(data: &[u8]) -> Vec<SingleByteXor> {
+    single_byte_brute_force_with_budget(data, &Budget::unlimited())
+}
+
+// Like `single_byte_brute_force`, one unit of `budget` per key.
+pub fn single_byte_brute_force_with_budget(data: &[u8], budget: &Budget) -> Vec<SingleByteXor> {
+    budget.set_total(256);
+    let mut candidates: Vec<SingleByteXor> = (0..=255u8)
+        .map_while(|key| {
+            if !budget.spend() {
+                return None;
+            }
+            let plaintext: Vec<u8> = data.iter().map(|b| b ^ key).collect();
+            let score = english_byte_score(&plaintext);
+            budget.record_score(score);
+            Some(SingleByteXor { key, score, plaintext })
+        })
+        .collect();
+
+    budget.report();
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // From the Cryptopals repeating-key XOR exercise.
+    #[test]
+    fn repeats_the_key_over_the_plaintext() {
+        let xor = XorCipher::new(b"ICE").unwrap();
+        let plain = "Burning 'em, if you ain't quick and nimble\nI go crazy when I hear a cymbal";
+        let cipher = concat!(
+            "0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63343c2a26226324272765272a282b2f20",
+            "430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f",
+        );
+        assert_eq!(xor.encrypt(plain), cipher);
+        assert_eq!(xor.decrypt(cipher), plain);
+    }
+
+    #[test]
+    fn single_byte_brute_force_finds_the_key() {
+        let data = XorCipher::new(b"X").unwrap().apply(b"Cooking MC's like a pound of bacon");
+        assert_eq!(single_byte_brute_force(&data)[0].key, b'X');
+    }
+}
@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+
+use crate::analysis::{NgramScorer, Scorer};
+use crate::budget::Budget;
+use crate::classical::{
+    affine, caesar, AffineCipher, AtbashCipher, AutokeyCipher, BeaufortCipher, BifidCipher, CaesarCipher,
+    ClassicalCipher, ColumnarTransposition, GronsfeldCipher, PlayfairCipher, PortaCipher, RouteCipher,
+    SubstitutionCipher, TrifidCipher, TurningGrille, VigenereCipher, VigenereSolver,
+};
+use crate::encoding::{
+    Ascii85, Base32, Base58, Base64, BinaryAscii, Braille, Codec, DecimalAscii, FlagSemaphore, Hex, HtmlEntities,
+    Morse, MorseLang, Ogham, Pigpen, Rot13, Rot47, SemaphoreStyle, UrlEncoding, Z85,
+};
+use crate::error::Result;
+use crate::magic::{self, Magic};
+use crate::registry::{Registry, Transform};
+
+mod recipe;
+
+pub const DEFAULT_MAX_DEPTH: usize = 4;
+
+// Shortest text worth running the keyword cipher solvers on.
+const MIN_SOLVER_LETTERS: usize = 8;
+const MIN_VIGENERE_LETTERS: usize = 40;
+// Letters per key letter the Vigenère solver needs before its columns stop
+// fitting noise.
+const LETTERS_PER_VIGENERE_KEY_LETTER: usize = 6;
+
+// One reversible step of a pipeline. The name is how the step is written
+// in a recipe: a codec name, or a cipher and its key (`caesar:3`).
+pub struct Step {
+    name: String,
+    transform: Transform,
+}
+
+impl Step {
+    pub fn codec(name: impl Into<String>, codec: Box<dyn Codec>) -> Self {
+        Self { name: name.into(), transform: Transform::Codec(codec) }
+    }
+
+    pub fn cipher(name: impl Into<String>, cipher: Box<dyn ClassicalCipher>) -> Self {
+        Self { name: name.into(), transform: Transform::Cipher(cipher) }
+    }
+
+    // Parses one recipe step: a codec name (`base64`, `morse:russian`) or
+    // a cipher and its key (`caesar:3`, `vigenere:LEMON`, `affine:5,8`),
+    // anything `Registry::shared` knows.
+    pub fn from_recipe(recipe: &str) -> Result<Self> {
+        let recipe = recipe.trim();
+        Ok(Self { name: recipe.to_string(), transform: Registry::shared().create(recipe)? })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Encodes or encrypts.
+    pub fn forward(&self, text: &str) -> String {
+        self.transform.forward(text)
+    }
+
+    // Decodes or decrypts.
+    pub fn backward(&self, text: &str) -> String {
+        self.transform.backward(text)
+    }
+
+    fn is_cipher(&self) -> bool {
+        self.transform.is_cipher()
+    }
+}
+
+// Codecs and ciphers whose settings fit in a recipe step convert straight
+// into one; anything else goes through `Step::codec` or `Step::cipher`.
+macro_rules! into_step {
+    ($make:ident: $($ty:ty => |$value:ident| $name:expr),* $(,)?) => {
+        $(impl From<$ty> for Step {
+            fn from($value: $ty) -> Self {
+                let name = $name;
+                Step::$make(name, Box::new($value))
+            }
+        })*
+    };
+}
+
+into_step!(codec:
+    Base64 => |codec| if codec.is_url_safe() { "base64url" } else { "base64" },
+    Base32 => |_codec| "base32",
+    Base58 => |_codec| "base58",
+    Ascii85 => |_codec| "ascii85",
+    Z85 => |_codec| "z85",
+    Hex => |_codec| "hex",
+    BinaryAscii => |_codec| "binary",
+    DecimalAscii => |_codec| "decimal",
+    Rot13 => |_codec| "rot13",
+    Rot47 => |_codec| "rot47",
+    Ogham => |_codec| "ogham",
+    UrlEncoding => |codec| if codec.is_form() { "url-form" } else { "url" },
+    HtmlEntities => |codec| if codec.is_numeric() { "html-numeric" } else { "html" },
+    Braille => |_codec| "braille",
+    Pigpen => |_codec| "pigpen",
+    FlagSemaphore => |codec| match codec.style() {
+        SemaphoreStyle::Names => "semaphore",
+        SemaphoreStyle::Arrows => "semaphore-arrows",
+    },
+    Morse => |codec| match codec.lang() {
+        MorseLang::International => "morse".to_string(),
+        lang => format!("morse:{}", lang.name()),
+    },
+);
+
+into_step!(cipher:
+    CaesarCipher => |cipher| match (cipher.key(), cipher.rotates_digits()) {
+        (13, true) => "rot18".to_string(),
+        (key, true) => format!("caesar:{},rot5", key),
+        (key, false) => format!("caesar:{}", key),
+    },
+    VigenereCipher => |cipher| format!("vigenere:{}", cipher.keyword()),
+    BeaufortCipher => |cipher| format!("beaufort:{}", cipher.keyword()),
+    AutokeyCipher => |cipher| format!("autokey:{}", cipher.keyword()),
+    PortaCipher => |cipher| format!("porta:{}", cipher.keyword()),
+    GronsfeldCipher => |cipher| format!("gronsfeld:{}", cipher.key()),
+    AffineCipher => |cipher| format!("affine:{},{}", cipher.a(), cipher.b()),
+    AtbashCipher => |_cipher| "atbash",
+    // The square read row by row is a keyword that rebuilds it.
+    PlayfairCipher => |cipher| format!("playfair:{}", cipher.square().concat()),
+    BifidCipher => |cipher| format!("bifid:{},{}", cipher.square().rows().concat(), cipher.period()),
+    TrifidCipher => |cipher| format!("trifid:{},{}", cipher.cube().rows().concat(), cipher.period()),
+    SubstitutionCipher => |cipher| format!("substitution:{}", cipher.alphabet()),
+    ColumnarTransposition => |cipher| {
+        let order: Vec<String> = cipher.order().iter().map(usize::to_string).collect();
+        format!("columnar:{}", order.join(","))
+    },
+    RouteCipher => |cipher| format!("route:{},{},{}", cipher.rows(), cipher.columns(), cipher.route()),
+    TurningGrille => |cipher| format!("grille:{}", cipher.mask()),
+);
+
+// Steps run in order going forward and are undone in reverse order going
+// backward, so `backward(forward(x)) == x` for lossless steps. Written as
+// a recipe, steps are separated by `|` or new lines:
+// `caesar:3 | base64`.
+#[derive(Default)]
+pub struct Pipeline {
+    steps: Vec<Step>,
+}
+
+pub struct Solution {
+    // Running `backward` on the input gives `text`.
+    pub pipeline: Pipeline,
+    pub text: String,
+    pub score: f64,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn then(mut self, step: impl Into<Step>) -> Self {
+        self.steps.push(step.into());
+        self
+    }
+
+    // Blank lines and `#` comments are skipped.
+    pub fn from_recipe(recipe: &str) -> Result<Self> {
+        recipe
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or(""))
+            .flat_map(|line| line.split('|'))
+            .filter(|step| !step.trim().is_empty())
+            .try_fold(Pipeline::new(), |pipeline, step| Ok(pipeline.then(Step::from_recipe(step)?)))
+    }
+
+    pub fn to_recipe(&self) -> String {
+        self.steps.iter().map(Step::name).collect::<Vec<_>>().join(" | ")
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn forward(&self, text: &str) -> String {
+        self.steps.iter().fold(text.to_string(), |text, step| step.forward(&text))
+    }
+
+    pub fn backward(&self, text: &str) -> String {
+        self.steps.iter().rev().fold(text.to_string(), |text, step| step.backward(&text))
+    }
+
+    // Like CyberChef's Magic, but cipher brute-forcers take part too: tries
+    // every codec that fits the text and the Caesar, affine, Atbash and
+    // Vigenère solvers, recursing into each result, and keeps the most
+    // English-looking text found. None if nothing beat the input.
+    pub fn auto_solve(input: &str) -> Option<Solution> {
+        Self::auto_solve_with_budget(input, DEFAULT_MAX_DEPTH, &Budget::unlimited())
+    }
+
+    // Each step tried costs one unit of the budget.
+    pub fn auto_solve_with_budget(input: &str, max_depth: usize, budget: &Budget) -> Option<Solution> {
+        let mut search = Search {
+            magic: Magic::new(),
+            max_depth,
+            budget,
+            seen: HashMap::from([(input.to_string(), 0)]),
+            chain: Vec::new(),
+            best: None,
+        };
+        let input_score = score(input);
+        search.expand(input, 0, false);
+        budget.report();
+
+        let (steps, text, best_score) = search.best?;
+        if best_score <= input_score {
+            return None;
+        }
+
+        // Found in decoding order; the pipeline runs the other way.
+        let pipeline = steps.iter().rev().filter_map(|name| Step::from_recipe(name).ok()).fold(Pipeline::new(), Pipeline::then);
+        Some(Solution { pipeline, text, score: best_score })
+    }
+}
+
+// A whole pipeline as one cipher, for recipes registered under a name.
+impl ClassicalCipher for Pipeline {
+    fn encrypt(&self, plain: &str) -> String {
+        self.forward(plain)
+    }
+
+    fn decrypt(&self, cipher: &str) -> String {
+        self.backward(cipher)
+    }
+}
+
+impl Solution {
+    // Step names in the order they were undone, starting from the input.
+    pub fn chain(&self) -> Vec<&str> {
+        self.pipeline.steps().iter().rev().map(Step::name).collect()
+    }
+}
+
+struct Search<'a> {
+    magic: Magic,
+    max_depth: usize,
+    budget: &'a Budget,
+    // Every text reached so far and the shallowest depth it was reached at,
+    // so each is expanded once however many chains lead to it.
+    seen: HashMap<String, usize>,
+    // Names of the steps undone so far.
+    chain: Vec<String>,
+    best: Option<(Vec<String>, String, f64)>,
+}
+
+impl Search<'_> {
+    fn expand(&mut self, text: &str, depth: usize, after_cipher: bool) {
+        if depth >= self.max_depth {
+            return;
+        }
+
+        for step in self.candidates(text, after_cipher).iter().filter_map(|name| Step::from_recipe(name).ok()) {
+            if !self.budget.spend() {
+                return;
+            }
+
+            let decoded = step.backward(text);
+            let reached_sooner = self.seen.get(&decoded).is_some_and(|seen_at| *seen_at <= depth + 1);
+            if decoded.trim().is_empty() || reached_sooner {
+                continue;
+            }
+
+            let score = score(&decoded);
+            self.budget.record_score(score);
+            self.chain.push(step.name().to_string());
+            // Ties go to the shorter chain.
+            let better = self.best.as_ref().is_none_or(|(chain, _, best)| {
+                score > *best || (score == *best && self.chain.len() < chain.len())
+            });
+            if better {
+                self.best = Some((self.chain.clone(), decoded.clone(), score));
+            }
+
+            self.seen.insert(decoded.clone(), depth + 1);
+            self.expand(&decoded, depth + 1, step.is_cipher());
+            self.chain.pop();
+        }
+    }
+
+    // Names of the steps worth undoing on `text`: every codec whose input
+    // it looks like, and the best key each cipher solver finds, the ones
+    // installed into the registry included. A cipher straight after
+    // another cipher only finds noise, so those are skipped, and so are
+    // letters that belong to a codec's alphabet: the ciphers leave digits
+    // and symbols alone, so text that has them and fits a codec other than
+    // ROT13 is that codec's output, not ciphertext.
+    fn candidates(&self, text: &str, after_cipher: bool) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .magic
+            .layers()
+            .iter()
+            .filter(|layer| (layer.detect)(text))
+            .map(|layer| layer.name.to_string())
+            .collect();
+        // Codecs installed into the registry.
+        let registry = Registry::shared();
+        let installed = registry.entries().iter().filter(|entry| entry.detects(text));
+        names.extend(installed.map(|entry| entry.name().to_string()));
+
+        let letters = text.chars().filter(|c| c.is_ascii_alphabetic()).count();
+        let symbols = text.chars().any(|c| c.is_ascii_digit() || !c.is_ascii());
+        let encoded = symbols && names.iter().any(|name| name != "rot13");
+        if after_cipher || encoded || letters < MIN_SOLVER_LETTERS {
+            return names;
+        }
+
+        if let Some(best) = caesar::brute_force(text).first() {
+            names.push(format!("caesar:{}", best.key));
+        }
+        if let Some(best) = affine::brute_force(text).first() {
+            // Keys come back as `a=5,b=8`.
+            names.push(format!("affine:{}", best.key.replace("a=", "").replace("b=", "")));
+        }
+        names.push("atbash".to_string());
+        if letters >= MIN_VIGENERE_LETTERS {
+            let solver = VigenereSolver::new().with_max_key_length(letters / LETTERS_PER_VIGENERE_KEY_LETTER);
+            if let Some(best) = solver.solve(text).first() {
+                names.push(format!("vigenere:{}", best.key));
+            }
+        }
+        names.extend(registry.entries().iter().filter_map(|entry| entry.solve(text)));
+
+        names
+    }
+}
+
+// Half `magic::score`, half quadgram fitness, so plaintext that lost its
+// spaces still beats letter soup.
+pub fn score(text: &str) -> f64 {
+    let letters = text.chars().filter(|c| c.is_ascii_alphabetic()).count();
+    if letters < 4 {
+        return magic::score(text);
+    }
+
+    (magic::score(text) + f64::from(NgramScorer.score(text))) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layered_encodings_solve_to_the_plaintext() {
+        let input = Hex::new().encode(&Morse::new().encode("attack at dawn"));
+        let solution = Pipeline::auto_solve(&input).unwrap();
+        assert_eq!(solution.text, "ATTACK AT DAWN");
+        assert_eq!(solution.chain(), ["hex", "morse"]);
+    }
+}
@@ -0,0 +1,285 @@
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::json::Json;
+use crate::pipeline::{Pipeline, Step};
+
+// Recipe files list the steps in order, each as its name and an optional
+// key. As JSON:
+//
+//     {"steps": [{"step": "caesar", "key": 3}, {"step": "base64"}]}
+//
+// and as YAML, where a step may also be written inline as `caesar:3`:
+//
+//     steps:
+//       - step: caesar
+//         key: 3
+//       - base64
+//
+// Any other extension is read as a plain recipe (`caesar:3 | base64`).
+impl Pipeline {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+
+        match extension(path).as_str() {
+            "json" => Self::from_json(&text),
+            "yaml" | "yml" => Self::from_yaml(&text),
+            _ => Self::from_recipe(&text),
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let text = match extension(path).as_str() {
+            "json" => self.to_json(),
+            "yaml" | "yml" => self.to_yaml(),
+            _ => self.to_recipe(),
+        };
+        fs::write(path, text + "\n")?;
+        Ok(())
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        let json = Json::parse(text).map_err(|e| Error::InvalidRecipe(e.to_string()))?;
+        Pipeline::try_from(&json)
+    }
+
+    pub fn to_json(&self) -> String {
+        Json::from(self).to_string()
+    }
+
+    // Reads the subset of YAML shown above: a `steps` list of scalars or
+    // `step`/`key` maps. Other top-level keys are ignored.
+    pub fn from_yaml(text: &str) -> Result<Self> {
+        let mut steps: Vec<(String, Option<String>)> = Vec::new();
+        let mut in_steps = false;
+
+        for (number, line) in text.lines().enumerate() {
+            let line = strip_comment(line);
+            if line.trim().is_empty() {
+                continue;
+            }
+            let bad_line = || Error::InvalidRecipe(format!("line {}: cannot read `{}`", number + 1, line.trim()));
+
+            if !line.starts_with([' ', '-']) {
+                in_steps = line.trim_end() == "steps:";
+                continue;
+            }
+            if !in_steps {
+                continue;
+            }
+
+            let entry = line.trim();
+            if let Some(item) = entry.strip_prefix('-') {
+                let item = item.trim();
+                match item.split_once(": ") {
+                    Some(("step", name)) => steps.push((unquote(name), None)),
+                    Some(_) => return Err(bad_line()),
+                    None => steps.push((unquote(item), None)),
+                }
+            } else if let Some(("key", key)) = entry.split_once(':') {
+                let (_, slot) = steps.last_mut().ok_or_else(bad_line)?;
+                *slot = Some(unquote(key.trim()));
+            } else {
+                return Err(bad_line());
+            }
+        }
+
+        steps.iter().try_fold(Pipeline::new(), |pipeline, (name, key)| {
+            let recipe = match key {
+                Some(key) => format!("{}:{}", name, key),
+                None => name.clone(),
+            };
+            Ok(pipeline.then(Step::from_recipe(&recipe)?))
+        })
+    }
+
+    pub fn to_yaml(&self) -> String {
+        let mut yaml = String::from("steps:");
+        for step in &self.steps {
+            match step.name().split_once(':') {
+                Some((name, key)) => yaml += &format!("\n  - step: {}\n    key: {}", name, quote(key)),
+                None => yaml += &format!("\n  - step: {}", step.name()),
+            }
+        }
+        yaml
+    }
+}
+
+impl From<&Pipeline> for Json {
+    fn from(pipeline: &Pipeline) -> Self {
+        let steps = pipeline
+            .steps
+            .iter()
+            .map(|step| match step.name().split_once(':') {
+                Some((name, key)) => Json::object(vec![("step", name.into()), ("key", key.into())]),
+                None => Json::object(vec![("step", step.name().into())]),
+            })
+            .collect();
+        Json::object(vec![("steps", Json::Array(steps))])
+    }
+}
+
+impl TryFrom<&Json> for Pipeline {
+    type Error = Error;
+
+    fn try_from(json: &Json) -> Result<Self> {
+        let steps = json
+            .get("steps")
+            .and_then(Json::as_array)
+            .ok_or_else(|| Error::InvalidRecipe("expected an object with a `steps` array".to_string()))?;
+
+        steps.iter().try_fold(Pipeline::new(), |pipeline, step| {
+            let recipe = match step {
+                Json::String(recipe) => recipe.clone(),
+                _ => {
+                    let name = step
+                        .get("step")
+                        .and_then(Json::as_str)
+                        .ok_or_else(|| Error::InvalidRecipe(format!("step {} has no `step` name", step)))?;
+                    match step.get("key") {
+                        Some(Json::String(key)) => format!("{}:{}", name, key),
+                        Some(Json::Number(key)) => format!("{}:{}", name, key),
+                        Some(key) => return Err(Error::InvalidRecipe(format!("`{}` key {} is not a string", name, key))),
+                        None => name.to_string(),
+                    }
+                }
+            };
+            Ok(pipeline.then(Step::from_recipe(&recipe)?))
+        })
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase()
+}
+
+// `#` starts a comment unless it's inside quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '#') => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    for q in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(q).and_then(|v| v.strip_suffix(q)) {
+            return inner.to_string();
+        }
+    }
+    value.to_string()
+}
+
+// Keys with YAML-significant characters (`,`, `:`, `#`...) are quoted.
+fn quote(value: &str) -> String {
+    if value.chars().all(|c| c.is_alphanumeric()) {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value.replace('"', "\\\""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classical::AtbashCipher;
+    use crate::registry::Registry;
+
+    fn recipe_error(result: Result<Pipeline>) -> String {
+        match result {
+            Err(Error::InvalidRecipe(message)) => message,
+            Err(other) => panic!("expected an invalid recipe, got {:?}", other),
+            Ok(pipeline) => panic!("expected an error, got `{}`", pipeline.to_recipe()),
+        }
+    }
+
+    // Takes only the key `a:b|c`, to check such keys come through whole.
+    fn install_keyed() {
+        Registry::shared_mut().register_cipher("recipe-test-keyed", "recipe-test-keyed:a:b|c", |params| {
+            match params.key() {
+                "a:b|c" => Ok(Box::new(AtbashCipher::new())),
+                _ => Err(params.error("expected the key `a:b|c`")),
+            }
+        });
+    }
+
+    #[test]
+    fn json_takes_a_list_of_steps() {
+        let json = r#"{"steps": ["base64", {"step": "caesar", "key": 3}, {"step": "affine", "key": "5,8"}]}"#;
+        assert_eq!(Pipeline::from_json(json).unwrap().to_recipe(), "base64 | caesar:3 | affine:5,8");
+    }
+
+    #[test]
+    fn yaml_takes_maps_scalars_and_comments() {
+        let yaml = "\
+# a classroom recipe
+name: homework
+steps:
+  - step: caesar   # the shift
+    key: 3
+  - base64
+  - 'vigenere:LEMON'
+
+other: ignored
+";
+        assert_eq!(Pipeline::from_yaml(yaml).unwrap().to_recipe(), "caesar:3 | base64 | vigenere:LEMON");
+    }
+
+    #[test]
+    fn quoted_keys_keep_colons_and_bars() {
+        install_keyed();
+        let yaml = "steps:\n  - step: recipe-test-keyed\n    key: \"a:b|c\"\n  - \"recipe-test-keyed:a:b|c\"\n";
+        let pipeline = Pipeline::from_yaml(yaml).unwrap();
+        assert_eq!(pipeline.steps()[0].name(), "recipe-test-keyed:a:b|c");
+        assert_eq!(pipeline.steps()[1].name(), "recipe-test-keyed:a:b|c");
+
+        let json = r#"{"steps": [{"step": "recipe-test-keyed", "key": "a:b|c"}]}"#;
+        assert_eq!(Pipeline::from_json(json).unwrap().steps()[0].name(), "recipe-test-keyed:a:b|c");
+    }
+
+    #[test]
+    fn saved_recipes_read_back() {
+        install_keyed();
+        let recipe = "affine:5,8 | base64 | recipe-test-keyed:a:b|c";
+        let steps = ["affine:5,8", "base64", "recipe-test-keyed:a:b|c"].map(|step| Step::from_recipe(step).unwrap());
+        let pipeline = steps.into_iter().fold(Pipeline::new(), Pipeline::then);
+
+        assert_eq!(Pipeline::from_yaml(&pipeline.to_yaml()).unwrap().to_recipe(), recipe);
+        assert_eq!(Pipeline::from_json(&pipeline.to_json()).unwrap().to_recipe(), recipe);
+    }
+
+    #[test]
+    fn malformed_json_is_rejected() {
+        assert!(!recipe_error(Pipeline::from_json(r#"{"steps": ["base64""#)).is_empty());
+        assert!(recipe_error(Pipeline::from_json(r#"["base64"]"#)).contains("`steps` array"));
+        assert!(recipe_error(Pipeline::from_json(r#"{"steps": [{"key": 3}]}"#)).contains("no `step` name"));
+        let listed_key = r#"{"steps": [{"step": "caesar", "key": [3]}]}"#;
+        assert!(recipe_error(Pipeline::from_json(listed_key)).contains("not a string"));
+    }
+
+    #[test]
+    fn malformed_yaml_names_the_line() {
+        assert!(recipe_error(Pipeline::from_yaml("steps:\n  - step: caesar\n    shift: 3\n")).contains("line 3"));
+        assert!(recipe_error(Pipeline::from_yaml("steps:\n    key: 3\n")).contains("line 2"));
+        assert!(recipe_error(Pipeline::from_yaml("steps:\n  - name: caesar\n")).contains("line 2"));
+    }
+
+    #[test]
+    fn unknown_steps_are_rejected() {
+        assert!(recipe_error(Pipeline::from_yaml("steps:\n  - rot99\n")).contains("rot99"));
+        assert!(recipe_error(Pipeline::from_json(r#"{"steps": ["rot99"]}"#)).contains("rot99"));
+        assert!(recipe_error(Pipeline::from_recipe("base64 | rot99")).contains("rot99"));
+        let bad_key = r#"{"steps": [{"step": "affine", "key": "2,3"}]}"#;
+        assert!(matches!(Pipeline::from_json(bad_key), Err(Error::InvalidKey(_))));
+    }
+}
@@ -0,0 +1,161 @@
+// The `cryptan` Python module. Build it with maturin (see pyproject.toml),
+// or `cargo build --features python-ext` and copy the library to
+// `cryptan.so`.
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::analysis;
+use crate::classical::{caesar, CaesarCipher};
+use crate::encoding::{self, Codec};
+
+fn value_error(e: impl std::fmt::Display) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+#[pyfunction]
+fn caesar_encrypt(text: &str, key: i64) -> PyResult<String> {
+    CaesarCipher::new(key).try_encrypt(text).map_err(value_error)
+}
+
+#[pyfunction]
+fn caesar_decrypt(text: &str, key: i64) -> PyResult<String> {
+    CaesarCipher::new(key).try_decrypt(text).map_err(value_error)
+}
+
+// `(key, text, score)` for the `top` most English-looking keys.
+#[pyfunction]
+#[pyo3(signature = (text, top = 5))]
+fn caesar_brute(text: &str, top: usize) -> Vec<(String, String, f64)> {
+    caesar::brute_force(text)
+        .into_iter()
+        .take(top)
+        .map(|result| (result.key, result.text, result.score))
+        .collect()
+}
+
+#[pyclass(name = "Morse", frozen)]
+struct PyMorse {
+    inner: encoding::Morse,
+}
+
+#[pymethods]
+impl PyMorse {
+    #[new]
+    #[pyo3(signature = (lang = "international"))]
+    fn new(lang: &str) -> PyResult<Self> {
+        let inner = encoding::Morse::try_from_lang(lang).map_err(value_error)?;
+        Ok(Self { inner })
+    }
+
+    #[getter]
+    fn lang(&self) -> &'static str {
+        self.inner.lang().name()
+    }
+
+    fn encode(&self, text: &str) -> String {
+        self.inner.encode(text)
+    }
+
+    fn decode(&self, code: &str) -> PyResult<String> {
+        self.inner.try_decode(code).map_err(value_error)
+    }
+}
+
+#[pyfunction]
+fn index_of_coincidence(text: &str) -> f64 {
+    analysis::index_of_coincidence(text)
+}
+
+#[pyfunction]
+fn friedman_estimate(text: &str) -> Option<f64> {
+    analysis::friedman_estimate(text)
+}
+
+// `(length, ic)` pairs, likeliest Vigenère key length first.
+#[pyfunction]
+#[pyo3(signature = (text, max_length = 20))]
+fn key_lengths(text: &str, max_length: usize) -> Vec<(usize, f64)> {
+    analysis::key_lengths(text, max_length).into_iter().map(|k| (k.length, k.ic)).collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, max_length = 20))]
+fn kasiski(text: &str, max_length: usize) -> Vec<(usize, usize)> {
+    analysis::kasiski(text, max_length)
+}
+
+#[pyfunction]
+fn letter_frequencies(text: &str) -> Vec<(char, f64)> {
+    analysis::letter_frequencies(text)
+}
+
+#[pyfunction]
+fn entropy(data: &[u8]) -> f64 {
+    analysis::entropy(data)
+}
+
+#[pyfunction]
+fn chi_squared_english(text: &str) -> f32 {
+    analysis::chi_squared_english(text)
+}
+
+#[pyfunction]
+fn english_score(text: &str) -> f64 {
+    analysis::english_score(text)
+}
+
+#[pyfunction]
+fn meaningful_ratio(text: &str) -> f64 {
+    analysis::meaningful_ratio(text)
+}
+
+// `(kind, confidence)` pairs, most likely first.
+#[pyfunction]
+fn identify(text: &str) -> Vec<(&'static str, f64)> {
+    analysis::CipherIdentifier::new()
+        .identify(text)
+        .into_iter()
+        .map(|found| (found.kind.name(), found.confidence))
+        .collect()
+}
+
+#[pymodule]
+fn cryptan(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(caesar_encrypt, module)?)?;
+    module.add_function(wrap_pyfunction!(caesar_decrypt, module)?)?;
+    module.add_function(wrap_pyfunction!(caesar_brute, module)?)?;
+    module.add_class::<PyMorse>()?;
+    module.add_function(wrap_pyfunction!(index_of_coincidence, module)?)?;
+    module.add_function(wrap_pyfunction!(friedman_estimate, module)?)?;
+    module.add_function(wrap_pyfunction!(key_lengths, module)?)?;
+    module.add_function(wrap_pyfunction!(kasiski, module)?)?;
+    module.add_function(wrap_pyfunction!(letter_frequencies, module)?)?;
+    module.add_function(wrap_pyfunction!(entropy, module)?)?;
+    module.add_function(wrap_pyfunction!(chi_squared_english, module)?)?;
+    module.add_function(wrap_pyfunction!(english_score, module)?)?;
+    module.add_function(wrap_pyfunction!(meaningful_ratio, module)?)?;
+    module.add_function(wrap_pyfunction!(identify, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caesar_brute_puts_the_key_first() {
+        let cipher = caesar_encrypt("the quick brown fox jumps over the lazy dog", 7).unwrap();
+        assert_eq!(cipher, "aol xbpjr iyvdu mve qbtwz vcly aol shgf kvn");
+        let best = &caesar_brute(&cipher, 3)[0];
+        assert_eq!(best.0, "7");
+        assert_eq!(best.1, "the quick brown fox jumps over the lazy dog");
+    }
+
+    #[test]
+    fn morse_takes_a_language() {
+        let morse = PyMorse::new("russian").unwrap();
+        assert_eq!(morse.lang(), "russian");
+        assert_eq!(morse.encode("ПРИВЕТ"), ".--. .-. .. .-- . -");
+        assert!(PyMorse::new("klingon").is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Bytes from the operating system's CSPRNG. Only Unix-like systems are
+// supported; elsewhere this fails with `Unsupported`.
+pub fn os_bytes(len: usize) -> io::Result<Vec<u8>> {
+    if !cfg!(unix) {
+        return Err(io::Error::new(io::ErrorKind::Unsupported, "no system random source on this platform"));
+    }
+
+    let mut bytes = vec![0; len];
+    File::open("/dev/urandom")?.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+// Uniform values below `bound` (at most 256), rejecting the bytes that
+// would bias the result.
+pub fn os_below(bound: u16, len: usize) -> io::Result<Vec<u8>> {
+    let limit = 256 - 256 % bound as usize;
+    let mut values = Vec::with_capacity(len);
+
+    while values.len() < len {
+        let wanted = len - values.len();
+        for byte in os_bytes(wanted + wanted / 4 + 8)? {
+            if (byte as usize) < limit && values.len() < len {
+                values.push((byte as usize % bound as usize) as u8);
+            }
+        }
+    }
+    Ok(values)
+}
+
+// Small seedable generator (SplitMix64) for keys in exercises that need
+// to come out the same every run. Not for real secrets.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn seeded(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    // Seeded from the system's random source, or from the clock where
+    // there is none.
+    pub fn from_os() -> Self {
+        let seed = match os_bytes(8) {
+            Ok(bytes) => u64::from_le_bytes(bytes.try_into().unwrap_or_default()),
+            Err(_) => SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as u64),
+        };
+        Self::seeded(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in 0..bound; `bound` must not be 0.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        let limit = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < limit {
+                return value % bound;
+            }
+        }
+    }
+
+    // Fisher-Yates.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
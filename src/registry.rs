@@ -0,0 +1,538 @@
+use std::str::FromStr;
+use std::sync::{OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::budget::Budget;
+use crate::classical::{
+    brute_force_cipher, AffineCipher, AtbashCipher, AutokeyCipher, BeaufortCipher, BifidCipher, BruteForceOptions,
+    CaesarCipher, ClassicalCipher, ColumnarTransposition, GronsfeldCipher, Keyspace, PlayfairCipher, PortaCipher,
+    RouteCipher, SubstitutionCipher, TrifidCipher, TurningGrille, VigenereCipher,
+};
+use crate::encoding::{
+    Ascii85, BaconVariant, Baconian, Base32, Base58, Base64, BinaryAscii, Braille, CodePage, Codec, DecimalAscii,
+    Ebcdic, FlagSemaphore, Hex, HtmlEntities, KeyboardRemap, LeetSpeak, Morse, Ogham, Pigpen, Rot13, Rot47,
+    SemaphoreStyle, UrlEncoding, Z85,
+};
+use crate::error::{Error, Result};
+use crate::pipeline::Pipeline;
+
+// What a registry entry makes.
+pub enum Transform {
+    Codec(Box<dyn Codec>),
+    Cipher(Box<dyn ClassicalCipher>),
+}
+
+impl Transform {
+    // Encodes or encrypts.
+    pub fn forward(&self, text: &str) -> String {
+        match self {
+            Transform::Codec(codec) => codec.encode(text),
+            Transform::Cipher(cipher) => cipher.encrypt(text),
+        }
+    }
+
+    // Decodes or decrypts.
+    pub fn backward(&self, text: &str) -> String {
+        match self {
+            Transform::Codec(codec) => codec.decode(text),
+            Transform::Cipher(cipher) => cipher.decrypt(text),
+        }
+    }
+
+    pub fn is_cipher(&self) -> bool {
+        matches!(self, Transform::Cipher(_))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Codec,
+    Cipher,
+}
+
+// The part of a spec after the name (`3,rot5` in `caesar:3,rot5`), with
+// parsers whose errors name the whole spec.
+pub struct Params<'a> {
+    spec: &'a str,
+    key: &'a str,
+}
+
+impl<'a> Params<'a> {
+    pub fn spec(&self) -> &'a str {
+        self.spec
+    }
+
+    pub fn key(&self) -> &'a str {
+        self.key
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.key.is_empty()
+    }
+
+    // The key split at commas, trimmed.
+    pub fn parts(&self) -> Vec<&'a str> {
+        self.key.split(',').map(str::trim).collect()
+    }
+
+    // Exactly `N` comma-separated parts, or an error showing `usage`.
+    pub fn exactly<const N: usize>(&self, usage: &str) -> Result<[&'a str; N]> {
+        self.parts().try_into().map_err(|_| self.error(&format!("expected `{}`", usage)))
+    }
+
+    pub fn number<T: FromStr>(&self, value: &str) -> Result<T> {
+        value.trim().parse().map_err(|_| self.error(&format!("`{}` is not a number", value.trim())))
+    }
+
+    pub fn error(&self, problem: &str) -> Error {
+        Error::InvalidRecipe(format!("`{}`: {}", self.spec, problem))
+    }
+}
+
+type Factory = Box<dyn Fn(&Params) -> Result<Transform> + Send + Sync>;
+type Solver = Box<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+pub struct Entry {
+    name: String,
+    usage: String,
+    kind: Kind,
+    factory: Factory,
+    // For the auto-solver: whether text looks like this codec's output,
+    // and the likeliest key of this cipher for a text. The built-in
+    // entries leave these to `Magic` and the solver's own brute forces.
+    detect: Option<fn(&str) -> bool>,
+    solver: Option<Solver>,
+}
+
+impl Entry {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // How a spec for this entry is written, e.g. `affine:a,b`.
+    pub fn usage(&self) -> &str {
+        &self.usage
+    }
+
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    // Lets `Pipeline::auto_solve` try decoding text that `detect` accepts.
+    pub fn detect_with(&mut self, detect: fn(&str) -> bool) -> &mut Self {
+        self.detect = Some(detect);
+        self
+    }
+
+    // Lets `Pipeline::auto_solve` try this cipher with the key `solver`
+    // finds for a text, written as the spec takes it after the colon.
+    pub fn solve_with<F>(&mut self, solver: F) -> &mut Self
+    where
+        F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+    {
+        self.solver = Some(Box::new(solver));
+        self
+    }
+
+    pub fn detects(&self, text: &str) -> bool {
+        self.detect.is_some_and(|detect| detect(text))
+    }
+
+    // The spec the solver suggests for `text`, e.g. `mycipher:7`.
+    pub fn solve(&self, text: &str) -> Option<String> {
+        let key = self.solver.as_ref()?(text)?;
+        Some(format!("{}:{}", self.name, key))
+    }
+}
+
+// Adds a downstream crate's ciphers and codecs to a registry, e.g. the
+// ones a class uses: `registry::install(&ClassroomCiphers)` once at
+// startup, and recipes, the CLI and the auto-solver all know them.
+pub trait Plugin {
+    fn register(&self, registry: &mut Registry);
+}
+
+static SHARED: OnceLock<RwLock<Registry>> = OnceLock::new();
+
+fn shared_lock() -> &'static RwLock<Registry> {
+    SHARED.get_or_init(|| RwLock::new(Registry::builtin()))
+}
+
+// Adds `plugin` to `Registry::shared`.
+pub fn install(plugin: &dyn Plugin) {
+    plugin.register(&mut Registry::shared_mut());
+}
+
+type MakeCodec = fn() -> Box<dyn Codec>;
+
+// The codecs that take no key, by name; `CODEC_NAMES` lists them for users.
+const PLAIN_CODECS: &[(&str, MakeCodec)] = &[
+    ("base64", || Box::new(Base64::new())),
+    ("base64url", || Box::new(Base64::url_safe())),
+    ("base32", || Box::new(Base32::new())),
+    ("base58", || Box::new(Base58)),
+    ("ascii85", || Box::new(Ascii85::new())),
+    ("z85", || Box::new(Z85)),
+    ("hex", || Box::new(Hex::new())),
+    ("binary", || Box::new(BinaryAscii)),
+    ("decimal", || Box::new(DecimalAscii)),
+    ("rot13", || Box::new(Rot13)),
+    ("rot47", || Box::new(Rot47)),
+    ("bacon", || Box::new(Baconian::new(BaconVariant::Classic24))),
+    ("bacon26", || Box::new(Baconian::new(BaconVariant::Full26))),
+    ("cp037", || Box::new(Ebcdic::new(CodePage::Cp037))),
+    ("cp500", || Box::new(Ebcdic::new(CodePage::Cp500))),
+    ("ogham", || Box::new(Ogham::new())),
+    ("url", || Box::new(UrlEncoding::new())),
+    ("url-form", || Box::new(UrlEncoding::form())),
+    ("html", || Box::new(HtmlEntities::new())),
+    ("html-numeric", || Box::new(HtmlEntities::numeric())),
+    ("braille", || Box::new(Braille)),
+    ("semaphore", || Box::new(FlagSemaphore::new())),
+    ("semaphore-arrows", || Box::new(FlagSemaphore::new().with_style(SemaphoreStyle::Arrows))),
+    ("pigpen", || Box::new(Pigpen)),
+    ("leet", || Box::new(LeetSpeak::new())),
+];
+
+// Other names of built-in entries.
+const ALIASES: &[(&str, &str)] =
+    &[("base85", "ascii85"), ("ebcdic", "cp037"), ("percent", "url"), ("html-entities", "html")];
+
+// The keyboard remaps listed among the codecs; any other pair of layouts
+// works too.
+const KEYBOARD_REMAPS: &[&str] = &["qwerty-dvorak", "qwerty-azerty", "dvorak-azerty"];
+
+// `<from>-<to>`, e.g. `qwerty-dvorak`.
+fn keyboard_remap(name: &str) -> Option<Box<dyn Codec>> {
+    let (from, to) = name.split_once('-')?;
+    Some(Box::new(KeyboardRemap::new(from.parse().ok()?, to.parse().ok()?)))
+}
+
+// Codecs and ciphers by name, made from specs like `base64`, `caesar:3` or
+// `route:4,5,spiral`: the name, then the key after a colon. Recipes, the
+// auto-solver and the CLI all go through `Registry::shared`.
+#[derive(Default)]
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Registry {
+    // No entries.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Every codec in `CODEC_NAMES` and every cipher that fits a recipe.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+
+        for &(name, make) in PLAIN_CODECS {
+            registry.register_codec(name, name, move |params| {
+                if !params.is_empty() {
+                    return Err(params.error("this codec takes no key"));
+                }
+                Ok(make())
+            });
+        }
+        for name in KEYBOARD_REMAPS {
+            registry.register_codec(name, name, move |params| {
+                if !params.is_empty() {
+                    return Err(params.error("this codec takes no key"));
+                }
+                keyboard_remap(name).ok_or_else(|| params.error("unknown keyboard layout"))
+            });
+        }
+        // `morse:russian` picks the alphabet.
+        registry.register_codec("morse", "morse[:language]", |params| match params.key() {
+            "" => Ok(Box::new(Morse::new())),
+            lang => Ok(Box::new(Morse::try_from_lang(lang).map_err(|e| params.error(&e.to_string()))?)),
+        });
+
+        // `caesar:3,rot5` also turns digits.
+        registry.register_cipher("caesar", "caesar:key[,rot5]", |params| {
+            let (shift, rot5) = match params.key().split_once(',') {
+                Some((shift, "rot5")) => (shift, true),
+                Some(_) => return Err(params.error("expected `caesar:key` or `caesar:key,rot5`")),
+                None => (params.key(), false),
+            };
+            Ok(Box::new(CaesarCipher::new(params.number(shift)?).with_rotated_digits(rot5)))
+        });
+        registry.register_cipher("rot18", "rot18", |_| Ok(Box::new(CaesarCipher::rot18())));
+        registry.register_cipher("affine", "affine:a,b", |params| {
+            let [a, b] = params.exactly("affine:a,b")?;
+            Ok(Box::new(AffineCipher::try_new(params.number(a)?, params.number(b)?)?))
+        });
+        registry.register_cipher("atbash", "atbash", |_| Ok(Box::new(AtbashCipher::new())));
+        registry.register_cipher("vigenere", "vigenere:keyword", |params| {
+            Ok(Box::new(VigenereCipher::try_new(params.key())?))
+        });
+        registry.register_cipher("beaufort", "beaufort:keyword", |params| {
+            Ok(Box::new(BeaufortCipher::try_new(params.key())?))
+        });
+        registry.register_cipher("autokey", "autokey:keyword", |params| {
+            Ok(Box::new(AutokeyCipher::try_new(params.key())?))
+        });
+        registry.register_cipher("porta", "porta:keyword", |params| Ok(Box::new(PortaCipher::try_new(params.key())?)));
+        registry.register_cipher("gronsfeld", "gronsfeld:digits", |params| {
+            Ok(Box::new(GronsfeldCipher::try_new(params.key())?))
+        });
+        registry.register_cipher("playfair", "playfair:keyword", |params| {
+            Ok(Box::new(PlayfairCipher::new(params.key())))
+        });
+        // A keyword, then optionally the period: `bifid:KEYWORD,5`.
+        registry.register_cipher("bifid", "bifid:keyword[,period]", |params| {
+            let (keyword, period) = keyword_and_period(params)?;
+            Ok(Box::new(BifidCipher::new(keyword).with_period(period)))
+        });
+        registry.register_cipher("trifid", "trifid:keyword[,period]", |params| {
+            let (keyword, period) = keyword_and_period(params)?;
+            Ok(Box::new(TrifidCipher::new(keyword).with_period(period)))
+        });
+        registry.register_cipher("substitution", "substitution:alphabet", |params| {
+            Ok(Box::new(SubstitutionCipher::try_new(params.key())?))
+        });
+        // A column order (`2,0,1`) or a keyword.
+        registry.register_cipher("columnar", "columnar:keyword|order", |params| {
+            if params.key().contains(',') {
+                let order = params.parts().into_iter().map(|i| params.number(i)).collect::<Result<_>>()?;
+                Ok(Box::new(ColumnarTransposition::try_from_order(order)?))
+            } else {
+                Ok(Box::new(ColumnarTransposition::try_from_keyword(params.key())?))
+            }
+        });
+        // The grille's mask: `grille:X.../X..X/..X./....`.
+        registry.register_cipher("grille", "grille:mask", |params| {
+            Ok(Box::new(TurningGrille::try_from_mask(params.key())?))
+        });
+        // Rows, columns and the route: `route:4,5,spiral`.
+        registry.register_cipher("route", "route:rows,columns,route", |params| {
+            let [rows, columns, route] = params.exactly("route:rows,columns,route")?;
+            let route = route.parse().map_err(|e: String| params.error(&e))?;
+            Ok(Box::new(RouteCipher::try_new(params.number(rows)?, params.number(columns)?, route)?))
+        });
+
+        registry
+    }
+
+    // The built-in registry plus whatever has been installed into it.
+    // Don't hold on to it while installing.
+    pub fn shared() -> RwLockReadGuard<'static, Registry> {
+        shared_lock().read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn shared_mut() -> RwLockWriteGuard<'static, Registry> {
+        shared_lock().write().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn with_plugin(mut self, plugin: &dyn Plugin) -> Self {
+        plugin.register(&mut self);
+        self
+    }
+
+    // Adds a cipher, replacing any entry of the same name. `usage` shows
+    // how its spec is written.
+    pub fn register_cipher<F>(&mut self, name: &str, usage: &str, factory: F) -> &mut Entry
+    where
+        F: Fn(&Params) -> Result<Box<dyn ClassicalCipher>> + Send + Sync + 'static,
+    {
+        self.insert(name, usage, Kind::Cipher, Box::new(move |params| factory(params).map(Transform::Cipher)))
+    }
+
+    // Like `register_cipher`, with a solver that brute forces `C`'s
+    // keyspace; `Keyspace::key_label` must give keys `factory` reads.
+    pub fn register_keyspace_cipher<C, F>(&mut self, name: &str, usage: &str, factory: F) -> &mut Entry
+    where
+        C: ClassicalCipher + Keyspace + 'static,
+        F: Fn(&Params) -> Result<C> + Send + Sync + 'static,
+    {
+        self.register_cipher(name, usage, move |params| Ok(Box::new(factory(params)?)))
+            .solve_with(|text| {
+                let options = BruteForceOptions::default().with_max_results(1);
+                let results = brute_force_cipher::<C>(text, options.scorer().as_ref(), &options, &Budget::unlimited());
+                results.into_iter().next().map(|best| best.key)
+            })
+    }
+
+    // A recipe under a name of its own, used as one cipher step: a
+    // classroom cipher made of built-in steps. The recipe is read each time
+    // the name is used, so check it with `Pipeline::from_recipe` first.
+    // Names already taken are refused, so a recipe can't end up using
+    // itself.
+    pub fn register_recipe(&mut self, name: &str, recipe: &str) -> Result<&mut Entry> {
+        if self.get(name).is_some() {
+            return Err(Error::InvalidRecipe(format!("`{}` is already defined", name.trim())));
+        }
+        let recipe = recipe.to_string();
+        Ok(self.register_cipher(name.trim(), name.trim(), move |_| Ok(Box::new(Pipeline::from_recipe(&recipe)?))))
+    }
+
+    pub fn register_codec<F>(&mut self, name: &str, usage: &str, factory: F) -> &mut Entry
+    where
+        F: Fn(&Params) -> Result<Box<dyn Codec>> + Send + Sync + 'static,
+    {
+        self.insert(name, usage, Kind::Codec, Box::new(move |params| factory(params).map(Transform::Codec)))
+    }
+
+    fn insert(&mut self, name: &str, usage: &str, kind: Kind, factory: Factory) -> &mut Entry {
+        let name = name.to_ascii_lowercase();
+        self.entries.retain(|entry| entry.name != name);
+        let entry = Entry { name, usage: usage.to_string(), kind, factory, detect: None, solver: None };
+        self.entries.push(entry);
+        self.entries.last_mut().expect("just pushed")
+    }
+
+    // Built-in aliases (`base85`, `percent`...) find their entries too.
+    pub fn get(&self, name: &str) -> Option<&Entry> {
+        let find = |name: &str| self.entries.iter().find(|entry| entry.name.eq_ignore_ascii_case(name));
+        find(name.trim()).or_else(|| {
+            let (_, canonical) = ALIASES.iter().find(|(alias, _)| alias.eq_ignore_ascii_case(name.trim()))?;
+            find(canonical)
+        })
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.iter().map(Entry::name).collect()
+    }
+
+    // Makes the transform `spec` describes. Names the registry lacks are
+    // tried as keyboard remaps (`azerty-qwerty`).
+    pub fn create(&self, spec: &str) -> Result<Transform> {
+        let spec = spec.trim();
+        let (name, key) = spec.split_once(':').unwrap_or((spec, ""));
+        let params = Params { spec, key: key.trim() };
+
+        match self.get(name) {
+            Some(entry) => (entry.factory)(&params),
+            None => keyboard_remap(&spec.to_ascii_lowercase())
+                .map(Transform::Codec)
+                .ok_or_else(|| params.error("unknown codec or cipher")),
+        }
+    }
+
+    pub fn create_cipher(&self, spec: &str) -> Result<Box<dyn ClassicalCipher>> {
+        match self.create(spec)? {
+            Transform::Cipher(cipher) => Ok(cipher),
+            Transform::Codec(_) => Err(Error::InvalidRecipe(format!("`{}` is a codec, not a cipher", spec.trim()))),
+        }
+    }
+
+    pub fn create_codec(&self, spec: &str) -> Result<Box<dyn Codec>> {
+        match self.create(spec)? {
+            Transform::Codec(codec) => Ok(codec),
+            Transform::Cipher(_) => Err(Error::InvalidRecipe(format!("`{}` is a cipher, not a codec", spec.trim()))),
+        }
+    }
+}
+
+fn keyword_and_period<'a>(params: &Params<'a>) -> Result<(&'a str, usize)> {
+    match params.key().split_once(',') {
+        Some((keyword, period)) => Ok((keyword, params.number(period)?)),
+        None => Ok((params.key(), 0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::CODEC_NAMES;
+
+    fn forward(spec: &str, text: &str) -> String {
+        Registry::builtin().create(spec).unwrap().forward(text)
+    }
+
+    fn recipe_error(spec: &str) -> String {
+        match Registry::builtin().create(spec) {
+            Err(Error::InvalidRecipe(message)) => message,
+            Err(other) => panic!("{}: expected an invalid recipe, got {:?}", spec, other),
+            Ok(_) => panic!("{}: expected an error", spec),
+        }
+    }
+
+    // Doubles a Caesar key.
+    struct Doubled;
+
+    impl Plugin for Doubled {
+        fn register(&self, registry: &mut Registry) {
+            registry.register_cipher("doubled", "doubled:key", |params| {
+                Ok(Box::new(CaesarCipher::new(params.number::<i64>(params.key())? * 2)))
+            });
+        }
+    }
+
+    #[test]
+    fn specs_take_their_keys_after_the_colon() {
+        assert_eq!(forward("caesar:3", "abc"), "def");
+        assert_eq!(forward(" Caesar : 3 ", "abc"), "def");
+        assert_eq!(forward("caesar:3,rot5", "a1"), "d6");
+        assert_eq!(forward("affine:5,8", "abc"), "ins");
+        assert_eq!(forward("affine: 5 , 8", "abc"), "ins");
+        assert_eq!(forward("route:2,3,columns", "abcdef"), "adbecf");
+        assert_eq!(forward("morse:russian", "Я"), ".-.-");
+        assert_eq!(forward("base85", "hi"), forward("ascii85", "hi"));
+        assert_eq!(forward("azerty-qwerty", "a"), "q");
+    }
+
+    #[test]
+    fn bad_keys_name_the_spec() {
+        assert_eq!(recipe_error("caesar:x"), "`caesar:x`: `x` is not a number");
+        assert_eq!(recipe_error("caesar:3,rot6"), "`caesar:3,rot6`: expected `caesar:key` or `caesar:key,rot5`");
+        assert_eq!(recipe_error("affine:5"), "`affine:5`: expected `affine:a,b`");
+        assert_eq!(recipe_error("affine:5,8,1"), "`affine:5,8,1`: expected `affine:a,b`");
+        assert_eq!(recipe_error("base64:3"), "`base64:3`: this codec takes no key");
+        assert_eq!(recipe_error("rot99"), "`rot99`: unknown codec or cipher");
+        assert!(matches!(Registry::builtin().create("affine:2,3"), Err(Error::InvalidKey(_))));
+        assert!(matches!(Registry::builtin().create("vigenere:"), Err(Error::InvalidKey(_))));
+    }
+
+    #[test]
+    fn ciphers_and_codecs_stay_apart() {
+        let registry = Registry::builtin();
+        assert!(registry.create_cipher("caesar:3").is_ok());
+        assert!(registry.create_codec("caesar:3").is_err());
+        assert!(registry.create_codec("hex").is_ok());
+        assert!(registry.create_cipher("hex").is_err());
+    }
+
+    #[test]
+    fn entries_cover_every_codec_name() {
+        let registry = Registry::builtin();
+        for name in CODEC_NAMES {
+            assert_eq!(registry.get(name).map(Entry::kind), Some(Kind::Codec), "{}", name);
+            assert!(registry.create_codec(name).is_ok(), "{}", name);
+        }
+        assert_eq!(registry.get("EBCDIC").map(Entry::name), Some("cp037"));
+        assert_eq!(registry.get("affine").map(Entry::usage), Some("affine:a,b"));
+        assert!(registry.get("rot99").is_none());
+    }
+
+    #[test]
+    fn plugins_add_entries() {
+        let mut registry = Registry::builtin().with_plugin(&Doubled);
+        assert_eq!(registry.create("doubled:2").unwrap().forward("abc"), "efg");
+        assert_eq!(registry.get("doubled").unwrap().solve("anything"), None);
+
+        let entry = registry.entries.iter_mut().find(|entry| entry.name() == "doubled").unwrap();
+        entry.solve_with(|_| Some("1".to_string()));
+        assert_eq!(registry.get("doubled").unwrap().solve("anything").as_deref(), Some("doubled:1"));
+        assert!(registry.create("doubled:x").is_err());
+        assert!(Registry::builtin().get("doubled").is_none());
+    }
+
+    #[test]
+    fn install_adds_to_the_shared_registry() {
+        install(&Doubled);
+        assert_eq!(Registry::shared().create("doubled:1").unwrap().backward("cde"), "abc");
+    }
+
+    #[test]
+    fn recipes_cannot_take_a_used_name() {
+        let mut registry = Registry::builtin();
+        registry.register_recipe("class", "caesar:3 | atbash").unwrap();
+        assert_eq!(registry.create("class").unwrap().forward("a"), "w");
+        assert!(registry.register_recipe("caesar", "atbash").is_err());
+        assert!(registry.register_recipe("class", "atbash").is_err());
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::analysis::words::words;
+use crate::analysis::{Language, NgramScorer, Scorer, Wordlist};
+use crate::diagnostics::Warning;
+use crate::error::{Error, Result};
+use crate::json::Json;
+
+const BOLD: &str = "\x1b[1m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+// How many unrecognised words an `Explanation` lists.
+const UNMATCHED_SHOWN: usize = 5;
+
+// Letters at which a confidence is about two-thirds of what the text's
+// evidence alone would give; short texts read as English by chance.
+const CONFIDENCE_LETTERS: f64 = 12.0;
+
+// A candidate plaintext and the key that gave it. `{}` prints it plain and
+// `{:#}` with terminal colours.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedResult {
+    pub key: String,
+    pub text: String,
+    pub score: f64,
+    // Input that was skipped or patched up while producing `text`.
+    pub warnings: Vec<Warning>,
+    // `Scorer::name` of what produced `score`, when known.
+    pub scorer: Option<String>,
+    // The language `text` was found to be in, from scorers that try
+    // several.
+    pub language: Option<Language>,
+}
+
+impl DecodedResult {
+    pub fn new(key: impl Into<String>, text: impl Into<String>, score: f64) -> Self {
+        Self { key: key.into(), text: text.into(), score, warnings: Vec::new(), scorer: None, language: None }
+    }
+
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    pub fn with_scorer(mut self, scorer: impl Into<String>) -> Self {
+        self.scorer = Some(scorer.into());
+        self
+    }
+
+    pub fn with_warnings(mut self, warnings: Vec<Warning>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    // What `{}` prints; never contains terminal escapes, whatever the
+    // output is going to.
+    pub fn plain_string(&self) -> String {
+        self.to_string()
+    }
+
+    // How much `text` reads like its language, English unless `language`
+    // says otherwise, whichever scorer ranked it. Scores from different
+    // scorers don't compare; confidences do.
+    pub fn explain(&self) -> Explanation {
+        let wordlist = Wordlist::for_language(self.language.unwrap_or_default());
+        let mut total = 0;
+        let mut unmatched: HashMap<String, usize> = HashMap::new();
+        for word in words(&self.text) {
+            total += 1;
+            if !wordlist.contains(&word) {
+                *unmatched.entry(word).or_default() += 1;
+            }
+        }
+        let missing: usize = unmatched.values().sum();
+        let mut unmatched: Vec<(String, usize)> = unmatched.into_iter().collect();
+        unmatched.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+
+        // Dictionary words only count once there are a few of them, as in
+        // `english_score`; quadgrams work with or without spaces.
+        let words_evidence = if total >= 2 { wordlist.meaningful_ratio(&self.text) } else { 0.0 };
+        let evidence = words_evidence.max(f64::from(NgramScorer.score(&self.text)));
+        let letters = self.text.chars().filter(|c| c.is_alphabetic()).count() as f64;
+        let reliability = 1.0 - (-letters / CONFIDENCE_LETTERS).exp();
+
+        Explanation {
+            scorer: self.scorer.clone(),
+            words: total,
+            recognized_words: total - missing,
+            unmatched: unmatched.into_iter().take(UNMATCHED_SHOWN).map(|(word, _)| word).collect(),
+            confidence: (evidence * reliability * 100.0).round() as u8,
+        }
+    }
+}
+
+// Why a result looks like plaintext or doesn't, for deciding whether to
+// trust it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub scorer: Option<String>,
+    pub words: usize,
+    // Words in the language's word list.
+    pub recognized_words: usize,
+    // The commonest of the other words, most frequent first.
+    pub unmatched: Vec<String>,
+    // 0 to 100, from dictionary words and quadgrams, discounted for short
+    // texts.
+    pub confidence: u8,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "SCORER: {}", self.scorer.as_deref().unwrap_or("unknown"))?;
+        writeln!(f, "CONFIDENCE: {}%", self.confidence)?;
+        write!(f, "WORDS: {} of {} recognized", self.recognized_words, self.words)?;
+        if !self.unmatched.is_empty() {
+            write!(f, "\nUNMATCHED: {}", self.unmatched.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&Explanation> for Json {
+    fn from(explanation: &Explanation) -> Self {
+        Json::object(vec![
+            ("scorer", explanation.scorer.as_deref().map_or(Json::Null, Json::from)),
+            ("confidence", (explanation.confidence as usize).into()),
+            ("words", explanation.words.into()),
+            ("recognized_words", explanation.recognized_words.into()),
+            ("unmatched", Json::Array(explanation.unmatched.iter().map(|word| word.as_str().into()).collect())),
+        ])
+    }
+}
+
+impl fmt::Display for DecodedResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (bold, green, reset) = if f.alternate() { (BOLD, GREEN, RESET) } else { ("", "", "") };
+        write!(f, "{b}KEY:{r} {}\n{b}SCORE:{r} {:.2}\n", self.key, self.score, b = bold, r = reset)?;
+        if let Some(language) = self.language {
+            writeln!(f, "{b}LANGUAGE:{r} {}", language.code(), b = bold, r = reset)?;
+        }
+        write!(f, "{b}DECODED TEXT:{r} {g}{}{r}", self.text, b = bold, g = green, r = reset)
+    }
+}
+
+impl From<&DecodedResult> for Json {
+    fn from(result: &DecodedResult) -> Self {
+        let mut fields = vec![
+            ("key", result.key.as_str().into()),
+            ("text", result.text.as_str().into()),
+            ("score", result.score.into()),
+            ("warnings", Json::Array(result.warnings.iter().map(Json::from).collect())),
+        ];
+        if let Some(scorer) = &result.scorer {
+            fields.push(("scorer", scorer.as_str().into()));
+        }
+        if let Some(language) = result.language {
+            fields.push(("language", language.code().into()));
+        }
+        Json::object(fields)
+    }
+}
+
+// `warnings`, `scorer` and `language` may be left out.
+impl TryFrom<&Json> for DecodedResult {
+    type Error = Error;
+
+    fn try_from(json: &Json) -> Result<Self> {
+        let warnings = match json.get("warnings") {
+            Some(Json::Array(warnings)) => warnings.iter().map(Warning::try_from).collect::<Result<_>>()?,
+            Some(_) => return Err(Error::InvalidJson("expected `warnings` to be an array".to_string())),
+            None => Vec::new(),
+        };
+        let mut result = DecodedResult::new(json.str_field("key")?, json.str_field("text")?, json.f64_field("score")?);
+        result.scorer = json.get("scorer").and_then(Json::as_str).map(str::to_string);
+        result.language = match json.get("language").and_then(Json::as_str) {
+            Some(code) => Some(code.parse().map_err(Error::InvalidJson)?),
+            None => None,
+        };
+        Ok(result.with_warnings(warnings))
+    }
+}
+
+// Highest score first.
+pub fn rank(results: &mut [DecodedResult]) {
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+}
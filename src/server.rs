@@ -0,0 +1,169 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use crate::api;
+use crate::diagnostics::{Diagnostics, Warning};
+use crate::json::Json;
+
+pub const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+// Largest request body we are willing to buffer.
+const MAX_BODY: usize = 1 << 20;
+
+// Largest request line and headers together.
+const MAX_HEAD: usize = 8 << 10;
+
+// How long a client may take over each read or write before the
+// connection is dropped, so a slow one can't hold its thread for good.
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Serves `POST /<method>` with a JSON object body, e.g. `POST /encrypt`
+// with `{"key": 3, "text": "hello"}`. Each connection gets its own thread.
+// Connections that fail before they start don't stop the server; they go
+// to `diagnostics`, if given.
+pub fn run(addr: &str, diagnostics: Option<&Diagnostics>) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                thread::spawn(move || {
+                    let _ = handle_connection(stream);
+                });
+            }
+            Err(e) => {
+                if let Some(diagnostics) = diagnostics {
+                    diagnostics.warn(Warning::InvalidInput(format!("connection failed: {}", e)));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_connection(stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let Some((request_line, content_length)) = read_head(&mut reader)? else {
+        return respond(stream, 431, &error_body("request headers too large"));
+    };
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("");
+
+    let (status, body) = if content_length > MAX_BODY {
+        (413, error_body("request body too large"))
+    } else {
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+        route(method, path, &String::from_utf8_lossy(&body))
+    };
+
+    respond(stream, status, &body)
+}
+
+// The request line and Content-Length, or None if the head runs past
+// `MAX_HEAD` bytes.
+fn read_head(reader: &mut impl BufRead) -> io::Result<Option<(String, usize)>> {
+    let mut head = reader.take(MAX_HEAD as u64);
+    let mut request_line = String::new();
+    head.read_line(&mut request_line)?;
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if head.read_line(&mut header)? == 0 {
+            if head.limit() == 0 {
+                return Ok(None);
+            }
+            break;
+        }
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    Ok(Some((request_line, content_length)))
+}
+
+fn route(method: &str, path: &str, body: &str) -> (u16, Json) {
+    let name = path.trim_start_matches('/');
+
+    if method == "GET" && (name.is_empty() || name == "methods") {
+        let methods = api::METHODS.iter().map(|m| Json::from(*m)).collect();
+        return (200, Json::object(vec![("methods", Json::Array(methods))]));
+    }
+    if method != "POST" {
+        return (405, error_body("use POST with a JSON body"));
+    }
+    if !api::METHODS.contains(&name) {
+        return (404, error_body(&format!("no endpoint `{}`", path)));
+    }
+
+    let params = match Json::parse(body) {
+        Ok(params) => params,
+        Err(e) => return (400, error_body(&e.to_string())),
+    };
+
+    match api::dispatch(name, &params) {
+        Ok(result) => (200, result),
+        Err(message) => (422, error_body(&message)),
+    }
+}
+
+fn error_body(message: &str) -> Json {
+    Json::object(vec![("error", message.into())])
+}
+
+fn respond(mut stream: TcpStream, status: u16, body: &Json) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        _ => "Unprocessable Entity",
+    };
+    let body = body.to_string();
+
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_head() {
+        let request = "POST /encrypt HTTP/1.1\r\nHost: x\r\nContent-Length: 12\r\n\r\n{\"text\": 1}";
+        let (line, length) = read_head(&mut request.as_bytes()).unwrap().unwrap();
+        assert_eq!((line.trim_end(), length), ("POST /encrypt HTTP/1.1", 12));
+    }
+
+    #[test]
+    fn refuses_endless_headers() {
+        let request = format!("GET / HTTP/1.1\r\nX-Padding: {}\r\n\r\n", "a".repeat(MAX_HEAD));
+        assert!(read_head(&mut request.as_bytes()).unwrap().is_none());
+        let request = format!("GET / HTTP/1.1\r\n{}", "X-Header: a\r\n".repeat(MAX_HEAD));
+        assert!(read_head(&mut request.as_bytes()).unwrap().is_none());
+    }
+}
@@ -0,0 +1,333 @@
+// Seeded random inputs and property checks for ciphers and codecs: round
+// trips, brute forces finding the key, and decoders surviving garbage. For
+// checking this crate's transforms and anyone's own `ClassicalCipher` or
+// `Codec`; a failure carries the input, so it can be replayed.
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::budget::Budget;
+use crate::classical::{brute_force_cipher, BruteForceOptions, ClassicalCipher, Keyspace};
+use crate::encoding::Codec;
+use crate::random::Rng;
+
+// Common English words, so brute force checks have text a scorer can rank.
+const WORDS: &[&str] = &[
+    "the", "of", "and", "to", "in", "is", "that", "it", "was", "for", "on", "are", "with", "as", "they", "be", "at",
+    "one", "have", "this", "from", "by", "word", "but", "what", "some", "we", "can", "out", "other", "were", "all",
+    "there", "when", "up", "use", "your", "how", "said", "each", "which", "their", "time", "will", "way", "about",
+    "many", "then", "them", "would", "write", "like", "so", "these", "her", "long", "make", "thing", "see", "him",
+    "two", "has", "look", "more", "day", "could", "come", "did", "number", "sound", "most", "people", "over", "know",
+    "water", "than", "call", "first", "who", "may", "down", "side", "been", "now", "find", "morning", "harbour",
+];
+
+// Characters beyond ASCII that transforms commonly trip over: accents,
+// other scripts, combining marks, emoji.
+const UNICODE_SAMPLES: &[char] = &['é', 'ß', 'Ω', 'ж', '中', 'ا', '\u{301}', '😀', '·', '—', '\u{a0}'];
+
+// Random text and bytes from a seed.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    rng: Rng,
+}
+
+impl Generator {
+    pub fn seeded(seed: u64) -> Self {
+        Self { rng: Rng::seeded(seed) }
+    }
+
+    pub fn rng(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    // Up to `max_len` uppercase A-Z.
+    pub fn letters(&mut self, max_len: usize) -> String {
+        let len = self.len(max_len);
+        (0..len).map(|_| (b'A' + self.rng.below(26) as u8) as char).collect()
+    }
+
+    // `words` common English words, lowercase, separated by spaces.
+    pub fn sentence(&mut self, words: usize) -> String {
+        let words: Vec<&str> = (0..words).map(|_| WORDS[self.rng.below(WORDS.len() as u64) as usize]).collect();
+        words.join(" ")
+    }
+
+    // Up to `max_len` printable ASCII characters, space included.
+    pub fn ascii(&mut self, max_len: usize) -> String {
+        let len = self.len(max_len);
+        (0..len).map(|_| (b' ' + self.rng.below(95) as u8) as char).collect()
+    }
+
+    // Like `ascii`, with about one character in four from beyond ASCII.
+    pub fn unicode(&mut self, max_len: usize) -> String {
+        let len = self.len(max_len);
+        (0..len)
+            .map(|_| match self.rng.below(4) {
+                0 => UNICODE_SAMPLES[self.rng.below(UNICODE_SAMPLES.len() as u64) as usize],
+                _ => (b' ' + self.rng.below(95) as u8) as char,
+            })
+            .collect()
+    }
+
+    // Up to `max_len` characters drawn from `alphabet`, e.g. ".- /" to
+    // throw near-Morse at a Morse decoder.
+    pub fn from_alphabet(&mut self, alphabet: &str, max_len: usize) -> String {
+        let chars: Vec<char> = alphabet.chars().collect();
+        if chars.is_empty() {
+            return String::new();
+        }
+        let len = self.len(max_len);
+        (0..len).map(|_| chars[self.rng.below(chars.len() as u64) as usize]).collect()
+    }
+
+    pub fn bytes(&mut self, max_len: usize) -> Vec<u8> {
+        let len = self.len(max_len);
+        (0..len).map(|_| self.rng.next_u64() as u8).collect()
+    }
+
+    fn len(&mut self, max_len: usize) -> usize {
+        self.rng.below(max_len as u64 + 1) as usize
+    }
+}
+
+// A property that didn't hold, and the input that broke it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub property: &'static str,
+    pub input: String,
+    pub detail: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} failed for {:?}: {}", self.property, self.input, self.detail)
+    }
+}
+
+impl std::error::Error for Failure {}
+
+fn failure(property: &'static str, input: &str, detail: String) -> Failure {
+    Failure { property, input: input.to_string(), detail }
+}
+
+// A panic as a failure rather than an unwinding test run.
+fn guard<T>(property: &'static str, input: &str, f: impl FnOnce() -> T) -> Result<T, Failure> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        failure(property, input, format!("panicked: {}", message))
+    })
+}
+
+// `decrypt(encrypt(x)) == x` for every input. Pick inputs the cipher keeps
+// whole: Playfair, say, drops J and pads, so only letters without J and
+// doubled letters round-trip.
+pub fn check_cipher_round_trip<I>(cipher: &dyn ClassicalCipher, inputs: I) -> Result<(), Failure>
+where
+    I: IntoIterator<Item = String>,
+{
+    for input in inputs {
+        let decrypted = guard("cipher round trip", &input, || cipher.decrypt(&cipher.encrypt(&input)))?;
+        if decrypted != input {
+            return Err(failure("cipher round trip", &input, format!("came back as {:?}", decrypted)));
+        }
+    }
+    Ok(())
+}
+
+// `decode(encode(x)) == x` for every input.
+pub fn check_codec_round_trip<I>(codec: &dyn Codec, inputs: I) -> Result<(), Failure>
+where
+    I: IntoIterator<Item = String>,
+{
+    for input in inputs {
+        let decoded = guard("codec round trip", &input, || codec.decode(&codec.encode(&input)))?;
+        if decoded != input {
+            return Err(failure("codec round trip", &input, format!("came back as {:?}", decoded)));
+        }
+    }
+    Ok(())
+}
+
+// Encrypts `plain` with `key` and checks that brute forcing `C`'s keyspace
+// lists `key` among its `top` best. `plain` wants to be English enough
+// for the default scorer, e.g. `Generator::sentence`.
+pub fn check_brute_force_finds_key<C>(key: &C, plain: &str, top: usize) -> Result<(), Failure>
+where
+    C: ClassicalCipher + Keyspace,
+{
+    let cipher = key.encrypt(plain);
+    let options = BruteForceOptions::default().with_max_results(top);
+    let results = guard("brute force finds the key", plain, || {
+        brute_force_cipher::<C>(&cipher, options.scorer().as_ref(), &options, &Budget::unlimited())
+    })?;
+
+    let label = key.key_label();
+    if results.iter().any(|result| result.key == label || result.text == plain) {
+        return Ok(());
+    }
+    let found: Vec<&str> = results.iter().map(|result| result.key.as_str()).collect();
+    Err(failure("brute force finds the key", plain, format!("key {} not in the top {}: {:?}", label, top, found)))
+}
+
+// Throws `iterations` generated inputs at the codec's decoders: none may
+// panic, and when `try_decode` accepts an input, `decode` must agree with
+// it. `input` makes each one, e.g. `|g| g.unicode(64)`.
+pub fn fuzz_decoder(
+    codec: &dyn Codec,
+    generator: &mut Generator,
+    iterations: usize,
+    mut input: impl FnMut(&mut Generator) -> String,
+) -> Result<(), Failure> {
+    for _ in 0..iterations {
+        let text = input(generator);
+        let lenient = guard("decoding never panics", &text, || codec.decode(&text))?;
+        let strict = guard("decoding never panics", &text, || codec.try_decode(&text))?;
+        if let Ok(strict) = strict {
+            if strict != lenient {
+                let detail = format!("try_decode gave {:?} but decode gave {:?}", strict, lenient);
+                return Err(failure("strict and lenient decoding agree", &text, detail));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classical::{AffineCipher, CaesarCipher, Route, RouteCipher};
+    use crate::registry::{Kind, Registry, Transform};
+
+    const CASES: usize = 40;
+
+    // A key for each built-in cipher, and text it keeps whole. New ciphers
+    // have to be added here.
+    fn cipher_case(name: &str) -> (&'static str, fn(&mut Generator) -> String) {
+        match name {
+            "caesar" => ("caesar:3,rot5", |g| g.unicode(40)),
+            "rot18" => ("rot18", |g| g.unicode(40)),
+            "affine" => ("affine:5,8", |g| g.unicode(40)),
+            "atbash" => ("atbash", |g| g.unicode(40)),
+            "vigenere" => ("vigenere:LEMON", |g| g.unicode(40)),
+            "beaufort" => ("beaufort:FORTIFICATION", |g| g.unicode(40)),
+            "autokey" => ("autokey:QUEENLY", |g| g.unicode(40)),
+            "porta" => ("porta:KEY", |g| g.unicode(40)),
+            "gronsfeld" => ("gronsfeld:31415", |g| g.unicode(40)),
+            "substitution" => ("substitution:QWERTYUIOPASDFGHJKLZXCVBNM", |g| g.unicode(40)),
+            "columnar" => ("columnar:ZEBRAS", |g| g.unicode(40)),
+            "grille" => ("grille:X.../X..X/..X./....", |g| g.unicode(40)),
+            "route" => ("route:4,5,spiral", |g| g.unicode(40)),
+            // Letters only, I stands in for J, and X and Q pad.
+            "playfair" => ("playfair:MONARCHY", |g| g.from_alphabet("ABCDEFGHIKLMNOPRSTUVWYZ", 40)),
+            "bifid" => ("bifid:KEYWORD,5", |g| g.from_alphabet("ABCDEFGHIKLMNOPQRSTUVWXYZ", 40)),
+            "trifid" => ("trifid:FELIX,5", |g| g.letters(40)),
+            other => panic!("no test case for cipher `{}`", other),
+        }
+    }
+
+    // Text each built-in codec keeps whole. New codecs have to be added.
+    fn codec_input(name: &str) -> fn(&mut Generator) -> String {
+        match name {
+            "base64" | "base64url" | "base32" | "base58" | "ascii85" | "z85" | "hex" | "binary" | "decimal"
+            | "rot13" | "rot47" | "url" | "url-form" | "html" | "html-numeric" | "braille" | "qwerty-dvorak"
+            | "qwerty-azerty" | "dvorak-azerty" => |g| g.unicode(40),
+            "cp037" | "cp500" => |g| g.ascii(40),
+            // Upper case only.
+            "morse" | "semaphore" | "semaphore-arrows" | "pigpen" => |g| g.sentence(5).to_uppercase(),
+            "bacon26" => |g| g.letters(40),
+            // Classic Bacon shares I/J and U/V.
+            "bacon" => |g| g.from_alphabet("ABCDEFGHIKLMNOPQRSTUWXYZ", 40),
+            // No J, K, V, W, X or Y in Ogham.
+            "ogham" => |g| g.from_alphabet("ABCDEFGHILMNOPQRSTUZ ", 40),
+            // `1` reads back as i, not l, and `\/\/` as w, not vv.
+            "leet" => |g| g.from_alphabet("abcdefghijkmnopqrstuwxyz ", 40),
+            other => panic!("no test case for codec `{}`", other),
+        }
+    }
+
+    fn entries(kind: Kind) -> Vec<String> {
+        let registry = Registry::builtin();
+        registry.entries().iter().filter(|entry| entry.kind() == kind).map(|entry| entry.name().to_string()).collect()
+    }
+
+    #[test]
+    fn every_registry_cipher_round_trips() {
+        for name in entries(Kind::Cipher) {
+            let (spec, input) = cipher_case(&name);
+            let cipher = Registry::builtin().create_cipher(spec).unwrap();
+            let mut generator = Generator::seeded(599);
+            let inputs: Vec<String> = (0..CASES).map(|_| input(&mut generator)).collect();
+            if let Err(failure) = check_cipher_round_trip(cipher.as_ref(), inputs) {
+                panic!("{}: {}", spec, failure);
+            }
+        }
+    }
+
+    #[test]
+    fn every_registry_codec_round_trips() {
+        for name in entries(Kind::Codec) {
+            let input = codec_input(&name);
+            let codec = Registry::builtin().create_codec(&name).unwrap();
+            let mut generator = Generator::seeded(599);
+            let inputs: Vec<String> = (0..CASES).map(|_| input(&mut generator)).collect();
+            if let Err(failure) = check_codec_round_trip(codec.as_ref(), inputs) {
+                panic!("{}: {}", name, failure);
+            }
+        }
+    }
+
+    // Garbage, and garbage made of the codec's own symbols.
+    #[test]
+    fn every_registry_codec_survives_fuzzing() {
+        for name in entries(Kind::Codec) {
+            let codec = Registry::builtin().create_codec(&name).unwrap();
+            let mut generator = Generator::seeded(599);
+            let symbols = codec.encode(&generator.sentence(12).to_uppercase());
+            let checks = [
+                fuzz_decoder(codec.as_ref(), &mut generator, 200, |g| g.unicode(64)),
+                fuzz_decoder(codec.as_ref(), &mut generator, 200, |g| g.from_alphabet(&symbols, 64)),
+            ];
+            for check in checks {
+                if let Err(failure) = check {
+                    panic!("{}: {}", name, failure);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn brute_force_finds_keys() {
+        let mut generator = Generator::seeded(599);
+        let plain = generator.sentence(12);
+        check_brute_force_finds_key(&CaesarCipher::new(11), &plain, 1).unwrap();
+        check_brute_force_finds_key(&AffineCipher::try_new(7, 3).unwrap(), &plain, 1).unwrap();
+        let route = RouteCipher::try_new(6, 11, Route::Spiral).unwrap();
+        check_brute_force_finds_key(&route, &plain[..60.min(plain.len())], 3).unwrap();
+    }
+
+    #[test]
+    fn lossy_round_trips_are_reported() {
+        let playfair = Registry::builtin().create_cipher("playfair:MONARCHY").unwrap();
+        let failure = check_cipher_round_trip(playfair.as_ref(), ["JUMP".to_string()]).unwrap_err();
+        assert_eq!(failure.property, "cipher round trip");
+        assert_eq!(failure.input, "JUMP");
+    }
+
+    #[test]
+    fn generators_are_repeatable() {
+        let (mut a, mut b) = (Generator::seeded(1), Generator::seeded(1));
+        assert_eq!(a.unicode(100), b.unicode(100));
+        assert_eq!(a.bytes(100), b.bytes(100));
+    }
+
+    #[test]
+    fn registry_creates_ciphers_from_specs() {
+        let caesar = Registry::builtin().create("caesar:1").unwrap();
+        assert!(matches!(caesar, Transform::Cipher(_)));
+        assert_eq!(caesar.forward("abc"), "bcd");
+    }
+}